@@ -6,15 +6,33 @@ use glium::glutin::{dpi::LogicalPosition, event::Event};
 
 use nalgebra::Vector2;
 
+pub mod callback;
 pub mod desktop;
+pub mod layout;
+pub mod menu;
+pub mod tooltip;
 mod utils;
 
+pub use callback::{Callback, Mailbox};
 pub use desktop::window::Window;
-pub use utils::Plane;
+pub use layout::{Align, Grid, GridCell, Insets, Item, Justify, Stack, Track};
+pub use menu::{Menu, MenuBar, MenuEntry, MenuItem};
+pub use utils::Dropdown;
+pub use utils::Icon;
+pub use utils::ScrollView;
+pub use utils::TabView;
 pub use utils::Text;
+pub use utils::{BoxShadow, Plane};
+pub use utils::{Button, ButtonState, ButtonStyle};
+pub use utils::{Checkbox, RadioGroup, Switch};
+pub use utils::{Image, ImageFit};
+pub use utils::{ListRow, ListSource, ListView, SelectionMode};
+pub use utils::{ProgressBar, Spinner};
+pub use utils::{Slider, SpinBox};
+pub use utils::{TreeProvider, TreeView};
 
 /// Logical component layout
-#[derive(Clone, Copy, Default, Debug)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
 pub struct Layout {
     pub position: Vector2<f32>,
     pub size: Vector2<f32>,
@@ -28,6 +46,34 @@ impl Layout {
             && self.position.y <= y
             && y <= self.position.y + self.size.y
     }
+
+    /// Smallest layout containing both layouts
+    pub fn union(&self, other: &Layout) -> Layout {
+        let min = self.position.inf(&other.position);
+        let max = (self.position + self.size).sup(&(other.position + other.size));
+        Layout {
+            position: min,
+            size: max - min,
+        }
+    }
+
+    /// Overlapping area of both layouts, with zero size if they are disjoint
+    pub fn intersection(&self, other: &Layout) -> Layout {
+        let min = self.position.sup(&other.position);
+        let max = (self.position + self.size).inf(&(other.position + other.size));
+        let size = (max - min).sup(&Vector2::zeros());
+        Layout {
+            position: min,
+            size,
+        }
+    }
+}
+
+/// Axis along which a widget lays out or moves its contents
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
 }
 
 #[allow(unused_variables)]
@@ -37,4 +83,9 @@ pub trait Component {
     fn update(&mut self, global: &Global) {}
 
     fn set_layout(&mut self, _layout: Layout) {}
+
+    /// Size the component needs for its contents, for containers to lay it out with
+    fn intrinsic_size(&self) -> Vector2<f32> {
+        Vector2::zeros()
+    }
 }
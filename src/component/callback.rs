@@ -0,0 +1,89 @@
+use crate::Global;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+type Handler<T> = Box<dyn FnMut(T, &Global)>;
+
+/// Handler a widget invokes with a value, e.g. `()` on click or the new state on toggle
+pub struct Callback<T> {
+    handler: Option<Handler<T>>,
+}
+
+impl<T> Callback<T> {
+    pub fn new() -> Self {
+        Callback { handler: None }
+    }
+
+    pub fn set(&mut self, handler: impl FnMut(T, &Global) + 'static) {
+        self.handler = Some(Box::new(handler));
+    }
+
+    /// Posts the message built from the value to `mailbox` whenever invoked
+    pub fn post<M: 'static>(&mut self, mailbox: &Mailbox<M>, message: impl Fn(T) -> M + 'static) {
+        let mailbox = mailbox.clone();
+        self.set(move |value, _| mailbox.post(message(value)));
+    }
+
+    pub fn clear(&mut self) {
+        self.handler = None;
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.handler.is_some()
+    }
+
+    pub fn call(&mut self, value: T, global: &Global) {
+        if let Some(handler) = &mut self.handler {
+            handler(value, global);
+        }
+    }
+}
+
+impl<T> Default for Callback<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Queue of app-defined messages that widgets post to and the owning application drains,
+/// typically right after forwarding an event to its widgets
+pub struct Mailbox<M> {
+    messages: Rc<RefCell<VecDeque<M>>>,
+}
+
+impl<M> Mailbox<M> {
+    pub fn new() -> Self {
+        Mailbox {
+            messages: Rc::new(RefCell::new(VecDeque::new())),
+        }
+    }
+
+    pub fn post(&self, message: M) {
+        self.messages.borrow_mut().push_back(message);
+    }
+
+    pub fn pop(&self) -> Option<M> {
+        self.messages.borrow_mut().pop_front()
+    }
+
+    /// Takes every pending message, oldest first
+    pub fn drain(&self) -> Vec<M> {
+        self.messages.borrow_mut().drain(..).collect()
+    }
+}
+
+impl<M> Clone for Mailbox<M> {
+    fn clone(&self) -> Self {
+        Mailbox {
+            messages: Rc::clone(&self.messages),
+        }
+    }
+}
+
+impl<M> Default for Mailbox<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
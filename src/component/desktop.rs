@@ -1,10 +1,12 @@
-use crate::component::Component;
+use crate::component::menu::MenuOverlay;
+use crate::component::tooltip::TooltipOverlay;
+use crate::component::{Component, Image, Layout};
 use crate::custom_event::CustomEvent;
 use crate::Global;
 use crate::RenderContextProxy;
 
-use glium::glutin::event::Event;
-use glium::Surface;
+use glium::glutin::event::{ElementState, Event, KeyboardInput, WindowEvent};
+use nalgebra::{Vector2, Vector4};
 
 pub mod window;
 
@@ -16,6 +18,10 @@ use window_manager::WindowManager;
 
 pub struct Desktop {
     window_manager: WindowManager,
+    menus: MenuOverlay,
+    tooltips: TooltipOverlay,
+    wallpaper: Option<Image>,
+    background: Vector4<f32>,
 }
 
 impl Desktop {
@@ -23,23 +29,123 @@ impl Desktop {
         let mut window_manager = WindowManager::new();
         let sample = Sample::new(global);
         window_manager.spawn(sample, global);
-        Desktop { window_manager }
+        Desktop {
+            window_manager,
+            menus: MenuOverlay::new(global),
+            tooltips: TooltipOverlay::new(global),
+            wallpaper: None,
+            background: global.theme().palette.desktop_background,
+        }
+    }
+
+    /// Reloads the wallpaper named by the config, keeping the current one if it fails to load
+    fn apply_config(&mut self, global: &Global) {
+        let config = global.config();
+        match &config.background.wallpaper {
+            Some(path) => match Image::from_path(path, global) {
+                Ok(wallpaper) => self.set_wallpaper(Some(wallpaper), global),
+                Err(err) => eprintln!("failed to load wallpaper {}: {}", path.display(), err),
+            },
+            None => self.set_wallpaper(None, global),
+        }
+    }
+
+    fn handle_key(&mut self, input: &KeyboardInput, global: &Global) {
+        let key = match input {
+            KeyboardInput {
+                state: ElementState::Pressed,
+                virtual_keycode: Some(key),
+                ..
+            } => *key,
+            _ => return,
+        };
+        let bindings = &global.config().keybindings;
+        let modifiers = global.modifiers();
+        if bindings.close_window.matches(key, modifiers) {
+            if let Some(id) = self.window_manager.active() {
+                self.window_manager.close(id, global);
+            }
+        } else if bindings.next_window.matches(key, modifiers) {
+            self.window_manager.activate_next(global);
+        } else if bindings.spawn_window.matches(key, modifiers) {
+            self.window_manager.spawn(Sample::new(global), global);
+        }
+    }
+
+    /// Opens and closes the menus components asked for through `Global::open_menu`
+    fn handle_menu_requests(&mut self, global: &Global) {
+        for request in global.take_menu_requests() {
+            self.menus.handle_request(request, global);
+        }
+    }
+
+    /// Image drawn over the background color, laid out to cover the whole screen
+    pub fn set_wallpaper(&mut self, wallpaper: Option<Image>, global: &Global) {
+        self.wallpaper = wallpaper;
+        global.request_redraw();
     }
 }
 
 impl Component for Desktop {
     fn draw(&self, proxy: &mut RenderContextProxy) {
-        let frame = proxy.frame();
-        frame.clear_color(0.8, 0.9, 1.0, 1.0);
+        proxy.clear(self.background);
+        if let Some(wallpaper) = &self.wallpaper {
+            wallpaper.draw(proxy);
+        }
 
         self.window_manager.draw(proxy);
+        self.menus.draw(proxy);
+        self.tooltips.draw(proxy);
     }
 
     fn update(&mut self, global: &Global) {
+        if let Some(wallpaper) = &mut self.wallpaper {
+            let size = global.render_context.frame_buffer_size();
+            let scale_factor = global.render_context.scale_factor() as f32;
+            wallpaper.set_layout(Layout {
+                position: Vector2::zeros(),
+                size: Vector2::new(size.width as f32, size.height as f32) / scale_factor,
+            });
+            wallpaper.update(global);
+        }
         self.window_manager.update(global);
+        self.handle_menu_requests(global);
+        self.menus.update(global);
+        self.tooltips.update(global);
     }
 
     fn handle_event(&mut self, event: &Event<'_, CustomEvent>, global: &Global) {
-        self.window_manager.handle_event(event, global);
+        // components register tooltips afresh on every move
+        let cursor_moved = matches!(
+            event,
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { .. },
+                ..
+            }
+        );
+        if cursor_moved {
+            global.replace_tooltip(None);
+        }
+        self.tooltips.handle_event(event, global);
+
+        // an open menu takes input before the windows below it
+        if !self.menus.handle_event(event, global) {
+            match event {
+                Event::UserEvent(CustomEvent::ThemeChanged) => {
+                    self.background = global.theme().palette.desktop_background;
+                }
+                Event::UserEvent(CustomEvent::ConfigChanged) => self.apply_config(global),
+                Event::WindowEvent {
+                    event: WindowEvent::KeyboardInput { input, .. },
+                    ..
+                } => self.handle_key(input, global),
+                _ => {}
+            }
+            self.window_manager.handle_event(event, global);
+        }
+        self.handle_menu_requests(global);
+        if cursor_moved {
+            self.tooltips.hover(global.replace_tooltip(None), global);
+        }
     }
 }
@@ -1,7 +1,12 @@
-use crate::component::Layout;
+use crate::component::{Layout, MenuBar};
 use crate::Component;
+use crate::Global;
 
+use nalgebra::Vector2;
+
+mod dialog;
 mod sample;
+pub use dialog::Dialog;
 pub use sample::Sample;
 
 pub struct AppInfo {
@@ -9,6 +14,30 @@ pub struct AppInfo {
     pub layout: Layout,
 }
 
+/// Contents of a window; its widgets report back through `Callback`s, typically posting to a
+/// `Mailbox` the application drains at the end of `handle_event`
 pub trait Application: Component + 'static {
     fn get_app_info(&self) -> &AppInfo;
+
+    /// Menu bar the window shows under its title
+    fn menu_bar(&self) -> Option<&MenuBar> {
+        None
+    }
+
+    fn menu_bar_mut(&mut self) -> Option<&mut MenuBar> {
+        None
+    }
+
+    /// Size the window opens with, instead of the configured one
+    fn preferred_size(&self, _global: &Global) -> Option<Vector2<f32>> {
+        None
+    }
+
+    /// Whether the app is done and its window should close, checked after each update
+    fn should_close(&self) -> bool {
+        false
+    }
+
+    /// Called as the window closes, whether the app asked for it or the user closed it
+    fn on_close(&mut self, _global: &Global) {}
 }
@@ -0,0 +1,278 @@
+use super::{AppInfo, Application};
+use crate::component::utils::{Button, Input};
+use crate::component::{Callback, Insets, Item, Justify, Layout, Mailbox, Stack, Text};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+
+use glium::glutin::event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent};
+use nalgebra::Vector2;
+
+/// Stock dialog asking a question, opened with `Global::open_dialog`. Whatever way it closes,
+/// `on_result` is invoked once with the answer, and `CustomEvent::DialogClosed` follows
+pub struct Dialog<T> {
+    app_info: AppInfo,
+    /// Invoked with the answer as the dialog closes
+    pub on_result: Callback<T>,
+
+    message: Text,
+    input: Option<Input>,
+    ok: Button,
+    cancel: Option<Button>,
+    /// Whether OK was chosen, once the user answered
+    answer: Option<bool>,
+    /// Turns the choice and the text entered into the result
+    resolve: fn(bool, &str) -> T,
+    mailbox: Mailbox<bool>,
+    line_height: f32,
+}
+
+impl Dialog<()> {
+    /// Shows `message` with an OK button
+    pub fn message(title: &str, message: &str, global: &Global) -> Self {
+        Self::new(title, message, false, false, |_, _| (), global)
+    }
+}
+
+impl Dialog<bool> {
+    /// Asks to confirm `message`, answering whether OK was chosen
+    pub fn confirm(title: &str, message: &str, global: &Global) -> Self {
+        Self::new(title, message, false, true, |ok, _| ok, global)
+    }
+}
+
+impl Dialog<Option<String>> {
+    /// Asks for a line of text starting out as `text`, answering `None` if cancelled
+    pub fn prompt(title: &str, message: &str, text: &str, global: &Global) -> Self {
+        let mut dialog = Self::new(
+            title,
+            message,
+            true,
+            true,
+            |ok, text| if ok { Some(text.to_owned()) } else { None },
+            global,
+        );
+        if let Some(input) = &mut dialog.input {
+            input.text.content = text.to_owned();
+        }
+        dialog
+    }
+}
+
+impl<T: 'static> Dialog<T> {
+    const WIDTH: f32 = 260.0;
+    const PADDING: f32 = 12.0;
+    const BUTTON_WIDTH: f32 = 72.0;
+    const BUTTON_SPACING: f32 = 8.0;
+
+    fn new(
+        title: &str,
+        message: &str,
+        has_input: bool,
+        has_cancel: bool,
+        resolve: fn(bool, &str) -> T,
+        global: &Global,
+    ) -> Self {
+        let mailbox = Mailbox::new();
+        let mut text = Text::new(global);
+        text.content = message.to_owned();
+
+        let input = if has_input {
+            let mut input = Input::new(global);
+            input.set_focused(true, global);
+            Some(input)
+        } else {
+            None
+        };
+
+        let mut ok = Button::new(global);
+        ok.text.content = "OK".to_owned();
+        ok.on_click.post(&mailbox, |()| true);
+        let cancel = if has_cancel {
+            let mut cancel = Button::new(global);
+            cancel.text.content = "Cancel".to_owned();
+            cancel.on_click.post(&mailbox, |()| false);
+            Some(cancel)
+        } else {
+            None
+        };
+
+        let mut dialog = Dialog {
+            app_info: AppInfo {
+                title: title.to_owned(),
+                layout: Layout::default(),
+            },
+            on_result: Callback::new(),
+            message: text,
+            input,
+            ok,
+            cancel,
+            answer: None,
+            resolve,
+            mailbox,
+            line_height: 0.0,
+        };
+        dialog.apply_theme(global);
+        dialog
+    }
+
+    fn apply_theme(&mut self, global: &Global) {
+        let theme = global.theme();
+        self.message.set_font_size(theme.typography.body_size);
+        self.message.color = theme.palette.text;
+        self.line_height = theme.typography.body_size * 1.25;
+    }
+
+    /// Message, input and row of buttons, for contents `width` wide. The message takes any
+    /// extra height, keeping the buttons at the bottom
+    fn stack(&self, width: f32) -> Stack {
+        let mut stack = Stack::vertical();
+        stack.padding = Insets::all(Self::PADDING);
+        stack.spacing = Self::PADDING;
+        let message = self.message.measure(Some(width - Self::PADDING * 2.0));
+        stack.push(Item {
+            grow: 1.0,
+            ..Item::sized(Vector2::new(0.0, message.y.ceil()))
+        });
+        if self.input.is_some() {
+            stack.push(Item::sized(Vector2::new(0.0, self.line_height + 8.0)));
+        }
+        stack.push(Item::sized(self.button_row().intrinsic_size()));
+        stack
+    }
+
+    /// Buttons lined up on the right, OK first
+    fn button_row(&self) -> Stack {
+        let mut row = Stack::horizontal();
+        row.spacing = Self::BUTTON_SPACING;
+        row.justify = Justify::End;
+        for button in std::iter::once(&self.ok).chain(&self.cancel) {
+            row.push(Item {
+                min: Vector2::new(Self::BUTTON_WIDTH, 0.0),
+                ..Item::sized(button.intrinsic_size())
+            });
+        }
+        row
+    }
+
+    fn answer(&mut self, ok: bool) {
+        if self.answer.is_none() {
+            self.answer = Some(ok);
+        }
+    }
+
+    fn handle_key(&mut self, input: &KeyboardInput) {
+        if input.state != ElementState::Pressed {
+            return;
+        }
+        match input.virtual_keycode {
+            // a focused button takes Enter itself
+            Some(VirtualKeyCode::Return)
+                if !self.ok.is_focused()
+                    && !self.cancel.as_ref().is_some_and(Button::is_focused) =>
+            {
+                self.answer(true)
+            }
+            Some(VirtualKeyCode::Escape) => self.answer(false),
+            _ => {}
+        }
+    }
+
+    fn buttons_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut Button> {
+        std::iter::once(&mut self.ok).chain(self.cancel.as_mut())
+    }
+}
+
+impl<T: 'static> Component for Dialog<T> {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        self.message.draw(proxy);
+        if let Some(input) = &self.input {
+            input.draw(proxy);
+        }
+        self.ok.draw(proxy);
+        if let Some(cancel) = &self.cancel {
+            cancel.draw(proxy);
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        self.message.update(global);
+        if let Some(input) = &mut self.input {
+            input.update(global);
+        }
+        for button in self.buttons_mut() {
+            button.update(global);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.apply_theme(global);
+                self.set_layout(self.app_info.layout);
+            }
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { input, .. },
+                ..
+            } => self.handle_key(input),
+            _ => {}
+        }
+        self.message.handle_event(event, global);
+        if let Some(input) = &mut self.input {
+            input.handle_event(event, global);
+        }
+        for button in self.buttons_mut() {
+            button.handle_event(event, global);
+        }
+        for ok in self.mailbox.drain() {
+            self.answer(ok);
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.app_info.layout = layout;
+        let layouts = self.stack(layout.size.x).layout(layout);
+        self.message.set_layout(layouts[0]);
+        if let Some(input) = &mut self.input {
+            input.set_layout(layouts[1]);
+        }
+        let row = match layouts.last() {
+            Some(&row) => self.button_row().layout(row),
+            None => return,
+        };
+        for (button, layout) in self.buttons_mut().zip(row) {
+            button.set_layout(layout);
+        }
+    }
+}
+
+impl<T: 'static> Application for Dialog<T> {
+    fn get_app_info(&self) -> &AppInfo {
+        &self.app_info
+    }
+
+    fn preferred_size(&self, global: &Global) -> Option<Vector2<f32>> {
+        let theme = global.theme();
+        let frame = theme.window_frame_width;
+        let height = self.stack(Self::WIDTH - frame * 2.0).intrinsic_size().y;
+        Some(Vector2::new(
+            Self::WIDTH,
+            height + theme.title_height + frame * 3.0,
+        ))
+    }
+
+    fn should_close(&self) -> bool {
+        self.answer.is_some()
+    }
+
+    fn on_close(&mut self, global: &Global) {
+        let ok = self.answer.take().unwrap_or(false);
+        let text = self
+            .input
+            .as_ref()
+            .map_or(String::new(), |input| input.text.content.clone());
+        let result = (self.resolve)(ok, &text);
+        self.on_result.call(result, global);
+    }
+}
@@ -1,22 +1,29 @@
-use nalgebra::Vector4;
-
 use crate::component::utils::{Button, Input};
-use crate::component::Layout;
+use crate::component::{Item, Layout, Mailbox, Menu, MenuBar, Stack};
+use crate::config::KeyBinding;
 use crate::custom_event::CustomEvent;
 use crate::Global;
 use crate::RenderContextProxy;
 
-use super::{AppInfo, Application};
+use super::{AppInfo, Application, Dialog};
 use crate::Component;
 
-use glium::glutin::event::Event;
+use glium::glutin::event::{
+    ElementState, Event, ModifiersState, MouseButton, VirtualKeyCode, WindowEvent,
+};
 
-use nalgebra::Vector2;
+enum Message {
+    ClearInput,
+    AskInsert,
+    Insert(Option<String>),
+}
 
 pub struct Sample {
     app_info: AppInfo,
     input: Input,
     button: Button,
+    menu_bar: MenuBar,
+    mailbox: Mailbox<Message>,
 }
 
 impl Sample {
@@ -26,22 +33,50 @@ impl Sample {
             title,
             layout: Layout::default(),
         };
-        let mut input = Input::new(global);
-        input.text.set_font_size(16.0);
-        input.text.color = Vector4::new(0.1, 0.1, 0.1, 1.0);
-        input.background.color = Vector4::new(1.0, 1.0, 1.0, 1.0);
-        input.background.round_radius = 2.0;
+        let input = Input::new(global);
+        let mailbox = Mailbox::new();
 
         let mut button = Button::new(global);
-        button.set_font_size(16.0);
-        button.text.color = Vector4::new(0.0, 0.4, 0.4, 1.0);
-        button.text.content = "push!!!!!!!!!!!!!!!!".to_owned();
-        button.round_radius = 2.0;
+        button.text.content = "clear".to_owned();
+        button.tooltip = Some("Empty the text field".to_owned());
+        button.on_click.post(&mailbox, |()| Message::ClearInput);
+
+        let mut menu_bar = MenuBar::new(global);
+        menu_bar.add_menu("Edit", Self::edit_menu(&mailbox), global);
 
         Sample {
             app_info,
             input,
             button,
+            menu_bar,
+            mailbox,
+        }
+    }
+
+    fn edit_menu(mailbox: &Mailbox<Message>) -> Menu {
+        let mut menu = Menu::new();
+        let clear = menu.add_item("Clear");
+        clear.accelerator = Some(KeyBinding::new(ModifiersState::CTRL, VirtualKeyCode::L));
+        clear.on_activate.post(mailbox, |()| Message::ClearInput);
+        menu.add_item("Insert text...")
+            .on_activate
+            .post(mailbox, |()| Message::AskInsert);
+        menu
+    }
+
+    fn handle_message(&mut self, message: Message, global: &Global) {
+        match message {
+            Message::ClearInput => self.input.text.content.clear(),
+            Message::AskInsert => {
+                let mut dialog = Dialog::prompt("Insert", "Text to insert:", "", global);
+                dialog.on_result.post(&self.mailbox, Message::Insert);
+                global.open_dialog(dialog);
+            }
+            Message::Insert(text) => {
+                if let Some(text) = text {
+                    self.input.text.content.push_str(&text);
+                }
+            }
         }
     }
 }
@@ -54,24 +89,40 @@ impl Component for Sample {
 
     fn set_layout(&mut self, layout: Layout) {
         self.app_info.layout = layout;
-        let input_ratio = 0.9;
-        let input_height = layout.size.y * input_ratio;
-        let button_height = layout.size.y - input_height;
-        let input_layout = Layout {
-            position: layout.position,
-            size: Vector2::new(layout.size.x, input_height),
-        };
-        let button_layout = Layout {
-            position: Vector2::new(layout.position.x, layout.position.y + input_height),
-            size: Vector2::new(layout.size.x, button_height),
-        };
-        self.input.set_layout(input_layout);
-        self.button.set_layout(button_layout);
+        // the input takes whatever the button leaves
+        let mut stack = Stack::vertical();
+        stack.push(Item::flexible(1.0));
+        stack.push(Item::sized(self.button.intrinsic_size()));
+        let layouts = stack.layout(layout);
+        self.input.set_layout(layouts[0]);
+        self.button.set_layout(layouts[1]);
     }
 
     fn handle_event(&mut self, event: &Event<'_, CustomEvent>, global: &Global) {
+        if let Event::WindowEvent {
+            event:
+                WindowEvent::MouseInput {
+                    state: ElementState::Pressed,
+                    button: MouseButton::Right,
+                    ..
+                },
+            ..
+        } = event
+        {
+            if self
+                .input
+                .background
+                .layout
+                .contains(&global.cursor_position())
+            {
+                global.open_context_menu(Self::edit_menu(&self.mailbox));
+            }
+        }
         self.input.handle_event(event, global);
         self.button.handle_event(event, global);
+        for message in self.mailbox.drain() {
+            self.handle_message(message, global);
+        }
     }
 
     fn update(&mut self, global: &Global) {
@@ -84,4 +135,12 @@ impl Application for Sample {
     fn get_app_info(&self) -> &AppInfo {
         &self.app_info
     }
+
+    fn menu_bar(&self) -> Option<&MenuBar> {
+        Some(&self.menu_bar)
+    }
+
+    fn menu_bar_mut(&mut self) -> Option<&mut MenuBar> {
+        Some(&mut self.menu_bar)
+    }
 }
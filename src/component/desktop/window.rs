@@ -2,17 +2,21 @@ use nalgebra::Vector2;
 use nalgebra::Vector4;
 
 use super::{application::Application, window_manager::WindowId};
-use crate::component::{Layout, Plane, Text};
-use crate::custom_event::EventProxy;
+use crate::component::{Insets, Item, Layout, MenuBar, Plane, Stack, Text};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::{Composite, Shadow};
 use crate::Component;
 use crate::Global;
 use crate::RenderContextProxy;
+use crate::Theme;
 
 use glium::glutin::{
     dpi::LogicalPosition,
     event::{ElementState, Event, WindowEvent},
 };
 
+use std::cell::Cell;
+
 pub struct Window {
     id: WindowId,
     app: Box<dyn Application>,
@@ -20,25 +24,29 @@ pub struct Window {
     background: Plane,
     layout: Layout,
     dragging_state: Option<LogicalPosition<f64>>,
+    pub opacity: f32,
+    active: bool,
+    contents_dirty: Cell<bool>,
+    title_height: f32,
+    frame_width: f32,
+    shadow_color: Vector4<f32>,
 }
 
 impl Window {
-    const TITLE_HEIGHT: f32 = 16.0;
-    const FRAME_WIDTH: f32 = 5.0;
+    const SHADOW_OFFSET: f32 = 4.0;
+    const SHADOW_BLUR_RADIUS: f32 = 8.0;
+    const INACTIVE_DIM: f32 = 0.25;
 
-    pub fn new(id: WindowId, app: impl Application, global: &Global) -> Self {
+    pub fn new(id: WindowId, app: Box<dyn Application>, global: &Global) -> Self {
         let app_info = app.get_app_info();
 
-        let mut title_text = Text::new(&global);
-        title_text.set_font_size(Self::TITLE_HEIGHT);
-        title_text.color = Vector4::new(0.4, 0.7, 0.9, 1.0);
+        let mut title_text = Text::new(global);
         title_text.content = app_info.title.clone();
+        let size = app
+            .preferred_size(global)
+            .unwrap_or_else(|| global.config().window.size());
 
-        let app = Box::new(app);
-
-        let mut background = Plane::new(global);
-        background.color = Vector4::new(0.2, 0.2, 0.2, 1.0);
-        background.round_radius = 3.0;
+        let background = Plane::new(global);
 
         let mut window = Window {
             id,
@@ -47,20 +55,136 @@ impl Window {
             title_text,
             layout: Layout::default(),
             dragging_state: None,
+            opacity: 1.0,
+            active: true,
+            contents_dirty: Cell::new(true),
+            title_height: 0.0,
+            frame_width: 0.0,
+            shadow_color: Vector4::zeros(),
         };
+        window.apply_theme(&global.theme());
         window.set_layout(Layout {
             position: Vector2::new(50.0, 50.0),
-            size: Vector2::new(180.0, 240.0),
+            size,
         });
         window
     }
+
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_active(&mut self, active: bool, global: &Global) {
+        if self.active != active {
+            self.active = active;
+            global.damage(self.bounds());
+        }
+    }
+
+    /// Whether the app asked for its window to close
+    pub fn should_close(&self) -> bool {
+        self.app.should_close()
+    }
+
+    /// Lets the app know its window is closing
+    pub fn close(&mut self, global: &Global) {
+        self.app.on_close(global);
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) {
+        self.title_height = theme.title_height;
+        self.frame_width = theme.window_frame_width;
+        self.shadow_color = theme.palette.window_shadow;
+        self.title_text.set_font_size(theme.typography.title_size);
+        self.title_text.color = theme.palette.window_title;
+        self.background.fill = theme.palette.window_background.into();
+        self.background.round_radius = theme.radii.window;
+        self.background.request_redraw();
+    }
+
+    /// Area covered by the window and its shadow
+    pub fn bounds(&self) -> Layout {
+        self.layout.union(&self.shadow().bounds())
+    }
+
+    fn shadow(&self) -> Shadow {
+        Shadow {
+            layout: self.layout,
+            round_radius: self.background.round_radius,
+            offset: Vector2::new(0.0, Self::SHADOW_OFFSET),
+            blur_radius: Self::SHADOW_BLUR_RADIUS,
+            spread: 0.0,
+            color: self.shadow_color,
+            inset: false,
+        }
+    }
+
+    /// Title, menu bar and app, from top to bottom inside the frame
+    fn sections(&self) -> Vec<Layout> {
+        let menu_bar_height = self.app.menu_bar().map_or(0.0, MenuBar::height);
+        let mut stack = Stack::vertical();
+        stack.padding = Insets::all(self.frame_width);
+        stack.push(Item {
+            margin: Insets {
+                bottom: self.frame_width,
+                ..Insets::default()
+            },
+            shrink: 0.0,
+            ..Item::sized(Vector2::new(0.0, self.title_height))
+        });
+        stack.push(Item {
+            shrink: 0.0,
+            ..Item::sized(Vector2::new(0.0, menu_bar_height))
+        });
+        stack.push(Item::flexible(1.0));
+        stack.layout(self.layout)
+    }
+
+    fn title_layout(&self) -> Layout {
+        self.sections()[0]
+    }
+
+    fn menu_bar_layout(&self) -> Layout {
+        self.sections()[1]
+    }
+
+    fn app_layout(&self) -> Layout {
+        self.sections()[2]
+    }
 }
 
 impl Component for Window {
     fn draw(&self, proxy: &mut RenderContextProxy) {
-        self.background.draw(proxy);
-        self.title_text.draw(proxy);
-        self.app.draw(proxy);
+        // contents are kept in a layer and only re-rendered when a child requested a redraw
+        if self.contents_dirty.replace(false) || !proxy.has_layer(self.id, &self.layout) {
+            proxy.with_layer(self.id, self.layout, |proxy| {
+                self.background.draw(proxy);
+                proxy.with_clip(self.title_layout(), |proxy| self.title_text.draw(proxy));
+                if let Some(menu_bar) = self.app.menu_bar() {
+                    proxy.with_clip(self.menu_bar_layout(), |proxy| menu_bar.draw(proxy));
+                }
+                proxy.with_clip(self.app_layout(), |proxy| self.app.draw(proxy));
+            });
+        }
+
+        proxy.draw_shadow(&self.shadow());
+        let dim = if self.active { 0.0 } else { Self::INACTIVE_DIM };
+        proxy.composite_layer(
+            self.id,
+            &Composite {
+                layout: self.layout,
+                opacity: self.opacity,
+                dim,
+            },
+        );
     }
 
     fn update(&mut self, global: &Global) {
@@ -68,15 +192,24 @@ impl Component for Window {
             let new_position = global.cursor_position();
             let (dx, dy) = (new_position.x - position.x, new_position.y - position.y);
             *position = new_position;
+            let old_bounds = self.bounds();
             let mut layout = self.layout;
             layout.position.x += dx as f32;
             layout.position.y += dy as f32;
             self.set_layout(layout);
-            global.request_redraw();
+            global.damage(old_bounds.union(&self.bounds()));
         }
+
+        let redraw_requests = global.redraw_requests();
         self.background.update(global);
         self.title_text.update(global);
+        if let Some(menu_bar) = self.app.menu_bar_mut() {
+            menu_bar.update(global);
+        }
         self.app.update(global);
+        if global.redraw_requests() != redraw_requests {
+            self.contents_dirty.set(true);
+        }
     }
 
     fn handle_event(&mut self, event: EventProxy, global: &Global) {
@@ -97,38 +230,67 @@ impl Component for Window {
                 } => {
                     self.dragging_state = None;
                 }
+                WindowEvent::CursorMoved { .. } => {
+                    // a title cut off by the window's width shows in full as a tooltip
+                    let title_layout = self.title_layout();
+                    if title_layout.contains(&global.cursor_position())
+                        && self.title_text.width() > title_layout.size.x
+                    {
+                        global.set_tooltip(title_layout, &self.title_text.content);
+                    }
+                }
                 _ => {}
             },
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.apply_theme(&global.theme());
+                self.set_layout(self.layout);
+                global.damage(self.bounds());
+            }
             _ => {}
         }
 
+        let redraw_requests = global.redraw_requests();
         self.background.handle_event(event, global);
         self.title_text.handle_event(event, global);
-        self.app.handle_event(event, global);
+        // clicks and keys only reach the menu bar of the active window
+        let is_input = matches!(
+            event,
+            Event::WindowEvent {
+                event: WindowEvent::MouseInput { .. } | WindowEvent::KeyboardInput { .. },
+                ..
+            }
+        );
+        let active = self.active;
+        if let Some(menu_bar) = self.app.menu_bar_mut() {
+            if active || !is_input {
+                menu_bar.handle_event(event, global);
+            }
+        }
+        // and typing only reaches the active window's app, so that e.g. Enter answers the
+        // dialog in front rather than every dialog open
+        let is_typing = matches!(
+            event,
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { .. } | WindowEvent::ReceivedCharacter(_),
+                ..
+            }
+        );
+        if active || !is_typing {
+            self.app.handle_event(event, global);
+        }
+        if global.redraw_requests() != redraw_requests {
+            self.contents_dirty.set(true);
+        }
     }
 
     fn set_layout(&mut self, layout: Layout) {
         self.layout = layout;
-        let title_text_layout = {
-            let position = layout.position + Vector2::new(Self::FRAME_WIDTH, Self::FRAME_WIDTH);
-            let size = Vector2::new(layout.size.x - Self::FRAME_WIDTH * 2.0, Self::TITLE_HEIGHT);
-            Layout { position, size }
-        };
-        let app_layout = {
-            let position = layout.position
-                + Vector2::new(
-                    Self::FRAME_WIDTH,
-                    Self::TITLE_HEIGHT + Self::FRAME_WIDTH * 2.0,
-                );
-            let size = Vector2::new(
-                layout.size.x - Self::FRAME_WIDTH * 2.0,
-                layout.size.y - Self::TITLE_HEIGHT - Self::FRAME_WIDTH * 3.0,
-            );
-            Layout { position, size }
-        };
-
         self.background.set_layout(layout);
-        self.title_text.set_layout(title_text_layout);
-        self.app.set_layout(app_layout);
+        self.title_text.set_layout(self.title_layout());
+        let menu_bar_layout = self.menu_bar_layout();
+        if let Some(menu_bar) = self.app.menu_bar_mut() {
+            menu_bar.set_layout(menu_bar_layout);
+        }
+        self.app.set_layout(self.app_layout());
     }
 }
@@ -2,15 +2,33 @@ use std::collections::HashMap;
 
 use super::application::Application;
 use super::window::Window;
-use crate::custom_event::EventProxy;
+use crate::component::Layout;
+use crate::custom_event::{CustomEvent, EventProxy};
 use crate::Component;
 use crate::Global;
 
+use glium::glutin::event::{ElementState, Event, WindowEvent};
+use nalgebra::Vector2;
+
 pub type WindowId = usize;
 
 pub struct WindowManager {
     window_id_counter: WindowId,
     windows: HashMap<WindowId, Window>,
+    /// Stacking order, from bottom to top
+    order: Vec<WindowId>,
+    /// Parent of each open dialog, which takes no input until the dialog closes
+    parents: HashMap<WindowId, WindowId>,
+    /// Window that took the last press, which gets the pointer until the release
+    pointer_grab: Option<WindowId>,
+    /// Window the pointer was last sent to, told when the cursor leaves it
+    hovered: Option<WindowId>,
+}
+
+impl Default for WindowManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WindowManager {
@@ -21,32 +39,369 @@ impl WindowManager {
         WindowManager {
             window_id_counter,
             windows,
+            order: Vec::new(),
+            parents: HashMap::new(),
+            pointer_grab: None,
+            hovered: None,
         }
     }
 
-    pub fn spawn(&mut self, app: impl Application, global: &Global) {
-        let window = Window::new(self.window_id_counter, app, global);
-        self.windows.insert(self.window_id_counter, window);
+    pub fn spawn(&mut self, app: impl Application, global: &Global) -> WindowId {
+        self.spawn_boxed(Box::new(app), global)
+    }
+
+    fn spawn_boxed(&mut self, app: Box<dyn Application>, global: &Global) -> WindowId {
+        let id = self.window_id_counter;
+        let window = Window::new(id, app, global);
+        self.windows.insert(id, window);
+        self.order.push(id);
         self.window_id_counter += 1;
+        self.activate(id, global);
+        id
+    }
+
+    /// Opens `app` as a dialog centered over `parent`, which takes no input until it closes
+    pub fn spawn_dialog(
+        &mut self,
+        parent: WindowId,
+        app: impl Application,
+        global: &Global,
+    ) -> WindowId {
+        self.spawn_dialog_boxed(parent, Box::new(app), global)
+    }
+
+    fn spawn_dialog_boxed(
+        &mut self,
+        parent: WindowId,
+        app: Box<dyn Application>,
+        global: &Global,
+    ) -> WindowId {
+        let parent_layout = match self.windows.get(&parent) {
+            Some(window) => window.layout(),
+            None => return self.spawn_boxed(app, global),
+        };
+        self.parents.insert(self.window_id_counter, parent);
+        let id = self.spawn_boxed(app, global);
+        let window = self.windows.get_mut(&id).expect("dialog was just spawned");
+        let size = window.layout().size;
+        let position = parent_layout.position + (parent_layout.size - size) / 2.0;
+        window.set_layout(Layout {
+            position: position.sup(&Vector2::zeros()),
+            size,
+        });
+        id
+    }
+
+    /// Topmost dialog open over `id`
+    fn dialog_of(&self, id: WindowId) -> Option<WindowId> {
+        self.order
+            .iter()
+            .rev()
+            .copied()
+            .find(|dialog| self.parents.get(dialog) == Some(&id))
+    }
+
+    /// Raises the window to the top and makes it the only active one. Its dialogs are raised
+    /// above it, the topmost of them becoming the active one
+    pub fn activate(&mut self, id: WindowId, global: &Global) {
+        if !self.windows.contains_key(&id) {
+            return;
+        }
+        self.raise(id, global);
+        let top = self.active();
+        for window in self.windows.values_mut() {
+            let active = Some(window.id()) == top;
+            window.set_active(active, global);
+        }
+    }
+
+    fn raise(&mut self, id: WindowId, global: &Global) {
+        self.order.retain(|&other| other != id);
+        self.order.push(id);
+        global.damage(self.windows[&id].bounds());
+        let dialogs: Vec<_> = self
+            .order
+            .iter()
+            .copied()
+            .filter(|dialog| self.parents.get(dialog) == Some(&id))
+            .collect();
+        for dialog in dialogs {
+            self.raise(dialog, global);
+        }
+    }
+
+    /// Topmost window, which is the active one
+    pub fn active(&self) -> Option<WindowId> {
+        self.order.last().copied()
+    }
+
+    /// Closes the window along with its dialogs. Closing a dialog hands focus back to its parent
+    pub fn close(&mut self, id: WindowId, global: &Global) {
+        while let Some(dialog) = self.dialog_of(id) {
+            self.close(dialog, global);
+        }
+        if let Some(mut window) = self.windows.remove(&id) {
+            self.order.retain(|&other| other != id);
+            if self.pointer_grab == Some(id) {
+                self.pointer_grab = None;
+            }
+            global.damage(window.bounds());
+            window.close(global);
+            let parent = self.parents.remove(&id);
+            if parent.is_some() {
+                let _ = global.send_event(CustomEvent::DialogClosed(id));
+            }
+            if let Some(top) = parent.or_else(|| self.active()) {
+                self.activate(top, global);
+            }
+        }
+    }
+
+    /// Opens the dialogs asked for through `Global::open_dialog` while `parent` was handling
+    /// an event or updating
+    fn spawn_requested_dialogs(&mut self, parent: WindowId, global: &Global) {
+        for app in global.take_dialog_requests() {
+            self.spawn_dialog_boxed(parent, app, global);
+        }
+    }
+
+    /// Activates the bottommost window, cycling through all of them when repeated
+    pub fn activate_next(&mut self, global: &Global) {
+        if let Some(&bottom) = self.order.first() {
+            self.activate(bottom, global);
+        }
+    }
+
+    /// Topmost window under the cursor
+    fn window_at_cursor(&self, global: &Global) -> Option<WindowId> {
+        let cursor_position = global.cursor_position();
+        self.order
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| self.windows[id].layout().contains(&cursor_position))
     }
 }
 
 impl Component for WindowManager {
     fn draw(&self, proxy: &mut crate::RenderContextProxy) {
-        for window in self.windows.values() {
-            window.draw(proxy);
+        for id in &self.order {
+            self.windows[id].draw(proxy);
         }
     }
 
     fn update(&mut self, global: &Global) {
-        for window in self.windows.values_mut() {
+        let ids: Vec<_> = self.order.clone();
+        for id in ids {
+            let window = match self.windows.get_mut(&id) {
+                Some(window) => window,
+                None => continue,
+            };
             window.update(global);
+            if window.should_close() {
+                self.close(id, global);
+            }
+            self.spawn_requested_dialogs(id, global);
         }
     }
 
     fn handle_event(&mut self, event: EventProxy, global: &Global) {
-        for window in self.windows.values_mut() {
-            window.handle_event(event, global)
+        let at_cursor = self.window_at_cursor(global);
+        let (window_id, window_event) = match event {
+            Event::WindowEvent { window_id, event } => (Some(*window_id), Some(event)),
+            _ => (None, None),
+        };
+        let is_pointer = matches!(
+            window_event,
+            Some(
+                WindowEvent::CursorMoved { .. }
+                    | WindowEvent::MouseInput { .. }
+                    | WindowEvent::MouseWheel { .. }
+            )
+        );
+
+        // pointer events go to the window under the cursor only, or to the one holding a
+        // press so that drags and releases outside it still reach it
+        let mut pointer_target = self.pointer_grab.or(at_cursor);
+        if let Some(WindowEvent::MouseInput {
+            state: ElementState::Pressed,
+            ..
+        }) = window_event
+        {
+            if let Some(id) = at_cursor {
+                if self.order.last() != Some(&id) {
+                    self.activate(id, global);
+                }
+            }
+            if self.pointer_grab.is_none() {
+                self.pointer_grab = at_cursor;
+                pointer_target = at_cursor;
+            }
         }
+        if let (Some(window_id), Some(WindowEvent::CursorMoved { device_id, .. })) =
+            (window_id, window_event)
+        {
+            if self.hovered != pointer_target {
+                let left = self.hovered.and_then(|id| self.windows.get_mut(&id));
+                if let Some(window) = left {
+                    let event = Event::WindowEvent {
+                        window_id,
+                        event: WindowEvent::CursorLeft {
+                            device_id: *device_id,
+                        },
+                    };
+                    window.handle_event(&event, global);
+                }
+            }
+            self.hovered = pointer_target;
+        }
+
+        let is_input = is_pointer
+            || matches!(
+                window_event,
+                Some(WindowEvent::KeyboardInput { .. } | WindowEvent::ReceivedCharacter(_))
+            );
+        let ids: Vec<_> = self.order.clone();
+        for id in ids {
+            if is_pointer && Some(id) != pointer_target {
+                continue;
+            }
+            // windows with a dialog open wait for it to close
+            if is_input && self.dialog_of(id).is_some() {
+                continue;
+            }
+            let window = match self.windows.get_mut(&id) {
+                Some(window) => window,
+                None => continue,
+            };
+            // only the window under the cursor may show a tooltip, not the ones it covers
+            let tooltip = global.replace_tooltip(None);
+            window.handle_event(event, global);
+            let registered = global
+                .replace_tooltip(None)
+                .filter(|_| Some(id) == at_cursor);
+            global.replace_tooltip(registered.or(tooltip));
+            self.spawn_requested_dialogs(id, global);
+        }
+
+        if let Some(WindowEvent::MouseInput {
+            state: ElementState::Released,
+            ..
+        }) = window_event
+        {
+            self.pointer_grab = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::utils::Button;
+    use crate::headless::{test_font, Headless};
+    use crate::RenderContextProxy;
+
+    use super::super::application::AppInfo;
+
+    use glium::glutin::dpi::{LogicalPosition, PhysicalSize};
+    use glium::glutin::event::MouseButton;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Window filled with a button counting its clicks
+    struct Clicker {
+        app_info: AppInfo,
+        button: Button,
+    }
+
+    impl Clicker {
+        fn new(clicks: Rc<Cell<u32>>, global: &Global) -> Self {
+            let mut button = Button::new(global);
+            button
+                .on_click
+                .set(move |(), _| clicks.set(clicks.get() + 1));
+            Clicker {
+                app_info: AppInfo {
+                    title: String::from("Clicker"),
+                    layout: Layout::default(),
+                },
+                button,
+            }
+        }
+    }
+
+    impl Component for Clicker {
+        fn draw(&self, proxy: &mut RenderContextProxy) {
+            self.button.draw(proxy);
+        }
+
+        fn handle_event(&mut self, event: EventProxy, global: &Global) {
+            self.button.handle_event(event, global);
+        }
+
+        fn update(&mut self, global: &Global) {
+            self.button.update(global);
+        }
+
+        fn set_layout(&mut self, layout: Layout) {
+            self.app_info.layout = layout;
+            self.button.set_layout(layout);
+        }
+    }
+
+    impl Application for Clicker {
+        fn get_app_info(&self) -> &AppInfo {
+            &self.app_info
+        }
+    }
+
+    /// Two windows opened at the same place, the second covering the first
+    fn stacked() -> (Headless<WindowManager>, Rc<Cell<u32>>, Rc<Cell<u32>>) {
+        let size = PhysicalSize::new(640, 420);
+        let mut headless = Headless::with_root(test_font(), size, 1.0, |_| WindowManager::new());
+        let (back, front) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        headless.root.spawn(
+            Clicker::new(back.clone(), &headless.global),
+            &headless.global,
+        );
+        headless.root.spawn(
+            Clicker::new(front.clone(), &headless.global),
+            &headless.global,
+        );
+        headless.render();
+        (headless, back, front)
+    }
+
+    #[test]
+    fn clicks_reach_only_the_front_window() {
+        let (mut headless, back, front) = stacked();
+
+        headless.click(LogicalPosition::new(140.0, 180.0));
+        assert_eq!((back.get(), front.get()), (0, 1));
+    }
+
+    #[test]
+    fn a_press_keeps_the_pointer_until_released() {
+        let (mut headless, back, front) = stacked();
+        let back_id = headless.root.order[0];
+        let mut layout = headless.root.windows[&back_id].layout();
+        layout.position.x += 300.0;
+        headless
+            .root
+            .windows
+            .get_mut(&back_id)
+            .unwrap()
+            .set_layout(layout);
+
+        // pressed on the front window, dragged onto the back one and released there
+        headless.move_cursor(LogicalPosition::new(140.0, 180.0));
+        headless.mouse_input(ElementState::Pressed, MouseButton::Left);
+        headless.move_cursor(LogicalPosition::new(440.0, 180.0));
+        headless.mouse_input(ElementState::Released, MouseButton::Left);
+        assert_eq!((back.get(), front.get()), (0, 0));
+
+        headless.click(LogicalPosition::new(440.0, 180.0));
+        assert_eq!((back.get(), front.get()), (1, 0));
     }
 }
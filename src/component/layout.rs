@@ -0,0 +1,155 @@
+use crate::component::{Layout, Orientation};
+
+use nalgebra::Vector2;
+
+mod grid;
+mod stack;
+
+pub use grid::{Grid, GridCell, Track};
+pub use stack::{Justify, Stack};
+
+/// Space kept inside or around a box, in logical pixels
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Insets {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Insets {
+    pub fn all(inset: f32) -> Self {
+        Self::symmetric(inset, inset)
+    }
+
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Self {
+        Insets {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+
+    /// Total inset along each axis
+    pub fn size(&self) -> Vector2<f32> {
+        Vector2::new(self.left + self.right, self.top + self.bottom)
+    }
+
+    /// What is left of `layout` once the insets are taken away
+    pub fn shrink(&self, layout: Layout) -> Layout {
+        Layout {
+            position: layout.position + Vector2::new(self.left, self.top),
+            size: (layout.size - self.size()).sup(&Vector2::zeros()),
+        }
+    }
+
+    /// Inset before the content along `axis`
+    fn leading(&self, axis: usize) -> f32 {
+        if axis == 0 {
+            self.left
+        } else {
+            self.top
+        }
+    }
+}
+
+/// Where a child sits within the space it's given
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    /// Fills the space, within its min and max size
+    Stretch,
+}
+
+/// Size constraints a container lays one child out with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Item {
+    /// Size the child starts from before growing or shrinking, e.g. its `intrinsic_size`
+    pub preferred: Vector2<f32>,
+    pub min: Vector2<f32>,
+    /// Infinite by default
+    pub max: Vector2<f32>,
+    /// Share of the free space along a stack the child takes, none by default
+    pub grow: f32,
+    /// Share of the missing space along a stack the child gives up, weighted by its preferred
+    /// size. One by default, zero keeps the child from shrinking
+    pub shrink: f32,
+    /// Overrides the container's alignment for this child
+    pub align: Option<Align>,
+    /// Space kept around the child, outside its layout
+    pub margin: Insets,
+}
+
+impl Default for Item {
+    fn default() -> Self {
+        Item {
+            preferred: Vector2::zeros(),
+            min: Vector2::zeros(),
+            max: Vector2::repeat(f32::INFINITY),
+            grow: 0.0,
+            shrink: 1.0,
+            align: None,
+            margin: Insets::default(),
+        }
+    }
+}
+
+impl Item {
+    /// Child starting from `preferred`
+    pub fn sized(preferred: Vector2<f32>) -> Self {
+        Item {
+            preferred,
+            ..Item::default()
+        }
+    }
+
+    /// Child starting from nothing and taking a `grow` share of the free space
+    pub fn flexible(grow: f32) -> Self {
+        Item {
+            grow,
+            ..Item::default()
+        }
+    }
+
+    /// `size` kept within the min and max size along `axis`
+    fn clamp(&self, size: f32, axis: usize) -> f32 {
+        size.min(self.max[axis]).max(self.min[axis])
+    }
+
+    /// Preferred size within min and max, margins included
+    fn outer_preferred(&self) -> Vector2<f32> {
+        Vector2::new(
+            self.clamp(self.preferred.x, 0),
+            self.clamp(self.preferred.y, 1),
+        ) + self.margin.size()
+    }
+
+    /// Position and size along `axis` within `available` space starting at `start`, margins
+    /// taken away
+    fn place(&self, align: Align, start: f32, available: f32, axis: usize) -> (f32, f32) {
+        let start = start + self.margin.leading(axis);
+        let available = available - self.margin.size()[axis];
+        let align = self.align.unwrap_or(align);
+        let size = match align {
+            Align::Stretch => self.clamp(available, axis),
+            _ => self.clamp(self.preferred[axis], axis),
+        };
+        let offset = match align {
+            Align::Start | Align::Stretch => 0.0,
+            Align::Center => (available - size) / 2.0,
+            Align::End => available - size,
+        };
+        (start + offset, size)
+    }
+}
+
+/// Index of the axis along `orientation`, then of the one across it
+fn axes(orientation: Orientation) -> (usize, usize) {
+    match orientation {
+        Orientation::Horizontal => (0, 1),
+        Orientation::Vertical => (1, 0),
+    }
+}
@@ -0,0 +1,264 @@
+use super::{Align, Insets, Item};
+use crate::component::Layout;
+
+use nalgebra::Vector2;
+
+/// Size of a grid column or row
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Track {
+    /// Exactly this many logical pixels
+    Fixed(f32),
+    /// As large as the largest preferred size among the children covering only this track.
+    /// Children spanning several tracks don't count towards it
+    Auto,
+    /// Share of the space left over by the other tracks, nothing when none is left. Sized as
+    /// `Auto` for the grid's intrinsic size
+    Fraction(f32),
+}
+
+/// Child of a grid and the cells it covers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridCell {
+    pub column: usize,
+    pub row: usize,
+    pub column_span: usize,
+    pub row_span: usize,
+    pub item: Item,
+}
+
+impl GridCell {
+    /// Child covering the single cell at `column` and `row`
+    pub fn new(column: usize, row: usize, item: Item) -> Self {
+        GridCell {
+            column,
+            row,
+            column_span: 1,
+            row_span: 1,
+            item,
+        }
+    }
+
+    /// First and last track covered along `axis`
+    fn span(&self, axis: usize) -> (usize, usize) {
+        let (start, span) = if axis == 0 {
+            (self.column, self.column_span)
+        } else {
+            (self.row, self.row_span)
+        };
+        (start, start + span.max(1) - 1)
+    }
+}
+
+/// Lays children out in cells of columns and rows, each child placed within the cells it
+/// covers by its alignment
+#[derive(Clone, Debug)]
+pub struct Grid {
+    pub columns: Vec<Track>,
+    pub rows: Vec<Track>,
+    pub padding: Insets,
+    /// Gaps between columns and between rows
+    pub spacing: Vector2<f32>,
+    /// Where children sit within their cells, unless they align themselves
+    pub align: Align,
+    pub cells: Vec<GridCell>,
+}
+
+impl Grid {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Grid {
+            columns,
+            rows,
+            padding: Insets::default(),
+            spacing: Vector2::zeros(),
+            align: Align::Stretch,
+            cells: Vec::new(),
+        }
+    }
+
+    /// Adds a child, returning its index among the layouts
+    pub fn push(&mut self, cell: GridCell) -> usize {
+        self.cells.push(cell);
+        self.cells.len() - 1
+    }
+
+    fn tracks(&self, axis: usize) -> &[Track] {
+        if axis == 0 {
+            &self.columns
+        } else {
+            &self.rows
+        }
+    }
+
+    /// Sizes of the tracks along `axis`, sharing out `available` space between fractions
+    fn track_sizes(&self, axis: usize, available: Option<f32>) -> Vec<f32> {
+        let tracks = self.tracks(axis);
+        let auto = |index: usize| {
+            self.cells
+                .iter()
+                .filter(|cell| cell.span(axis) == (index, index))
+                .map(|cell| cell.item.outer_preferred()[axis])
+                .fold(0.0, f32::max)
+        };
+        let mut sizes: Vec<f32> = tracks
+            .iter()
+            .enumerate()
+            .map(|(index, track)| match track {
+                Track::Fixed(size) => *size,
+                Track::Auto => auto(index),
+                Track::Fraction(_) if available.is_none() => auto(index),
+                Track::Fraction(_) => 0.0,
+            })
+            .collect();
+
+        if let Some(available) = available {
+            let gaps = self.spacing[axis] * tracks.len().saturating_sub(1) as f32;
+            let free = (available - gaps - sizes.iter().sum::<f32>()).max(0.0);
+            let fractions: f32 = tracks
+                .iter()
+                .map(|track| match track {
+                    Track::Fraction(fraction) => *fraction,
+                    _ => 0.0,
+                })
+                .sum();
+            for (size, track) in sizes.iter_mut().zip(tracks) {
+                if let Track::Fraction(fraction) = track {
+                    if fractions > 0.0 {
+                        *size = free * fraction / fractions;
+                    }
+                }
+            }
+        }
+        sizes
+    }
+
+    /// Size taking every track at its fixed or auto size
+    pub fn intrinsic_size(&self) -> Vector2<f32> {
+        let mut size = Vector2::zeros();
+        for axis in 0..2 {
+            let tracks = self.track_sizes(axis, None);
+            let gaps = self.spacing[axis] * tracks.len().saturating_sub(1) as f32;
+            size[axis] = tracks.iter().sum::<f32>() + gaps;
+        }
+        size + self.padding.size()
+    }
+
+    /// Layouts of the children within `layout`, in the order they were pushed. Children
+    /// outside the grid's tracks get no size
+    pub fn layout(&self, layout: Layout) -> Vec<Layout> {
+        let inner = self.padding.shrink(layout);
+        let mut starts = [Vec::new(), Vec::new()];
+        let mut sizes = [Vec::new(), Vec::new()];
+        for axis in 0..2 {
+            sizes[axis] = self.track_sizes(axis, Some(inner.size[axis]));
+            let mut start = inner.position[axis];
+            for size in &sizes[axis] {
+                starts[axis].push(start);
+                start += size + self.spacing[axis];
+            }
+        }
+
+        self.cells
+            .iter()
+            .map(|cell| {
+                let mut position = Vector2::zeros();
+                let mut size = Vector2::zeros();
+                for axis in 0..2 {
+                    let (first, last) = cell.span(axis);
+                    if last >= sizes[axis].len() {
+                        return Layout::default();
+                    }
+                    let start = starts[axis][first];
+                    let available = starts[axis][last] + sizes[axis][last] - start;
+                    let (cell_position, cell_size) =
+                        cell.item.place(self.align, start, available, axis);
+                    position[axis] = cell_position;
+                    size[axis] = cell_size;
+                }
+                Layout { position, size }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Layout {
+        Layout {
+            position: Vector2::new(x, y),
+            size: Vector2::new(width, height),
+        }
+    }
+
+    #[test]
+    fn lays_out_fixed_auto_and_fraction_tracks() {
+        let mut grid = Grid::new(
+            vec![Track::Fixed(40.0), Track::Auto, Track::Fraction(1.0)],
+            vec![Track::Auto, Track::Fraction(1.0)],
+        );
+        grid.spacing = Vector2::new(4.0, 4.0);
+        grid.push(GridCell::new(0, 0, Item::sized(Vector2::new(10.0, 20.0))));
+        grid.push(GridCell::new(
+            1,
+            0,
+            Item {
+                align: Some(Align::End),
+                ..Item::sized(Vector2::new(70.0, 10.0))
+            },
+        ));
+        grid.push(GridCell {
+            column_span: 3,
+            ..GridCell::new(0, 1, Item::sized(Vector2::new(500.0, 10.0)))
+        });
+
+        // the cell spanning the columns counts towards none of them, only towards its row
+        assert_eq!(grid.intrinsic_size(), Vector2::new(118.0, 34.0));
+        assert_eq!(
+            grid.layout(rect(0.0, 0.0, 200.0, 100.0)),
+            vec![
+                rect(0.0, 0.0, 40.0, 20.0),
+                rect(44.0, 10.0, 70.0, 10.0),
+                rect(0.0, 24.0, 200.0, 76.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn fractions_share_what_is_left() {
+        let mut grid = Grid::new(
+            vec![
+                Track::Fixed(80.0),
+                Track::Fraction(1.0),
+                Track::Fraction(3.0),
+            ],
+            Vec::new(),
+        );
+        grid.spacing = Vector2::new(10.0, 0.0);
+        assert_eq!(grid.track_sizes(0, Some(180.0)), vec![80.0, 20.0, 60.0]);
+        // nothing left over
+        assert_eq!(grid.track_sizes(0, Some(50.0)), vec![80.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn auto_ignores_spanning_cells() {
+        let mut grid = Grid::new(vec![Track::Auto, Track::Auto], vec![Track::Auto]);
+        grid.push(GridCell {
+            column_span: 2,
+            ..GridCell::new(0, 0, Item::sized(Vector2::new(100.0, 10.0)))
+        });
+        grid.push(GridCell::new(1, 0, Item::sized(Vector2::new(30.0, 5.0))));
+        assert_eq!(grid.track_sizes(0, Some(200.0)), vec![0.0, 30.0]);
+        assert_eq!(grid.track_sizes(1, None), vec![10.0]);
+    }
+
+    #[test]
+    fn cells_outside_the_tracks_get_no_size() {
+        let mut grid = Grid::new(vec![Track::Fixed(10.0)], vec![Track::Fixed(10.0)]);
+        grid.push(GridCell::new(1, 0, Item::sized(Vector2::new(5.0, 5.0))));
+        assert_eq!(
+            grid.layout(rect(0.0, 0.0, 10.0, 10.0)),
+            vec![Layout::default()]
+        );
+    }
+}
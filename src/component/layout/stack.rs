@@ -0,0 +1,286 @@
+use super::{axes, Align, Insets, Item};
+use crate::component::{Layout, Orientation};
+
+use nalgebra::Vector2;
+
+/// Where the children of a stack sit along it when they leave space free
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Justify {
+    Start,
+    Center,
+    End,
+    /// Free space spread evenly between the children
+    SpaceBetween,
+}
+
+/// Lays children out one after another along `orientation`, flexbox style: each starts from
+/// its preferred size, then grows into free space or shrinks to fit
+#[derive(Clone, Debug)]
+pub struct Stack {
+    pub orientation: Orientation,
+    pub padding: Insets,
+    /// Gap between neighbouring children
+    pub spacing: f32,
+    /// Where children sit across the stack, unless they align themselves
+    pub align: Align,
+    pub justify: Justify,
+    pub items: Vec<Item>,
+}
+
+impl Stack {
+    pub fn new(orientation: Orientation) -> Self {
+        Stack {
+            orientation,
+            padding: Insets::default(),
+            spacing: 0.0,
+            align: Align::Stretch,
+            justify: Justify::Start,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn horizontal() -> Self {
+        Self::new(Orientation::Horizontal)
+    }
+
+    pub fn vertical() -> Self {
+        Self::new(Orientation::Vertical)
+    }
+
+    /// Appends a child, returning its index among the layouts
+    pub fn push(&mut self, item: Item) -> usize {
+        self.items.push(item);
+        self.items.len() - 1
+    }
+
+    /// Total spacing between the children
+    fn gaps(&self) -> f32 {
+        self.spacing * self.items.len().saturating_sub(1) as f32
+    }
+
+    /// Size taking every child at its preferred size
+    pub fn intrinsic_size(&self) -> Vector2<f32> {
+        let (main, cross) = axes(self.orientation);
+        let mut size = Vector2::<f32>::zeros();
+        for item in &self.items {
+            let preferred = item.outer_preferred();
+            size[main] += preferred[main];
+            size[cross] = size[cross].max(preferred[cross]);
+        }
+        size[main] += self.gaps();
+        size + self.padding.size()
+    }
+
+    /// Layouts of the children within `layout`, in the order they were pushed
+    pub fn layout(&self, layout: Layout) -> Vec<Layout> {
+        let (main, cross) = axes(self.orientation);
+        let inner = self.padding.shrink(layout);
+        let margins: f32 = self.items.iter().map(|item| item.margin.size()[main]).sum();
+        let available = inner.size[main] - self.gaps() - margins;
+        let sizes = self.resolve(available);
+
+        let free = (available - sizes.iter().sum::<f32>()).max(0.0);
+        let (mut offset, gap) = match self.justify {
+            Justify::Start => (0.0, self.spacing),
+            Justify::Center => (free / 2.0, self.spacing),
+            Justify::End => (free, self.spacing),
+            Justify::SpaceBetween if self.items.len() > 1 => {
+                (0.0, self.spacing + free / (self.items.len() - 1) as f32)
+            }
+            Justify::SpaceBetween => (0.0, self.spacing),
+        };
+
+        let mut layouts = Vec::with_capacity(self.items.len());
+        for (item, size) in self.items.iter().zip(sizes) {
+            let mut position = Vector2::zeros();
+            let mut child_size = Vector2::zeros();
+            position[main] = inner.position[main] + offset + item.margin.leading(main);
+            child_size[main] = size;
+            let (start, across) =
+                item.place(self.align, inner.position[cross], inner.size[cross], cross);
+            position[cross] = start;
+            child_size[cross] = across;
+            layouts.push(Layout {
+                position,
+                size: child_size,
+            });
+            offset += size + item.margin.size()[main] + gap;
+        }
+        layouts
+    }
+
+    /// Sizes along the stack filling `available`. Free space is handed out by `grow` and
+    /// missing space taken back by `shrink`, sharing again what children stopping at their
+    /// min or max size couldn't take
+    fn resolve(&self, available: f32) -> Vec<f32> {
+        let (main, _) = axes(self.orientation);
+        let base: Vec<f32> = self
+            .items
+            .iter()
+            .map(|item| item.clamp(item.preferred[main], main))
+            .collect();
+        let mut sizes = base.clone();
+        let mut frozen = vec![false; self.items.len()];
+        loop {
+            let free = available - sizes.iter().sum::<f32>();
+            let weight = |index: usize| {
+                let item = &self.items[index];
+                if free > 0.0 {
+                    item.grow
+                } else {
+                    item.shrink * base[index]
+                }
+            };
+            let total: f32 = (0..sizes.len())
+                .filter(|&index| !frozen[index])
+                .map(weight)
+                .sum();
+            if free.abs() < 0.01 || total <= 0.0 {
+                return sizes;
+            }
+
+            let mut clamped = false;
+            for index in 0..sizes.len() {
+                if frozen[index] {
+                    continue;
+                }
+                let target = sizes[index] + free * weight(index) / total;
+                let size = self.items[index].clamp(target, main);
+                if size != target {
+                    frozen[index] = true;
+                    clamped = true;
+                }
+                sizes[index] = size;
+            }
+            if !clamped {
+                return sizes;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(width: f32, height: f32) -> Layout {
+        Layout {
+            position: Vector2::zeros(),
+            size: Vector2::new(width, height),
+        }
+    }
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Layout {
+        Layout {
+            position: Vector2::new(x, y),
+            size: Vector2::new(width, height),
+        }
+    }
+
+    #[test]
+    fn grows_into_free_space() {
+        let mut stack = Stack::horizontal();
+        stack.padding = Insets::all(10.0);
+        stack.spacing = 5.0;
+        stack.push(Item::sized(Vector2::new(50.0, 30.0)));
+        stack.push(Item {
+            max: Vector2::new(60.0, f32::INFINITY),
+            ..Item::flexible(1.0)
+        });
+        stack.push(Item {
+            align: Some(Align::Center),
+            margin: Insets::all(2.0),
+            ..Item::sized(Vector2::new(30.0, 10.0))
+        });
+        stack.push(Item::flexible(1.0));
+
+        assert_eq!(stack.intrinsic_size(), Vector2::new(119.0, 50.0));
+        // the second child stops at its max size, leaving the rest to the last one
+        assert_eq!(
+            stack.layout(layout(300.0, 50.0)),
+            vec![
+                rect(10.0, 10.0, 50.0, 30.0),
+                rect(65.0, 10.0, 60.0, 30.0),
+                rect(132.0, 20.0, 30.0, 10.0),
+                rect(169.0, 10.0, 121.0, 30.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn shrinks_to_fit() {
+        let mut stack = Stack::vertical();
+        stack.push(Item::sized(Vector2::new(10.0, 100.0)));
+        stack.push(Item {
+            min: Vector2::new(0.0, 80.0),
+            ..Item::sized(Vector2::new(10.0, 100.0))
+        });
+        stack.push(Item::sized(Vector2::new(10.0, 100.0)));
+        // the second child stops at its min size, the others give up the rest
+        assert_eq!(stack.resolve(180.0), vec![50.0, 80.0, 50.0]);
+
+        let mut stack = Stack::vertical();
+        stack.push(Item {
+            shrink: 0.0,
+            ..Item::sized(Vector2::new(10.0, 100.0))
+        });
+        stack.push(Item::sized(Vector2::new(10.0, 100.0)));
+        assert_eq!(stack.resolve(150.0), vec![100.0, 50.0]);
+    }
+
+    #[test]
+    fn shares_by_weight() {
+        let mut stack = Stack::horizontal();
+        stack.push(Item::flexible(1.0));
+        stack.push(Item::flexible(3.0));
+        assert_eq!(stack.resolve(200.0), vec![50.0, 150.0]);
+
+        // shrinking is weighted by the preferred size too
+        let mut stack = Stack::horizontal();
+        stack.push(Item::sized(Vector2::new(100.0, 0.0)));
+        stack.push(Item::sized(Vector2::new(300.0, 0.0)));
+        assert_eq!(stack.resolve(200.0), vec![50.0, 150.0]);
+    }
+
+    #[test]
+    fn justifies_free_space() {
+        let mut stack = Stack::horizontal();
+        stack.push(Item::sized(Vector2::new(50.0, 10.0)));
+        stack.push(Item::sized(Vector2::new(50.0, 10.0)));
+        let starts = |stack: &Stack| -> Vec<f32> {
+            stack
+                .layout(layout(300.0, 10.0))
+                .iter()
+                .map(|layout| layout.position.x)
+                .collect()
+        };
+
+        assert_eq!(starts(&stack), vec![0.0, 50.0]);
+        stack.justify = Justify::Center;
+        assert_eq!(starts(&stack), vec![100.0, 150.0]);
+        stack.justify = Justify::End;
+        assert_eq!(starts(&stack), vec![200.0, 250.0]);
+        stack.justify = Justify::SpaceBetween;
+        assert_eq!(starts(&stack), vec![0.0, 250.0]);
+    }
+
+    #[test]
+    fn aligns_across() {
+        let mut stack = Stack::horizontal();
+        stack.align = Align::End;
+        stack.push(Item::sized(Vector2::new(10.0, 20.0)));
+        stack.push(Item {
+            align: Some(Align::Start),
+            ..Item::sized(Vector2::new(10.0, 20.0))
+        });
+        stack.push(Item {
+            align: Some(Align::Stretch),
+            max: Vector2::new(f32::INFINITY, 70.0),
+            ..Item::sized(Vector2::new(10.0, 20.0))
+        });
+        let layouts = stack.layout(layout(30.0, 100.0));
+        assert_eq!(layouts[0], rect(0.0, 80.0, 10.0, 20.0));
+        assert_eq!(layouts[1], rect(10.0, 0.0, 10.0, 20.0));
+        assert_eq!(layouts[2], rect(20.0, 0.0, 10.0, 70.0));
+    }
+}
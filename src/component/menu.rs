@@ -0,0 +1,206 @@
+use crate::component::Callback;
+use crate::config::KeyBinding;
+use crate::Global;
+
+use glium::glutin::event::{ModifiersState, VirtualKeyCode};
+
+use std::time::{Duration, Instant};
+
+mod menu_bar;
+mod overlay;
+
+pub use menu_bar::MenuBar;
+pub(crate) use overlay::{MenuOverlay, MenuRequest};
+
+/// Identifies one opening of a popup menu, reported back by `CustomEvent::MenuClosed`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MenuId(pub(crate) u64);
+
+/// How a popup menu is shown, for `Global::open_menu_with`
+#[derive(Clone, Copy, Debug)]
+pub struct MenuOptions {
+    /// Narrowest the popup may be, e.g. to match the widget it drops down from
+    pub min_width: f32,
+    /// Item highlighted when the popup appears
+    pub highlighted: Option<usize>,
+    /// Whether the popup takes keyboard input. Otherwise keys keep going to the windows, for
+    /// popups such as suggestions shown while typing
+    pub keyboard: bool,
+}
+
+impl Default for MenuOptions {
+    fn default() -> Self {
+        MenuOptions {
+            min_width: 0.0,
+            highlighted: None,
+            keyboard: true,
+        }
+    }
+}
+
+/// Prefix typed to jump between labels, forgotten after a pause in typing
+#[derive(Default)]
+pub(crate) struct TypeAhead {
+    prefix: String,
+    last_key: Option<Instant>,
+}
+
+impl TypeAhead {
+    const TIMEOUT: Duration = Duration::from_millis(1000);
+
+    /// Index of the label matching the prefix extended by `c`, searching from `current`.
+    /// Typing the same letter again moves on to the next label starting with it. Labels that
+    /// can't be picked are `None`
+    pub fn find(
+        &mut self,
+        c: char,
+        labels: &[Option<&str>],
+        current: Option<usize>,
+    ) -> Option<usize> {
+        let now = Instant::now();
+        if matches!(self.last_key, Some(last_key) if now - last_key > Self::TIMEOUT) {
+            self.prefix.clear();
+        }
+        self.last_key = Some(now);
+        // a space only counts within a prefix, on its own it's the key that opened the list
+        if c == ' ' && self.prefix.is_empty() {
+            return None;
+        }
+        self.prefix.extend(c.to_lowercase());
+
+        let first = self.prefix.chars().next()?;
+        let (prefix, start) = match current {
+            Some(current) if self.prefix.chars().all(|c| c == first) => {
+                (first.to_string(), current + 1)
+            }
+            Some(current) => (self.prefix.clone(), current),
+            None => (self.prefix.clone(), 0),
+        };
+        let len = labels.len();
+        (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&index| {
+                labels[index].is_some_and(|label| label.to_lowercase().starts_with(&prefix))
+            })
+    }
+}
+
+/// Entry of a menu, opened with `Global::open_menu` or shown in a `MenuBar`
+pub enum MenuEntry {
+    Item(MenuItem),
+    Separator,
+}
+
+pub struct MenuItem {
+    pub label: String,
+    /// Key combination shown next to the label. Menu bars also trigger the item with it
+    pub accelerator: Option<KeyBinding>,
+    pub enabled: bool,
+    /// Check mark state of checkable items, `None` for plain ones
+    pub checked: Option<bool>,
+    /// Menu opened from this item instead of activating it
+    pub submenu: Option<Menu>,
+    /// Invoked when the item is chosen
+    pub on_activate: Callback<()>,
+    /// Invoked with the new state when a checkable item is chosen
+    pub on_toggle: Callback<bool>,
+}
+
+impl MenuItem {
+    pub fn new(label: &str) -> Self {
+        MenuItem {
+            label: label.to_owned(),
+            accelerator: None,
+            enabled: true,
+            checked: None,
+            submenu: None,
+            on_activate: Callback::new(),
+            on_toggle: Callback::new(),
+        }
+    }
+
+    /// Flips the check mark of checkable items and invokes the callbacks
+    pub fn activate(&mut self, global: &Global) {
+        if let Some(checked) = &mut self.checked {
+            *checked = !*checked;
+            let checked = *checked;
+            self.on_toggle.call(checked, global);
+        }
+        self.on_activate.call((), global);
+    }
+}
+
+#[derive(Default)]
+pub struct Menu {
+    pub entries: Vec<MenuEntry>,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an item and returns it for setting its callbacks and options
+    pub fn add_item(&mut self, label: &str) -> &mut MenuItem {
+        self.entries.push(MenuEntry::Item(MenuItem::new(label)));
+        match self.entries.last_mut() {
+            Some(MenuEntry::Item(item)) => item,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn add_separator(&mut self) {
+        self.entries.push(MenuEntry::Separator);
+    }
+
+    pub fn item(&self, index: usize) -> Option<&MenuItem> {
+        match self.entries.get(index) {
+            Some(MenuEntry::Item(item)) => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn item_mut(&mut self, index: usize) -> Option<&mut MenuItem> {
+        match self.entries.get_mut(index) {
+            Some(MenuEntry::Item(item)) => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Submenu reached by following `path`, a list of item indices, from this menu
+    fn submenu(&self, path: &[usize]) -> Option<&Menu> {
+        path.iter().try_fold(self, |menu, &index| {
+            menu.item(index).and_then(|item| item.submenu.as_ref())
+        })
+    }
+
+    fn submenu_mut(&mut self, path: &[usize]) -> Option<&mut Menu> {
+        path.iter().try_fold(self, |menu, &index| {
+            menu.item_mut(index).and_then(|item| item.submenu.as_mut())
+        })
+    }
+
+    /// Activates the enabled item, here or in a submenu, whose accelerator matches.
+    /// Returns whether there was one
+    pub fn activate_accelerator(
+        &mut self,
+        key: VirtualKeyCode,
+        modifiers: ModifiersState,
+        global: &Global,
+    ) -> bool {
+        self.entries.iter_mut().any(|entry| match entry {
+            MenuEntry::Item(item) if item.enabled => {
+                if let Some(submenu) = &mut item.submenu {
+                    submenu.activate_accelerator(key, modifiers, global)
+                } else if matches!(item.accelerator, Some(accelerator) if accelerator.matches(key, modifiers))
+                {
+                    item.activate(global);
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        })
+    }
+}
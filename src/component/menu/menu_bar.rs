@@ -0,0 +1,253 @@
+use super::{Menu, MenuId};
+use crate::component::{Layout, Text};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{ElementState, Event, KeyboardInput, MouseButton, WindowEvent};
+use nalgebra::{Vector2, Vector4};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct BarMenu {
+    title: Text,
+    menu: Rc<RefCell<Menu>>,
+    /// Title width, fitted to the label
+    width: f32,
+}
+
+/// Row of menu titles shown under a window's title. Clicking a title drops its menu down, and
+/// the accelerators of every item work while the window is active
+pub struct MenuBar {
+    pub layout: Layout,
+
+    menus: Vec<BarMenu>,
+    /// Menu currently dropped down, with the id it was opened under
+    open: Option<(usize, MenuId)>,
+    hovered: Option<usize>,
+    theme: Rc<Theme>,
+}
+
+impl MenuBar {
+    const PADDING: f32 = 8.0;
+
+    pub fn new(global: &Global) -> Self {
+        MenuBar {
+            layout: Layout::default(),
+            menus: Vec::new(),
+            open: None,
+            hovered: None,
+            theme: global.theme(),
+        }
+    }
+
+    /// Appends a menu under `title`, handing back a shared handle for editing it later
+    pub fn add_menu(&mut self, title: &str, menu: Menu, global: &Global) -> Rc<RefCell<Menu>> {
+        let menu = Rc::new(RefCell::new(menu));
+        let mut bar_menu = BarMenu {
+            title: Text::new(global),
+            menu: menu.clone(),
+            width: 0.0,
+        };
+        bar_menu.title.content = title.to_owned();
+        self.menus.push(bar_menu);
+        self.style_menu(self.menus.len() - 1);
+        self.relayout();
+        global.damage(self.layout);
+        menu
+    }
+
+    pub fn len(&self) -> usize {
+        self.menus.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.menus.is_empty()
+    }
+
+    pub fn menu(&self, index: usize) -> Rc<RefCell<Menu>> {
+        self.menus[index].menu.clone()
+    }
+
+    /// Height the bar wants, for the window to lay it out with
+    pub fn height(&self) -> f32 {
+        (self.theme.typography.body_size * 1.25 + Self::PADDING).ceil()
+    }
+
+    fn style_menu(&mut self, index: usize) {
+        let theme = &self.theme;
+        let bar_menu = &mut self.menus[index];
+        bar_menu.title.set_font_size(theme.typography.body_size);
+        bar_menu.title.color = theme.palette.text;
+        bar_menu.width = bar_menu.title.width().ceil() + Self::PADDING * 2.0;
+    }
+
+    fn title_layout(&self, index: usize) -> Layout {
+        let x: f32 = self.menus[..index].iter().map(|menu| menu.width).sum();
+        Layout {
+            position: self.layout.position + Vector2::new(x, 0.0),
+            size: Vector2::new(self.menus[index].width, self.layout.size.y),
+        }
+    }
+
+    fn relayout(&mut self) {
+        let font_size = self.theme.typography.body_size;
+        for index in 0..self.len() {
+            let layout = self.title_layout(index);
+            let title = &mut self.menus[index].title;
+            // wide enough to never wrap
+            let width = title.width().ceil() + 1.0;
+            title.set_layout(Layout {
+                position: layout.position
+                    + Vector2::new(Self::PADDING, (layout.size.y - font_size * 1.25) / 2.0),
+                size: Vector2::new(width, font_size * 1.25),
+            });
+        }
+    }
+
+    fn title_at_cursor(&self, global: &Global) -> Option<usize> {
+        let cursor_position = global.cursor_position();
+        (0..self.len()).find(|&index| self.title_layout(index).contains(&cursor_position))
+    }
+
+    /// Drops menu `index` down under its title
+    fn open(&mut self, index: usize, global: &Global) {
+        let layout = self.title_layout(index);
+        let position = layout.position + Vector2::new(0.0, layout.size.y);
+        let id = global.open_menu(self.menus[index].menu.clone(), position);
+        self.open = Some((index, id));
+        global.damage(self.layout);
+    }
+
+    fn handle_key(&mut self, input: &KeyboardInput, global: &Global) {
+        if let KeyboardInput {
+            state: ElementState::Pressed,
+            virtual_keycode: Some(key),
+            ..
+        } = input
+        {
+            let modifiers = global.modifiers();
+            for bar_menu in &self.menus {
+                if bar_menu
+                    .menu
+                    .borrow_mut()
+                    .activate_accelerator(*key, modifiers, global)
+                {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl Component for MenuBar {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: self.layout,
+            fill: palette.surface.into(),
+            round_radius: 0.0,
+            border_width: 0.0,
+            border_color: Vector4::zeros(),
+        });
+        proxy.with_clip(self.layout, |proxy| {
+            for (index, bar_menu) in self.menus.iter().enumerate() {
+                let fill = if matches!(self.open, Some((open, _)) if open == index) {
+                    let mut fill = palette.accent;
+                    fill.w *= 0.35;
+                    Some(fill)
+                } else if self.hovered == Some(index) {
+                    let mut fill = palette.control_border;
+                    fill.w *= 0.3;
+                    Some(fill)
+                } else {
+                    None
+                };
+                if let Some(fill) = fill {
+                    proxy.draw_rounded_rect(&RoundedRect {
+                        layout: self.title_layout(index),
+                        fill: fill.into(),
+                        round_radius: 0.0,
+                        border_width: 0.0,
+                        border_color: Vector4::zeros(),
+                    });
+                }
+                bar_menu.title.draw(proxy);
+            }
+        });
+    }
+
+    fn update(&mut self, global: &Global) {
+        for bar_menu in &mut self.menus {
+            bar_menu.title.update(global);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                for index in 0..self.len() {
+                    self.style_menu(index);
+                }
+                self.relayout();
+                global.damage(self.layout);
+            }
+            Event::UserEvent(CustomEvent::MenuClosed(id)) => {
+                if matches!(self.open, Some((_, open)) if open == *id) {
+                    self.open = None;
+                    global.damage(self.layout);
+                }
+            }
+            Event::UserEvent(CustomEvent::MenuStep { id, forward }) => {
+                if let Some((index, open)) = self.open {
+                    if open == *id {
+                        let len = self.len();
+                        let index = if *forward {
+                            (index + 1) % len
+                        } else {
+                            (index + len - 1) % len
+                        };
+                        self.open(index, global);
+                    }
+                }
+            }
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::CursorMoved { .. } => {
+                    let hovered = self.title_at_cursor(global);
+                    if self.hovered != hovered {
+                        self.hovered = hovered;
+                        global.damage(self.layout);
+                    }
+                    // while a menu is down, hovering another title switches to its menu
+                    if let (Some(index), Some((open, _))) = (hovered, self.open) {
+                        if index != open {
+                            self.open(index, global);
+                        }
+                    }
+                }
+                WindowEvent::MouseInput {
+                    state: ElementState::Pressed,
+                    button: MouseButton::Left,
+                    ..
+                } => {
+                    if let Some(index) = self.title_at_cursor(global) {
+                        self.open(index, global);
+                    }
+                }
+                WindowEvent::KeyboardInput { input, .. } => self.handle_key(input, global),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.relayout();
+    }
+}
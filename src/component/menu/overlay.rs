@@ -0,0 +1,780 @@
+use super::{Menu, MenuEntry, MenuId, MenuOptions, TypeAhead};
+use crate::component::{Icon, Layout, Text};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::{RoundedRect, Shadow};
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent};
+use nalgebra::{Vector2, Vector4};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const CHECK_MARK: &str = r#"<svg viewBox="0 0 16 16"><path d="M3.5 8.5 L6.5 11.5 L12.5 4.5" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"/></svg>"#;
+const ARROW: &str =
+    r#"<svg viewBox="0 0 16 16"><polygon points="6,4 11,8 6,12" fill="currentColor"/></svg>"#;
+
+/// Change to the open menu, queued through `Global::open_menu` and `Global::close_menu`
+pub(crate) enum MenuRequest {
+    Open {
+        id: MenuId,
+        menu: Rc<RefCell<Menu>>,
+        position: Vector2<f32>,
+        options: MenuOptions,
+    },
+    Close(MenuId),
+    /// Highlights an item of the open menu's top level
+    Highlight(MenuId, Option<usize>),
+    /// Rebuilds the open menu's top level after its items changed
+    Refresh(MenuId),
+}
+
+struct ItemRow {
+    label: Text,
+    accelerator: Option<Text>,
+    check: Option<Icon>,
+    arrow: Option<Icon>,
+    enabled: bool,
+}
+
+struct Row {
+    layout: Layout,
+    /// `None` for separators
+    item: Option<ItemRow>,
+}
+
+impl Row {
+    fn is_selectable(&self) -> bool {
+        matches!(self.item, Some(ItemRow { enabled: true, .. }))
+    }
+
+    fn has_submenu(&self) -> bool {
+        matches!(self.item, Some(ItemRow { arrow: Some(_), .. }))
+    }
+}
+
+/// One open level of the menu
+struct Popup {
+    /// Item indices leading from the root menu to the one shown
+    path: Vec<usize>,
+    layout: Layout,
+    rows: Vec<Row>,
+    highlighted: Option<usize>,
+}
+
+/// Popup menus drawn above every window. Owned by the desktop, which hands it the requests
+/// made through `Global::open_menu` and lets it take input first while a menu is open
+pub(crate) struct MenuOverlay {
+    open: Option<(MenuId, Rc<RefCell<Menu>>)>,
+    /// Root popup followed by the submenus opened from it
+    popups: Vec<Popup>,
+    /// Whether a button went down over a popup, so releasing it there picks an item
+    pressed: bool,
+    /// Whether the open menu takes keyboard input
+    keyboard: bool,
+    /// Narrowest the root popup may be
+    min_width: f32,
+    type_ahead: TypeAhead,
+    /// Check mark and submenu arrow, parsed once and cloned into rows
+    check_mark: RefCell<Icon>,
+    arrow: RefCell<Icon>,
+    theme: Rc<Theme>,
+}
+
+impl MenuOverlay {
+    const PADDING: f32 = 4.0;
+    const ITEM_PADDING: f32 = 8.0;
+    const ICON_SIZE: f32 = 16.0;
+    /// Gap between the longest label and the accelerators
+    const ACCELERATOR_SPACING: f32 = 24.0;
+    const SEPARATOR_HEIGHT: f32 = 9.0;
+    const MIN_WIDTH: f32 = 120.0;
+    const SHADOW_OFFSET: f32 = 2.0;
+    const SHADOW_BLUR_RADIUS: f32 = 8.0;
+
+    pub fn new(global: &Global) -> Self {
+        MenuOverlay {
+            open: None,
+            popups: Vec::new(),
+            pressed: false,
+            keyboard: true,
+            min_width: 0.0,
+            type_ahead: TypeAhead::default(),
+            check_mark: RefCell::new(
+                Icon::from_str(CHECK_MARK, global).expect("invalid built-in icon"),
+            ),
+            arrow: RefCell::new(Icon::from_str(ARROW, global).expect("invalid built-in icon")),
+            theme: global.theme(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    pub fn handle_request(&mut self, request: MenuRequest, global: &Global) {
+        match request {
+            MenuRequest::Open {
+                id,
+                menu,
+                position,
+                options,
+            } => self.open(id, menu, position, options, global),
+            MenuRequest::Close(id) if self.is_showing(id) => self.close(global),
+            MenuRequest::Highlight(id, index) if self.is_showing(id) => {
+                let index = index.filter(|&index| {
+                    self.popups[0]
+                        .rows
+                        .get(index)
+                        .is_some_and(Row::is_selectable)
+                });
+                self.highlight(0, index, global);
+            }
+            MenuRequest::Refresh(id) if self.is_showing(id) => self.refresh(global),
+            _ => {}
+        }
+    }
+
+    /// Whether menu `id` is the one open
+    fn is_showing(&self, id: MenuId) -> bool {
+        matches!(self.open, Some((open, _)) if open == id)
+    }
+
+    /// Shows `menu`, closing the one already open
+    fn open(
+        &mut self,
+        id: MenuId,
+        menu: Rc<RefCell<Menu>>,
+        position: Vector2<f32>,
+        options: MenuOptions,
+        global: &Global,
+    ) {
+        self.close(global);
+        self.keyboard = options.keyboard;
+        self.min_width = options.min_width;
+        self.type_ahead = TypeAhead::default();
+        let mut popup = self.build_popup(
+            &menu.borrow(),
+            Vec::new(),
+            position,
+            None,
+            self.min_width,
+            global,
+        );
+        popup.highlighted = options
+            .highlighted
+            .filter(|&index| popup.rows.get(index).is_some_and(Row::is_selectable));
+        global.damage(self.bounds(&popup));
+        self.popups.push(popup);
+        self.open = Some((id, menu));
+    }
+
+    pub fn close(&mut self, global: &Global) {
+        if let Some((id, _)) = self.open.take() {
+            self.truncate(0, global);
+            self.pressed = false;
+            let _ = global.send_event(CustomEvent::MenuClosed(id));
+        }
+    }
+
+    fn row_height(&self) -> f32 {
+        (self.theme.typography.body_size * 1.25 + Self::ITEM_PADDING).ceil()
+    }
+
+    fn screen_size(global: &Global) -> Vector2<f32> {
+        let size = global.render_context.frame_buffer_size();
+        let scale_factor = global.render_context.scale_factor() as f32;
+        Vector2::new(size.width as f32, size.height as f32) / scale_factor
+    }
+
+    fn text(&self, content: &str, color: Vector4<f32>, global: &Global) -> Text {
+        let mut text = Text::new(global);
+        text.content = content.to_owned();
+        text.set_font_size(self.theme.typography.body_size);
+        text.color = color;
+        text
+    }
+
+    /// Copy of `icon` in `color`, sharing its bitmap with the rows before it in that color
+    fn icon(icon: &RefCell<Icon>, color: Vector4<f32>, global: &Global) -> Icon {
+        let mut icon = icon.borrow_mut();
+        icon.set_color(color);
+        icon.rasterize_at(
+            Vector2::new(Self::ICON_SIZE, Self::ICON_SIZE),
+            global.scale_factor,
+        );
+        icon.clone()
+    }
+
+    /// Lays out `menu` with its top left corner at `position`, moving it onto the screen. A
+    /// popup that overflows the right edge is placed to the left of `flip_x` when given
+    fn build_popup(
+        &self,
+        menu: &Menu,
+        path: Vec<usize>,
+        position: Vector2<f32>,
+        flip_x: Option<f32>,
+        min_width: f32,
+        global: &Global,
+    ) -> Popup {
+        let palette = &self.theme.palette;
+        let mut rows = Vec::with_capacity(menu.entries.len());
+        let mut label_width = 0.0f32;
+        let mut accelerator_width = 0.0f32;
+        for entry in &menu.entries {
+            let row = match entry {
+                MenuEntry::Separator => Row {
+                    layout: Layout::default(),
+                    item: None,
+                },
+                MenuEntry::Item(item) => {
+                    let color = if item.enabled {
+                        palette.text
+                    } else {
+                        palette.disabled_text
+                    };
+                    let label = self.text(&item.label, color, global);
+                    label_width = label_width.max(label.width().ceil());
+                    let accelerator = item.accelerator.map(|accelerator| {
+                        let text =
+                            self.text(&accelerator.to_string(), palette.disabled_text, global);
+                        accelerator_width = accelerator_width.max(text.width().ceil());
+                        text
+                    });
+                    let check = match item.checked {
+                        Some(true) => Some(Self::icon(&self.check_mark, color, global)),
+                        _ => None,
+                    };
+                    let arrow = item
+                        .submenu
+                        .as_ref()
+                        .map(|_| Self::icon(&self.arrow, color, global));
+                    Row {
+                        layout: Layout::default(),
+                        item: Some(ItemRow {
+                            label,
+                            accelerator,
+                            check,
+                            arrow,
+                            enabled: item.enabled,
+                        }),
+                    }
+                }
+            };
+            rows.push(row);
+        }
+
+        let accelerator_width = if accelerator_width > 0.0 {
+            accelerator_width + Self::ACCELERATOR_SPACING
+        } else {
+            0.0
+        };
+        let width =
+            (label_width + accelerator_width + Self::ICON_SIZE * 2.0 + Self::ITEM_PADDING * 4.0)
+                .max(Self::MIN_WIDTH)
+                .max(min_width);
+        let row_height = self.row_height();
+        let height = rows
+            .iter()
+            .map(|row| match row.item {
+                Some(_) => row_height,
+                None => Self::SEPARATOR_HEIGHT,
+            })
+            .sum::<f32>()
+            + Self::PADDING * 2.0;
+
+        let screen = Self::screen_size(global);
+        let mut position = position;
+        if position.x + width > screen.x {
+            position.x = match flip_x {
+                Some(flip_x) => flip_x - width,
+                None => screen.x - width,
+            };
+        }
+        if position.y + height > screen.y {
+            position.y = screen.y - height;
+        }
+        let position = position.sup(&Vector2::zeros());
+
+        let font_size = self.theme.typography.body_size;
+        let mut y = position.y + Self::PADDING;
+        for row in &mut rows {
+            let height = match row.item {
+                Some(_) => row_height,
+                None => Self::SEPARATOR_HEIGHT,
+            };
+            row.layout = Layout {
+                position: Vector2::new(position.x, y),
+                size: Vector2::new(width, height),
+            };
+            if let Some(ItemRow {
+                label,
+                accelerator,
+                check,
+                arrow,
+                ..
+            }) = &mut row.item
+            {
+                let text_y = y + (row_height - font_size * 1.25) / 2.0;
+                let icon_y = y + (row_height - Self::ICON_SIZE) / 2.0;
+                let icon_size = Vector2::new(Self::ICON_SIZE, Self::ICON_SIZE);
+                // wide enough to never wrap
+                label.set_layout(Layout {
+                    position: Vector2::new(
+                        position.x + Self::ITEM_PADDING * 2.0 + Self::ICON_SIZE,
+                        text_y,
+                    ),
+                    size: Vector2::new(label.width().ceil() + 1.0, font_size * 1.25),
+                });
+                if let Some(accelerator) = accelerator {
+                    let accelerator_width = accelerator.width().ceil() + 1.0;
+                    accelerator.set_layout(Layout {
+                        position: Vector2::new(
+                            position.x + width
+                                - Self::ITEM_PADDING * 2.0
+                                - Self::ICON_SIZE
+                                - accelerator_width,
+                            text_y,
+                        ),
+                        size: Vector2::new(accelerator_width, font_size * 1.25),
+                    });
+                }
+                if let Some(check) = check {
+                    check.set_layout(Layout {
+                        position: Vector2::new(position.x + Self::ITEM_PADDING, icon_y),
+                        size: icon_size,
+                    });
+                }
+                if let Some(arrow) = arrow {
+                    arrow.set_layout(Layout {
+                        position: Vector2::new(
+                            position.x + width - Self::ITEM_PADDING - Self::ICON_SIZE,
+                            icon_y,
+                        ),
+                        size: icon_size,
+                    });
+                }
+            }
+            y += height;
+        }
+
+        Popup {
+            path,
+            layout: Layout {
+                position,
+                size: Vector2::new(width, height),
+            },
+            rows,
+            highlighted: None,
+        }
+    }
+
+    fn shadow(&self, popup: &Popup) -> Shadow {
+        Shadow {
+            layout: popup.layout,
+            round_radius: self.theme.radii.control,
+            offset: Vector2::new(0.0, Self::SHADOW_OFFSET),
+            blur_radius: Self::SHADOW_BLUR_RADIUS,
+            spread: 0.0,
+            color: self.theme.palette.window_shadow,
+            inset: false,
+        }
+    }
+
+    /// Area covered by a popup and its shadow
+    fn bounds(&self, popup: &Popup) -> Layout {
+        popup.layout.union(&self.shadow(popup).bounds())
+    }
+
+    /// Closes the popups past the first `len` ones
+    fn truncate(&mut self, len: usize, global: &Global) {
+        for popup in self.popups.iter().skip(len) {
+            global.damage(self.bounds(popup));
+        }
+        self.popups.truncate(len);
+    }
+
+    fn highlight(&mut self, level: usize, index: Option<usize>, global: &Global) {
+        self.truncate(level + 1, global);
+        let popup = &mut self.popups[level];
+        if popup.highlighted != index {
+            popup.highlighted = index;
+            global.damage(popup.layout);
+        }
+    }
+
+    /// Opens the submenu of the item highlighted at `level`, if it has one
+    fn open_submenu(&mut self, level: usize, global: &Global) -> bool {
+        let root = match &self.open {
+            Some((_, root)) => root.clone(),
+            None => return false,
+        };
+        let popup = &self.popups[level];
+        let index = match popup.highlighted {
+            Some(index) if popup.rows[index].has_submenu() => index,
+            _ => return false,
+        };
+        let mut path = popup.path.clone();
+        path.push(index);
+        let row = popup.rows[index].layout;
+        let position = Vector2::new(
+            popup.layout.position.x + popup.layout.size.x,
+            row.position.y - Self::PADDING,
+        );
+        let flip_x = popup.layout.position.x;
+
+        let root = root.borrow();
+        let submenu = match root.submenu(&path) {
+            Some(submenu) => submenu,
+            None => return false,
+        };
+        self.truncate(level + 1, global);
+        let popup = self.build_popup(submenu, path, position, Some(flip_x), 0.0, global);
+        global.damage(self.bounds(&popup));
+        self.popups.push(popup);
+        true
+    }
+
+    /// Picks the item highlighted at `level`, opening it instead if it holds a submenu
+    fn activate(&mut self, level: usize, global: &Global) {
+        if self.open_submenu(level, global) {
+            self.step(level + 1, true, global);
+            return;
+        }
+        let popup = &self.popups[level];
+        let index = match popup.highlighted {
+            Some(index) if popup.rows[index].is_selectable() => index,
+            _ => return,
+        };
+        let path = popup.path.clone();
+        if let Some((_, root)) = self.open.clone() {
+            if let Some(item) = root
+                .borrow_mut()
+                .submenu_mut(&path)
+                .and_then(|menu| menu.item_mut(index))
+            {
+                item.activate(global);
+            }
+        }
+        self.close(global);
+    }
+
+    /// Moves the highlight at `level` to the next or previous selectable row, wrapping around
+    fn step(&mut self, level: usize, forward: bool, global: &Global) {
+        let popup = &self.popups[level];
+        let len = popup.rows.len();
+        let start = match (popup.highlighted, forward) {
+            (Some(index), _) => index,
+            (None, true) => len.saturating_sub(1),
+            (None, false) => 0,
+        };
+        let next = (1..=len)
+            .map(|offset| {
+                if forward {
+                    (start + offset) % len
+                } else {
+                    (start + len - offset) % len
+                }
+            })
+            .find(|&index| popup.rows[index].is_selectable());
+        if next.is_some() {
+            self.highlight(level, next, global);
+        }
+    }
+
+    fn popup_at(&self, global: &Global) -> Option<usize> {
+        let cursor_position = global.cursor_position();
+        self.popups
+            .iter()
+            .rposition(|popup| popup.layout.contains(&cursor_position))
+    }
+
+    fn row_at(&self, level: usize, global: &Global) -> Option<usize> {
+        let cursor_position = global.cursor_position();
+        self.popups[level]
+            .rows
+            .iter()
+            .position(|row| row.is_selectable() && row.layout.contains(&cursor_position))
+    }
+
+    /// Highlights the next item in the innermost popup whose label starts with what was typed
+    fn search(&mut self, c: char, global: &Global) {
+        let level = self.popups.len() - 1;
+        let popup = &self.popups[level];
+        let labels: Vec<_> = popup
+            .rows
+            .iter()
+            .map(|row| match &row.item {
+                Some(item) if item.enabled => Some(item.label.content.as_str()),
+                _ => None,
+            })
+            .collect();
+        if let Some(index) = self.type_ahead.find(c, &labels, popup.highlighted) {
+            self.highlight(level, Some(index), global);
+        }
+    }
+
+    fn handle_key(&mut self, input: &KeyboardInput, global: &Global) {
+        let key = match input {
+            KeyboardInput {
+                state: ElementState::Pressed,
+                virtual_keycode: Some(key),
+                ..
+            } => *key,
+            _ => return,
+        };
+        let level = self.popups.len() - 1;
+        let id = match &self.open {
+            Some((id, _)) => *id,
+            None => return,
+        };
+        match key {
+            VirtualKeyCode::Down => self.step(level, true, global),
+            VirtualKeyCode::Up => self.step(level, false, global),
+            VirtualKeyCode::Home => {
+                self.popups[level].highlighted = None;
+                self.step(level, true, global);
+            }
+            VirtualKeyCode::End => {
+                self.popups[level].highlighted = None;
+                self.step(level, false, global);
+            }
+            VirtualKeyCode::Right => {
+                if self.open_submenu(level, global) {
+                    self.step(level + 1, true, global);
+                } else {
+                    let _ = global.send_event(CustomEvent::MenuStep { id, forward: true });
+                }
+            }
+            VirtualKeyCode::Left if level > 0 => self.truncate(level, global),
+            VirtualKeyCode::Left => {
+                let _ = global.send_event(CustomEvent::MenuStep { id, forward: false });
+            }
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => self.activate(level, global),
+            VirtualKeyCode::Escape if level > 0 => self.truncate(level, global),
+            VirtualKeyCode::Escape => self.close(global),
+            _ => {}
+        }
+    }
+
+    /// Handles `event` while a menu is open, returning whether it was used up so that windows
+    /// below shouldn't see it
+    pub fn handle_event(&mut self, event: EventProxy, global: &Global) -> bool {
+        let event = match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                self.restyle(global);
+                return false;
+            }
+            Event::WindowEvent { event, .. } if self.is_open() => event,
+            _ => return false,
+        };
+        match event {
+            WindowEvent::CursorMoved { .. } => match self.popup_at(global) {
+                Some(level) => {
+                    let index = self.row_at(level, global);
+                    if self.popups[level].highlighted != index {
+                        self.highlight(level, index, global);
+                        self.open_submenu(level, global);
+                    }
+                    true
+                }
+                None => false,
+            },
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                ..
+            } => {
+                if self.popup_at(global).is_some() {
+                    self.pressed = true;
+                } else {
+                    self.close(global);
+                }
+                true
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                ..
+            } => {
+                // the release ending the press that opened the menu goes to whoever got the press
+                if !std::mem::replace(&mut self.pressed, false) {
+                    return false;
+                }
+                if let Some(level) = self.popup_at(global) {
+                    if let Some(index) = self.row_at(level, global) {
+                        self.highlight(level, Some(index), global);
+                        self.activate(level, global);
+                    }
+                }
+                true
+            }
+            WindowEvent::KeyboardInput { input, .. } if self.keyboard => {
+                self.handle_key(input, global);
+                true
+            }
+            WindowEvent::ReceivedCharacter(c) if self.keyboard => {
+                if !c.is_control() {
+                    self.search(*c, global);
+                }
+                true
+            }
+            WindowEvent::MouseWheel { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Rebuilds the open popups in the current theme where they are
+    /// Rebuilds the root popup where it is from the menu's current items, closing submenus
+    fn refresh(&mut self, global: &Global) {
+        let root = match &self.open {
+            Some((_, root)) => root.clone(),
+            None => return,
+        };
+        let position = self.popups[0].layout.position;
+        self.truncate(0, global);
+        let popup = self.build_popup(
+            &root.borrow(),
+            Vec::new(),
+            position,
+            None,
+            self.min_width,
+            global,
+        );
+        global.damage(self.bounds(&popup));
+        self.popups.push(popup);
+    }
+
+    fn restyle(&mut self, global: &Global) {
+        let root = match &self.open {
+            Some((_, root)) => root.clone(),
+            None => return,
+        };
+        let root = root.borrow();
+        let old = std::mem::take(&mut self.popups);
+        for popup in &old {
+            global.damage(self.bounds(popup));
+        }
+        for old in old {
+            let menu = match root.submenu(&old.path) {
+                Some(menu) => menu,
+                None => break,
+            };
+            let min_width = if old.path.is_empty() {
+                self.min_width
+            } else {
+                0.0
+            };
+            let mut popup =
+                self.build_popup(menu, old.path, old.layout.position, None, min_width, global);
+            popup.highlighted = old.highlighted;
+            global.damage(self.bounds(&popup));
+            self.popups.push(popup);
+        }
+    }
+}
+
+impl Component for MenuOverlay {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        let round_radius = self.theme.radii.control;
+        for popup in &self.popups {
+            proxy.draw_shadow(&self.shadow(popup));
+            proxy.draw_rounded_rect(&RoundedRect {
+                layout: popup.layout,
+                fill: palette.window_background.into(),
+                round_radius,
+                border_width: 1.0,
+                border_color: palette.control_border,
+            });
+
+            proxy.with_clip(popup.layout, |proxy| {
+                for (index, row) in popup.rows.iter().enumerate() {
+                    let layout = row.layout;
+                    match &row.item {
+                        None => {
+                            proxy.draw_rounded_rect(&RoundedRect {
+                                layout: Layout {
+                                    position: layout.position
+                                        + Vector2::new(
+                                            Self::ITEM_PADDING,
+                                            (layout.size.y / 2.0).floor(),
+                                        ),
+                                    size: Vector2::new(
+                                        layout.size.x - Self::ITEM_PADDING * 2.0,
+                                        1.0,
+                                    ),
+                                },
+                                fill: palette.control_border.into(),
+                                round_radius: 0.0,
+                                border_width: 0.0,
+                                border_color: Vector4::zeros(),
+                            });
+                        }
+                        Some(ItemRow {
+                            label,
+                            accelerator,
+                            check,
+                            arrow,
+                            ..
+                        }) => {
+                            if popup.highlighted == Some(index) {
+                                let mut highlight = palette.accent;
+                                highlight.w *= 0.35;
+                                proxy.draw_rounded_rect(&RoundedRect {
+                                    layout: Layout {
+                                        position: layout.position
+                                            + Vector2::new(Self::PADDING, 0.0),
+                                        size: layout.size - Vector2::new(Self::PADDING * 2.0, 0.0),
+                                    },
+                                    fill: highlight.into(),
+                                    round_radius,
+                                    border_width: 0.0,
+                                    border_color: Vector4::zeros(),
+                                });
+                            }
+                            label.draw(proxy);
+                            if let Some(accelerator) = accelerator {
+                                accelerator.draw(proxy);
+                            }
+                            if let Some(check) = check {
+                                check.draw(proxy);
+                            }
+                            if let Some(arrow) = arrow {
+                                arrow.draw(proxy);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        for popup in &mut self.popups {
+            for row in &mut popup.rows {
+                if let Some(ItemRow {
+                    label,
+                    accelerator,
+                    check,
+                    arrow,
+                    ..
+                }) = &mut row.item
+                {
+                    label.update(global);
+                    if let Some(accelerator) = accelerator {
+                        accelerator.update(global);
+                    }
+                    if let Some(check) = check {
+                        check.update(global);
+                    }
+                    if let Some(arrow) = arrow {
+                        arrow.update(global);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::component::{BoxShadow, Layout, Plane, Text};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{ElementState, Event, KeyboardInput, WindowEvent};
+use nalgebra::Vector2;
+
+use std::rc::Rc;
+use std::time::Instant;
+
+/// Tooltip a component registered through `Global::set_tooltip`
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TooltipRequest {
+    pub layout: Layout,
+    pub text: String,
+}
+
+/// Bubble showing the tooltip of whatever the pointer rests over, above everything else
+pub(crate) struct TooltipOverlay {
+    /// Tooltip under the pointer, with when the pointer arrived
+    hovered: Option<(TooltipRequest, Instant)>,
+    visible: bool,
+    /// Layout whose tooltip was dismissed by a click, not shown again until the pointer leaves
+    dismissed: Option<Layout>,
+    background: Plane,
+    text: Text,
+    theme: Rc<Theme>,
+}
+
+impl TooltipOverlay {
+    const PADDING: f32 = 6.0;
+    /// Gap between the cursor and the bubble below it, leaving room for the pointer
+    const CURSOR_OFFSET: f32 = 20.0;
+    const SHADOW_OFFSET: f32 = 2.0;
+    const SHADOW_BLUR_RADIUS: f32 = 6.0;
+
+    pub fn new(global: &Global) -> Self {
+        let mut overlay = TooltipOverlay {
+            hovered: None,
+            visible: false,
+            dismissed: None,
+            background: Plane::new(global),
+            text: Text::new(global),
+            theme: global.theme(),
+        };
+        overlay.restyle();
+        overlay
+    }
+
+    fn restyle(&mut self) {
+        let theme = &self.theme;
+        self.background.fill = theme.palette.window_background.into();
+        self.background.round_radius = theme.radii.control;
+        self.background.border_width = 1.0;
+        self.background.border_color = theme.palette.control_border;
+        self.background.shadows = vec![BoxShadow {
+            offset: Vector2::new(0.0, Self::SHADOW_OFFSET),
+            blur_radius: Self::SHADOW_BLUR_RADIUS,
+            spread: 0.0,
+            color: theme.palette.window_shadow,
+            inset: false,
+        }];
+        self.text.set_font_size(theme.typography.body_size);
+        self.text.color = theme.palette.text;
+    }
+
+    /// Follows the tooltip registered while the last `CursorMoved` was handled. The delay
+    /// restarts whenever the pointer moves onto a different one
+    pub fn hover(&mut self, request: Option<TooltipRequest>, global: &Global) {
+        if self.dismissed.is_some() && self.dismissed != request.as_ref().map(|r| r.layout) {
+            self.dismissed = None;
+        }
+        if matches!((&self.hovered, &request), (Some((hovered, _)), Some(request)) if hovered == request)
+        {
+            return;
+        }
+        self.hide(global);
+        self.hovered = request
+            .filter(|request| Some(request.layout) != self.dismissed)
+            .map(|request| (request, Instant::now()));
+    }
+
+    /// Hides the tooltip until the pointer leaves what it belongs to
+    fn dismiss(&mut self, global: &Global) {
+        if let Some((hovered, _)) = self.hovered.take() {
+            self.dismissed = Some(hovered.layout);
+        }
+        self.hide(global);
+    }
+
+    fn hide(&mut self, global: &Global) {
+        if self.visible {
+            self.visible = false;
+            global.damage(self.background.bounds());
+        }
+    }
+
+    /// Places the bubble under the cursor, or above it near the bottom, within the screen
+    fn show(&mut self, text: &str, global: &Global) {
+        self.text.content = text.to_owned();
+        let size = global.render_context.frame_buffer_size();
+        let screen = Vector2::new(size.width as f32, size.height as f32)
+            / global.render_context.scale_factor() as f32;
+        let line_height = self.theme.typography.body_size * 1.25;
+        let max_width = screen.x - Self::PADDING * 2.0;
+        // wide enough to never wrap
+        let text_size = Vector2::new((self.text.width().ceil() + 1.0).min(max_width), line_height);
+        let size = text_size + Vector2::repeat(Self::PADDING * 2.0);
+
+        let cursor = global.cursor_position();
+        let cursor = Vector2::new(cursor.x as f32, cursor.y as f32);
+        let x = cursor.x.min(screen.x - size.x).max(0.0);
+        let y = if cursor.y + Self::CURSOR_OFFSET + size.y > screen.y {
+            cursor.y - size.y - Self::PADDING
+        } else {
+            cursor.y + Self::CURSOR_OFFSET
+        };
+        let position = Vector2::new(x, y.max(0.0));
+
+        self.background.set_layout(Layout { position, size });
+        self.text.set_layout(Layout {
+            position: position + Vector2::repeat(Self::PADDING),
+            size: text_size,
+        });
+        self.visible = true;
+        global.damage(self.background.bounds());
+    }
+}
+
+impl Component for TooltipOverlay {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        if self.visible {
+            self.background.draw(proxy);
+            proxy.with_clip(self.background.layout, |proxy| self.text.draw(proxy));
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        if let Some((hovered, since)) = &self.hovered {
+            if !self.visible {
+                let due = *since + global.config().tooltip.delay();
+                if Instant::now() >= due {
+                    let text = hovered.text.clone();
+                    self.show(&text, global);
+                } else {
+                    global.wake_up_at(due);
+                }
+            }
+        }
+        self.background.update(global);
+        self.text.update(global);
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                self.restyle();
+                self.hide(global);
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        ..
+                    }
+                    | WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => self.dismiss(global),
+            _ => {}
+        }
+    }
+}
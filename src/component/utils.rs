@@ -1,11 +1,40 @@
 mod button;
+mod checkbox;
 mod cursor;
+mod dropdown;
+mod icon;
+mod image;
 mod input;
+mod interaction;
+mod list_view;
 mod plane;
+mod progress_bar;
+mod radio_group;
+mod scroll_view;
+mod slider;
+mod spin_box;
+mod spinner;
+mod switch;
+mod tab_view;
 mod text;
+mod tree_view;
 
-pub use button::Button;
+pub use button::{Button, ButtonState, ButtonStyle};
+pub use checkbox::Checkbox;
 pub use cursor::Cursor;
+pub use dropdown::Dropdown;
+pub use icon::Icon;
+pub use image::{Image, ImageFit};
 pub use input::Input;
-pub use plane::Plane;
+pub use list_view::{ListRow, ListSource, ListView, SelectionMode};
+pub use plane::{BoxShadow, Plane};
+pub use progress_bar::ProgressBar;
+pub use radio_group::RadioGroup;
+pub use scroll_view::ScrollView;
+pub use slider::Slider;
+pub use spin_box::SpinBox;
+pub use spinner::Spinner;
+pub use switch::Switch;
+pub use tab_view::TabView;
 pub use text::Text;
+pub use tree_view::{TreeProvider, TreeView};
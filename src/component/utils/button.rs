@@ -1,101 +1,159 @@
+use super::interaction::Interaction;
 use super::Text;
+use crate::component::{Callback, Insets};
+use crate::render_context::RoundedRect;
 use crate::RenderContextProxy;
+use crate::Theme;
 use crate::{component::Layout, custom_event::CustomEvent};
 
-use glium::{
-    draw_parameters::DrawParameters,
-    glutin::event::WindowEvent,
-    glutin::event::{ElementState, Event},
-    implement_vertex,
-    index::NoIndices,
-    index::PrimitiveType,
-    uniform, Display, Program, Surface, VertexBuffer,
-};
-use nalgebra::Vector2;
+use glium::glutin::event::{Event, WindowEvent};
+use nalgebra::{Vector2, Vector4};
 
 use crate::Component;
 use crate::Global;
 
-#[derive(Copy, Clone)]
-pub struct Vertex {
-    a_position: [f32; 2],
+/// Where a button is in its press/release cycle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonState {
+    Normal,
+    Hovered,
+    /// Pressed inside and not yet released; leaving the bounds cancels the click
+    Pressed,
+    Disabled,
 }
 
-implement_vertex!(Vertex, a_position);
+/// Look of a button in one `ButtonState`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ButtonStyle {
+    pub background: Vector4<f32>,
+    pub text: Vector4<f32>,
+    pub border_width: f32,
+    pub border_color: Vector4<f32>,
+    /// Ring drawn inside the edge while the button has keyboard focus
+    pub focus_ring_width: f32,
+    pub focus_ring_color: Vector4<f32>,
+}
 
 pub struct Button {
     pub text: Text,
     pub layout: Layout,
     pub visibility: bool,
     pub event_on_click: Option<CustomEvent>,
+    /// Invoked while the releasing event is handled, before `event_on_click` reaches the loop
+    pub on_click: Callback<()>,
     pub round_radius: f32,
-
-    pressed: bool,
-    should_redraw: bool,
-    display: Display,
-    program: Program,
-    vbo: Option<VertexBuffer<Vertex>>,
+    pub normal_style: ButtonStyle,
+    pub hovered_style: ButtonStyle,
+    pub pressed_style: ButtonStyle,
+    pub disabled_style: ButtonStyle,
+    /// Shown after the pointer rests over the button, disabled or not
+    pub tooltip: Option<String>,
+
+    enabled: bool,
+    interaction: Interaction,
+    frame_width: f32,
 }
 
 impl Button {
-    const VSRC: &'static str = include_str!("button/button.vert");
-    const FSRC: &'static str = include_str!("button/button.frag");
-    const FRAME_WIDTH: f32 = 3.0;
-
     pub fn new(global: &Global) -> Self {
         let text = Text::new(global);
-        let display = global.render_context.display().clone();
-        let program = Program::from_source(global.display(), Self::VSRC, Self::FSRC, None).unwrap();
-        let vbo = None;
-        let round_radius = 10.0;
+        let style = ButtonStyle {
+            background: Vector4::zeros(),
+            text: Vector4::zeros(),
+            border_width: 0.0,
+            border_color: Vector4::new(0.0, 0.0, 0.0, 1.0),
+            focus_ring_width: 2.0,
+            focus_ring_color: Vector4::zeros(),
+        };
 
-        Button {
+        let mut button = Button {
             text,
             layout: Layout::default(),
             visibility: true,
             event_on_click: None,
-            round_radius,
+            on_click: Callback::new(),
+            round_radius: 0.0,
+            normal_style: style,
+            hovered_style: style,
+            pressed_style: style,
+            disabled_style: style,
+            tooltip: None,
+
+            enabled: true,
+            interaction: Interaction::default(),
+            frame_width: 0.0,
+        };
+        button.apply_theme(&global.theme());
+        button
+    }
 
-            pressed: false,
-            should_redraw: true,
-            display,
-            program,
-            vbo,
-        }
+    fn apply_theme(&mut self, theme: &Theme) {
+        let palette = &theme.palette;
+        let base = self.normal_style;
+        let style = |background, text| ButtonStyle {
+            background,
+            text,
+            focus_ring_color: palette.accent,
+            ..base
+        };
+        self.normal_style = style(palette.button_background, palette.button_text);
+        self.hovered_style = style(palette.button_hovered, palette.button_text);
+        self.pressed_style = style(palette.button_pressed, palette.button_text);
+        self.disabled_style = style(palette.button_disabled, palette.disabled_text);
+        self.round_radius = theme.radii.control;
+        self.frame_width = theme.button_frame_width;
+        self.set_font_size(theme.typography.body_size);
     }
 
     pub fn set_font_size(&mut self, font_size: f32) {
         self.text.set_font_size(font_size);
-        self.text.update_vbo();
     }
 
-    pub fn update_vbo(&mut self) {
-        if !self.should_redraw {
-            return;
+    pub fn state(&self) -> ButtonState {
+        if !self.enabled {
+            ButtonState::Disabled
+        } else if self.interaction.pressed {
+            ButtonState::Pressed
+        } else if self.interaction.hovered {
+            ButtonState::Hovered
+        } else {
+            ButtonState::Normal
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.interaction.focused
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Disabling drops focus and cancels a press in progress
+    pub fn set_enabled(&mut self, enabled: bool, global: &Global) {
+        if self.enabled != enabled {
+            self.enabled = enabled;
+            self.interaction.reset();
+            self.interaction.hovered = enabled && self.is_cursor_hovering(global);
+            global.damage(self.layout);
+        }
+    }
+
+    /// Style of the current state
+    pub fn style(&self) -> &ButtonStyle {
+        match self.state() {
+            ButtonState::Normal => &self.normal_style,
+            ButtonState::Hovered => &self.hovered_style,
+            ButtonState::Pressed => &self.pressed_style,
+            ButtonState::Disabled => &self.disabled_style,
+        }
+    }
+
+    fn click(&mut self, global: &Global) {
+        self.on_click.call((), global);
+        if let Some(e) = &self.event_on_click {
+            let _ = global.send_event(e.clone());
         }
-        let left = 0.0;
-        let right = self.layout.size.x;
-        let down = 0.0;
-        let up = self.layout.size.y;
-        let vbo = VertexBuffer::new(
-            &self.display,
-            &[
-                Vertex {
-                    a_position: [left, down],
-                },
-                Vertex {
-                    a_position: [right, down],
-                },
-                Vertex {
-                    a_position: [right, up],
-                },
-                Vertex {
-                    a_position: [left, up],
-                },
-            ],
-        )
-        .expect("failed to create vbo");
-        self.vbo = Some(vbo);
     }
 
     fn is_cursor_hovering(&self, global: &Global) -> bool {
@@ -105,70 +163,78 @@ impl Button {
 
 impl Component for Button {
     fn draw(&self, proxy: &mut RenderContextProxy) {
-        if self.visibility {
-            let center: [f32; 2] = (self.layout.position + 0.5 * self.layout.size).into();
-            let scale_factor = proxy.scale_factor() as f32;
-            let resolution: [f32; 2] = proxy.frame_buffer_size().into();
-            let frame = proxy.frame();
-            let position: [f32; 2] = self.layout.position.into();
-            let button_size: [f32; 2] = self.layout.size.into();
-            frame
-                .draw(
-                    self.vbo.as_ref().expect("vbo not initialized"),
-                    NoIndices(PrimitiveType::TriangleFan),
-                    &self.program,
-                    &uniform! {
-                        u_pressed: self.pressed,
-                        u_center_position: center,
-                        u_scale_factor: scale_factor,
-                        u_position: position,
-                        u_button_size: button_size,
-                        u_resolution: resolution,
-                        u_round_radius: self.round_radius,
-                        u_color: [1.0f32, 1.0, 0.5, 1.0],
-                    },
-                    &DrawParameters::default(),
-                )
-                .expect("failed to draw");
+        if !self.visibility {
+            return;
         }
-        self.text.draw(proxy);
+
+        let style = self.style();
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: self.layout,
+            fill: style.background.into(),
+            round_radius: self.round_radius,
+            border_width: style.border_width,
+            border_color: style.border_color,
+        });
+        if self.interaction.focused {
+            proxy.draw_rounded_rect(&RoundedRect {
+                layout: self.layout,
+                fill: Vector4::zeros().into(),
+                round_radius: self.round_radius,
+                border_width: style.focus_ring_width,
+                border_color: style.focus_ring_color,
+            });
+        }
+        proxy.with_clip(self.layout, |proxy| self.text.draw(proxy));
     }
 
     fn handle_event(&mut self, event: &Event<'_, CustomEvent>, global: &Global) {
-        match event {
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::MouseInput { state, .. } => match state {
-                    ElementState::Pressed if self.is_cursor_hovering(global) => {
-                        if let Some(e) = &self.event_on_click {
-                            let _ = global.send_event(e.clone());
-                        }
-
-                        self.pressed = true;
-                        global.request_redraw()
-                    }
-                    ElementState::Released => {
-                        self.pressed = false;
-                        global.request_redraw();
-                    }
-                    _ => {}
-                },
+        if let Event::UserEvent(CustomEvent::ThemeChanged) = event {
+            self.apply_theme(&global.theme());
+            self.set_layout(self.layout);
+            global.damage(self.layout);
+            return;
+        }
+
+        if let Event::WindowEvent {
+            event: WindowEvent::CursorMoved { .. },
+            ..
+        } = event
+        {
+            match &self.tooltip {
+                Some(tooltip) if self.visibility && self.is_cursor_hovering(global) => {
+                    global.set_tooltip(self.layout, tooltip)
+                }
                 _ => {}
-            },
+            }
+        }
+
+        match event {
+            Event::WindowEvent { event, .. } if self.visibility && self.enabled => {
+                let activated = self.interaction.handle_event(event, &self.layout, global);
+                if activated {
+                    self.click(global);
+                }
+            }
             _ => {}
         }
     }
 
     fn update(&mut self, global: &Global) {
-        self.update_vbo();
+        let color = self.style().text;
+        if self.text.color != color {
+            self.text.color = color;
+            global.damage(self.layout);
+        }
         self.text.update(global);
     }
 
     fn set_layout(&mut self, layout: Layout) {
         self.layout = layout;
-        let text_layout = Layout {
-            position: layout.position + Vector2::new(Self::FRAME_WIDTH, Self::FRAME_WIDTH),
-            size: layout.size - 2.0 * Vector2::new(Self::FRAME_WIDTH, Self::FRAME_WIDTH),
-        };
-        self.text.set_layout(text_layout);
+        self.text
+            .set_layout(Insets::all(self.frame_width).shrink(layout));
+    }
+
+    fn intrinsic_size(&self) -> Vector2<f32> {
+        self.text.intrinsic_size() + Insets::all(self.frame_width).size()
     }
 }
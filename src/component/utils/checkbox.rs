@@ -0,0 +1,178 @@
+use super::interaction::{draw_focus_ring, Interaction};
+use super::{Icon, Text};
+use crate::component::{Callback, Layout};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::Event;
+use nalgebra::Vector2;
+
+use std::rc::Rc;
+
+const CHECK_MARK: &str = r#"<svg viewBox="0 0 16 16"><path d="M3.5 8.5 L6.5 11.5 L12.5 4.5" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"/></svg>"#;
+
+/// Labelled box toggled by clicking it or pressing Space while focused
+pub struct Checkbox {
+    pub label: Text,
+    pub layout: Layout,
+    /// Invoked with the new state when the user toggles the box
+    pub on_change: Callback<bool>,
+
+    checked: bool,
+    enabled: bool,
+    interaction: Interaction,
+    check_mark: Icon,
+    theme: Rc<Theme>,
+}
+
+impl Checkbox {
+    const BOX_SIZE: f32 = 16.0;
+    const SPACING: f32 = 6.0;
+    /// Room left around the box for the focus ring
+    const RING_GAP: f32 = 2.0;
+
+    pub fn new(label: &str, global: &Global) -> Self {
+        let mut text = Text::new(global);
+        text.content = label.to_owned();
+        let check_mark = Icon::from_str(CHECK_MARK, global).expect("invalid built-in icon");
+
+        let mut checkbox = Checkbox {
+            label: text,
+            layout: Layout::default(),
+            on_change: Callback::new(),
+            checked: false,
+            enabled: true,
+            interaction: Interaction::default(),
+            check_mark,
+            theme: global.theme(),
+        };
+        checkbox.apply_theme();
+        checkbox
+    }
+
+    fn apply_theme(&mut self) {
+        let palette = &self.theme.palette;
+        self.label.set_font_size(self.theme.typography.body_size);
+        self.label.color = if self.enabled {
+            palette.text
+        } else {
+            palette.disabled_text
+        };
+        self.check_mark.set_color(palette.input_background);
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Changes the state without invoking `on_change`
+    pub fn set_checked(&mut self, checked: bool, global: &Global) {
+        if self.checked != checked {
+            self.checked = checked;
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool, global: &Global) {
+        if self.enabled != enabled {
+            self.enabled = enabled;
+            self.interaction.reset();
+            self.apply_theme();
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.interaction.focused
+    }
+
+    fn box_layout(&self) -> Layout {
+        Layout {
+            position: self.layout.position
+                + Vector2::new(Self::RING_GAP, (self.layout.size.y - Self::BOX_SIZE) / 2.0),
+            size: Vector2::new(Self::BOX_SIZE, Self::BOX_SIZE),
+        }
+    }
+}
+
+impl Component for Checkbox {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        let box_layout = self.box_layout();
+        let fill = match (self.enabled, self.checked) {
+            (false, _) => palette.button_disabled,
+            (true, true) => palette.accent,
+            (true, false) => palette.input_background,
+        };
+        let border_color = if self.interaction.hovered && self.enabled {
+            palette.accent
+        } else {
+            palette.control_border
+        };
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: box_layout,
+            fill: fill.into(),
+            round_radius: self.theme.radii.control + 1.0,
+            border_width: if self.checked { 0.0 } else { 1.5 },
+            border_color,
+        });
+        if self.checked {
+            self.check_mark.draw(proxy);
+        }
+        if self.interaction.focused {
+            draw_focus_ring(
+                proxy,
+                &box_layout,
+                self.theme.radii.control + 1.0,
+                &self.theme,
+            );
+        }
+        proxy.with_clip(self.layout, |proxy| self.label.draw(proxy));
+    }
+
+    fn update(&mut self, global: &Global) {
+        self.check_mark.update(global);
+        self.label.update(global);
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        self.check_mark.handle_event(event, global);
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                self.apply_theme();
+                self.set_layout(self.layout);
+                global.damage(self.layout);
+            }
+            Event::WindowEvent { event, .. } if self.enabled => {
+                let activated = self.interaction.handle_event(event, &self.layout, global);
+                if activated {
+                    self.checked = !self.checked;
+                    self.on_change.call(self.checked, global);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        let box_layout = self.box_layout();
+        self.check_mark.set_layout(box_layout);
+        let label_x = Self::RING_GAP * 2.0 + Self::BOX_SIZE + Self::SPACING;
+        let font_size = self.theme.typography.body_size;
+        self.label.set_layout(Layout {
+            position: layout.position
+                + Vector2::new(label_x, (layout.size.y - font_size * 1.25) / 2.0),
+            size: Vector2::new(layout.size.x - label_x, font_size * 1.25),
+        });
+    }
+}
@@ -0,0 +1,600 @@
+use super::interaction::{draw_focus_ring, Interaction};
+use super::{Icon, Input, Text};
+use crate::component::menu::{MenuId, MenuOptions, TypeAhead};
+use crate::component::{Callback, Layout, Mailbox, Menu};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent};
+use nalgebra::{Vector2, Vector4};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const ARROW: &str =
+    r#"<svg viewBox="0 0 16 16"><polygon points="4,6 12,6 8,11" fill="currentColor"/></svg>"#;
+
+/// Box showing the chosen option that drops down a list of all of them in the menu overlay.
+/// An editable dropdown is a combo box instead: free text typed into an `Input`, with the
+/// options containing it suggested below
+pub struct Dropdown {
+    pub layout: Layout,
+    /// Invoked with the index of the option the user picked
+    pub on_change: Callback<usize>,
+    /// Invoked with the text of an editable dropdown whenever the user types or picks one
+    pub on_text_change: Callback<String>,
+
+    options: Vec<String>,
+    selected: Option<usize>,
+    label: Text,
+    arrow: Icon,
+    /// Text field of editable dropdowns
+    input: Option<Input>,
+    interaction: Interaction,
+    /// Open popup, the menu it shows and the options it lists
+    popup: Option<(MenuId, Rc<RefCell<Menu>>, Vec<usize>)>,
+    /// Suggestion highlighted with the arrow keys, as a row of the popup
+    highlighted: Option<usize>,
+    /// Options picked in the popup, applied once the overlay is done with the event
+    picks: Mailbox<usize>,
+    type_ahead: TypeAhead,
+    theme: Rc<Theme>,
+}
+
+impl Dropdown {
+    const PADDING: f32 = 8.0;
+    const ARROW_SIZE: f32 = 16.0;
+    const BORDER_WIDTH: f32 = 1.0;
+    /// Room left around the box for the focus ring
+    const RING_GAP: f32 = 2.0;
+
+    pub fn new(global: &Global) -> Self {
+        Self::with_input(None, global)
+    }
+
+    /// Combo box taking free text, suggesting the options that contain it
+    pub fn new_editable(global: &Global) -> Self {
+        Self::with_input(Some(Input::new(global)), global)
+    }
+
+    fn with_input(input: Option<Input>, global: &Global) -> Self {
+        let mut dropdown = Dropdown {
+            layout: Layout::default(),
+            on_change: Callback::new(),
+            on_text_change: Callback::new(),
+            options: Vec::new(),
+            selected: None,
+            label: Text::new(global),
+            arrow: Icon::from_str(ARROW, global).expect("invalid built-in icon"),
+            input,
+            interaction: Interaction::default(),
+            popup: None,
+            highlighted: None,
+            picks: Mailbox::new(),
+            type_ahead: TypeAhead::default(),
+            theme: global.theme(),
+        };
+        dropdown.apply_theme();
+        dropdown
+    }
+
+    pub fn is_editable(&self) -> bool {
+        self.input.is_some()
+    }
+
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Replaces the options, keeping the selection if it is still in range
+    pub fn set_options(&mut self, options: Vec<String>, global: &Global) {
+        self.options = options;
+        self.close_popup(global);
+        if matches!(self.selected, Some(selected) if selected >= self.options.len()) {
+            self.selected = None;
+        }
+        self.show_selected();
+        global.damage(self.layout);
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Chooses an option without invoking the callbacks
+    pub fn set_selected(&mut self, selected: Option<usize>, global: &Global) {
+        self.selected = selected.filter(|&selected| selected < self.options.len());
+        self.show_selected();
+        global.damage(self.layout);
+    }
+
+    /// Typed text of editable dropdowns, the selected option's otherwise
+    pub fn text(&self) -> &str {
+        match &self.input {
+            Some(input) => &input.text.content,
+            None => self
+                .selected
+                .map_or("", |selected| self.options[selected].as_str()),
+        }
+    }
+
+    /// Replaces the typed text of an editable dropdown without invoking `on_text_change`
+    pub fn set_text(&mut self, text: &str, global: &Global) {
+        if let Some(input) = &mut self.input {
+            input.text.content = text.to_owned();
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_some()
+    }
+
+    pub fn is_focused(&self) -> bool {
+        match &self.input {
+            Some(input) => input.is_focused(),
+            None => self.interaction.focused,
+        }
+    }
+
+    fn apply_theme(&mut self) {
+        let theme = &self.theme;
+        self.label.set_font_size(theme.typography.body_size);
+        self.label.color = theme.palette.text;
+        self.arrow.set_color(theme.palette.text);
+        self.relayout();
+    }
+
+    fn show_selected(&mut self) {
+        let text = self
+            .selected
+            .map_or_else(String::new, |selected| self.options[selected].clone());
+        match &mut self.input {
+            Some(input) => input.text.content = text,
+            None => {
+                self.label.content = text;
+                self.relayout();
+            }
+        }
+    }
+
+    /// Box drawn inside the layout, leaving room for the focus ring
+    fn box_layout(&self) -> Layout {
+        Layout {
+            position: self.layout.position + Vector2::new(Self::RING_GAP, Self::RING_GAP),
+            size: (self.layout.size - Vector2::new(Self::RING_GAP, Self::RING_GAP) * 2.0)
+                .sup(&Vector2::zeros()),
+        }
+    }
+
+    /// Square at the right of the box holding the arrow
+    fn button_layout(&self) -> Layout {
+        let layout = self.box_layout();
+        let width = layout.size.y.min(layout.size.x);
+        Layout {
+            position: layout.position + Vector2::new(layout.size.x - width, 0.0),
+            size: Vector2::new(width, layout.size.y),
+        }
+    }
+
+    /// Part of the box left of the arrow, inside the border
+    fn field_layout(&self) -> Layout {
+        let layout = self.box_layout();
+        let button = self.button_layout();
+        Layout {
+            position: layout.position + Vector2::new(Self::BORDER_WIDTH, Self::BORDER_WIDTH),
+            size: Vector2::new(
+                button.position.x - layout.position.x - Self::BORDER_WIDTH,
+                layout.size.y - Self::BORDER_WIDTH * 2.0,
+            )
+            .sup(&Vector2::zeros()),
+        }
+    }
+
+    fn relayout(&mut self) {
+        let font_size = self.theme.typography.body_size;
+        let field = self.field_layout();
+        let button = self.button_layout();
+        // wide enough to never wrap, the field clips what doesn't fit
+        let width = self.label.width().ceil() + 1.0;
+        self.label.set_layout(Layout {
+            position: field.position
+                + Vector2::new(Self::PADDING, (field.size.y - font_size * 1.25) / 2.0),
+            size: Vector2::new(width, font_size * 1.25),
+        });
+        self.arrow.set_layout(Layout {
+            position: button.position
+                + (button.size - Vector2::new(Self::ARROW_SIZE, Self::ARROW_SIZE)) / 2.0,
+            size: Vector2::new(Self::ARROW_SIZE, Self::ARROW_SIZE),
+        });
+        if let Some(input) = &mut self.input {
+            input.set_layout(field);
+        }
+    }
+
+    /// Menu listing `options`, each posting its index to `picks`
+    fn menu(&self, options: &[usize]) -> Menu {
+        let mut menu = Menu::new();
+        for &option in options {
+            let item = menu.add_item(&self.options[option]);
+            if self.input.is_none() && self.selected == Some(option) {
+                item.checked = Some(true);
+            }
+            item.on_activate.post(&self.picks, move |()| option);
+        }
+        menu
+    }
+
+    /// Shows `options` below the box, taking the keyboard unless the dropdown is editable
+    fn open_popup(&mut self, options: Vec<usize>, highlighted: Option<usize>, global: &Global) {
+        let menu = Rc::new(RefCell::new(self.menu(&options)));
+        let layout = self.box_layout();
+        let id = global.open_menu_with(
+            menu.clone(),
+            layout.position + Vector2::new(0.0, layout.size.y),
+            MenuOptions {
+                min_width: layout.size.x,
+                highlighted,
+                keyboard: self.input.is_none(),
+            },
+        );
+        self.popup = Some((id, menu, options));
+        self.highlighted = highlighted;
+        global.damage(self.layout);
+    }
+
+    fn close_popup(&mut self, global: &Global) {
+        if let Some((id, _, _)) = self.popup.take() {
+            global.close_menu(id);
+            self.highlighted = None;
+            global.damage(self.layout);
+        }
+    }
+
+    /// Chooses `option` on behalf of the user, invoking the callbacks
+    fn pick(&mut self, option: usize, global: &Global) {
+        self.selected = Some(option);
+        self.show_selected();
+        global.damage(self.layout);
+        if self.input.is_some() {
+            self.on_text_change
+                .call(self.options[option].clone(), global);
+        }
+        self.on_change.call(option, global);
+    }
+
+    /// Options containing the typed text, or all of them when nothing is typed
+    fn suggestions(&self) -> Vec<usize> {
+        let text = self.text().to_lowercase();
+        (0..self.options.len())
+            .filter(|&option| self.options[option].to_lowercase().contains(&text))
+            .collect()
+    }
+
+    /// Lists the suggestions for the typed text, in the popup already open if there is one
+    fn show_suggestions(&mut self, global: &Global) {
+        let suggestions = self.suggestions();
+        if suggestions.is_empty() || self.text().is_empty() {
+            self.close_popup(global);
+            return;
+        }
+        if matches!(&self.popup, Some((_, _, options)) if *options == suggestions) {
+            return;
+        }
+        let menu = self.menu(&suggestions);
+        match &mut self.popup {
+            Some((id, open, options)) => {
+                *open.borrow_mut() = menu;
+                *options = suggestions;
+                self.highlighted = None;
+                global.refresh_menu(*id);
+            }
+            None => self.open_popup(suggestions, None, global),
+        }
+    }
+
+    /// Moves the highlighted suggestion by `step` rows, wrapping around
+    fn step_suggestion(&mut self, step: isize, global: &Global) {
+        let len = match &self.popup {
+            Some((_, _, suggestions)) => suggestions.len(),
+            None => self.suggestions().len(),
+        } as isize;
+        if len == 0 {
+            return;
+        }
+        let highlighted = match self.highlighted {
+            Some(highlighted) => (highlighted as isize + step).rem_euclid(len),
+            None if step > 0 => 0,
+            None => len - 1,
+        } as usize;
+        match &self.popup {
+            Some((id, _, _)) => {
+                global.highlight_menu_item(*id, Some(highlighted));
+                self.highlighted = Some(highlighted);
+            }
+            None => self.open_popup(self.suggestions(), Some(highlighted), global),
+        }
+    }
+
+    fn handle_key(&mut self, key: VirtualKeyCode, global: &Global) {
+        let len = self.options.len();
+        if len == 0 {
+            return;
+        }
+        let option = match key {
+            VirtualKeyCode::Up => self
+                .selected
+                .map_or(0, |selected| selected.saturating_sub(1)),
+            VirtualKeyCode::Down => self
+                .selected
+                .map_or(0, |selected| (selected + 1).min(len - 1)),
+            VirtualKeyCode::Home => 0,
+            VirtualKeyCode::End => len - 1,
+            _ => return,
+        };
+        if self.selected != Some(option) {
+            self.pick(option, global);
+        }
+    }
+
+    fn handle_window_event(&mut self, event: &WindowEvent, global: &Global) {
+        let layout = self.box_layout();
+        if self.interaction.handle_event(event, &layout, global) {
+            let options = (0..self.options.len()).collect();
+            self.open_popup(options, self.selected, global);
+            return;
+        }
+        match event {
+            WindowEvent::KeyboardInput { input, .. }
+                if self.interaction.focused && input.state == ElementState::Pressed =>
+            {
+                if let Some(key) = input.virtual_keycode {
+                    self.handle_key(key, global);
+                }
+            }
+            WindowEvent::ReceivedCharacter(c) if self.interaction.focused && !c.is_control() => {
+                let labels: Vec<_> = self
+                    .options
+                    .iter()
+                    .map(|option| Some(option.as_str()))
+                    .collect();
+                if let Some(option) = self.type_ahead.find(*c, &labels, self.selected) {
+                    if self.selected != Some(option) {
+                        self.pick(option, global);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_editable_event(&mut self, event: EventProxy, global: &Global) {
+        let (text, focused) = match &self.input {
+            Some(input) => (input.text.content.clone(), input.is_focused()),
+            None => return,
+        };
+        let mut forward = true;
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
+                WindowEvent::MouseInput {
+                    state: ElementState::Pressed,
+                    button: MouseButton::Left,
+                    ..
+                } if self.button_layout().contains(&global.cursor_position()) => {
+                    // the arrow lists every option and keeps the field focused for typing
+                    forward = false;
+                    if let Some(input) = &mut self.input {
+                        input.set_focused(true, global);
+                    }
+                    let options = (0..self.options.len()).collect();
+                    self.open_popup(options, None, global);
+                }
+                WindowEvent::KeyboardInput { input, .. }
+                    if focused && input.state == ElementState::Pressed =>
+                {
+                    match input.virtual_keycode {
+                        Some(VirtualKeyCode::Down) => self.step_suggestion(1, global),
+                        Some(VirtualKeyCode::Up) => self.step_suggestion(-1, global),
+                        Some(VirtualKeyCode::Escape) => self.close_popup(global),
+                        Some(VirtualKeyCode::Return) | Some(VirtualKeyCode::NumpadEnter) => {
+                            let option = match (&self.popup, self.highlighted) {
+                                (Some((_, _, options)), Some(highlighted)) => {
+                                    options.get(highlighted).copied()
+                                }
+                                _ => None,
+                            };
+                            self.close_popup(global);
+                            if let Some(option) = option {
+                                self.picks.post(option);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(input) = &mut self.input {
+            if forward {
+                input.handle_event(event, global);
+            }
+            if input.text.content != text {
+                let text = input.text.content.clone();
+                self.on_text_change.call(text, global);
+                self.show_suggestions(global);
+            }
+        }
+    }
+}
+
+impl Component for Dropdown {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        let round_radius = self.theme.radii.control;
+        let layout = self.box_layout();
+        let border_color = if self.interaction.hovered || self.is_open() {
+            palette.accent
+        } else {
+            palette.control_border
+        };
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout,
+            fill: palette.input_background.into(),
+            round_radius,
+            border_width: Self::BORDER_WIDTH,
+            border_color,
+        });
+
+        match &self.input {
+            Some(input) => input.draw(proxy),
+            None => {
+                let mut clip = self.field_layout();
+                clip.size.x -= Self::PADDING / 2.0;
+                proxy.with_clip(clip, |proxy| self.label.draw(proxy));
+            }
+        }
+        let button = self.button_layout();
+        if self.input.is_some() {
+            proxy.draw_rounded_rect(&RoundedRect {
+                layout: Layout {
+                    position: button.position,
+                    size: Vector2::new(Self::BORDER_WIDTH, button.size.y),
+                },
+                fill: palette.control_border.into(),
+                round_radius: 0.0,
+                border_width: 0.0,
+                border_color: Vector4::zeros(),
+            });
+        }
+        self.arrow.draw(proxy);
+
+        if self.input.is_none() && self.interaction.focused {
+            draw_focus_ring(proxy, &layout, round_radius, &self.theme);
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        self.label.update(global);
+        self.arrow.update(global);
+        if let Some(input) = &mut self.input {
+            input.update(global);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                self.apply_theme();
+                global.damage(self.layout);
+            }
+            Event::UserEvent(CustomEvent::MenuClosed(id)) => {
+                if matches!(&self.popup, Some((popup, _, _)) if popup == id) {
+                    self.popup = None;
+                    self.highlighted = None;
+                    global.damage(self.layout);
+                }
+            }
+            Event::WindowEvent { event, .. } if self.input.is_none() => {
+                self.handle_window_event(event, global);
+            }
+            _ => {}
+        }
+        if self.input.is_some() {
+            self.handle_editable_event(event, global);
+        }
+        for option in self.picks.drain() {
+            self.pick(option, global);
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.relayout();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::menu::MenuRequest;
+    use crate::headless::{test_font, Headless};
+
+    use glium::glutin::dpi::{LogicalPosition, PhysicalSize};
+
+    fn headless() -> Headless<Dropdown> {
+        let size = PhysicalSize::new(200, 100);
+        let mut headless = Headless::with_root(test_font(), size, 1.0, |global| {
+            let mut dropdown = Dropdown::new_editable(global);
+            let options = ["apple", "apricot", "banana"];
+            dropdown.set_options(
+                options.iter().map(|&option| option.into()).collect(),
+                global,
+            );
+            dropdown.set_layout(Layout {
+                position: Vector2::zeros(),
+                size: Vector2::new(200.0, 30.0),
+            });
+            dropdown
+        });
+        headless.render();
+        headless.click(LogicalPosition::new(50.0, 15.0));
+        headless.global.take_menu_requests();
+        headless
+    }
+
+    #[test]
+    fn typing_updates_the_open_suggestions() {
+        let mut headless = headless();
+
+        headless.type_str("a");
+        let requests = headless.global.take_menu_requests();
+        let id = match requests.as_slice() {
+            [MenuRequest::Open { id, .. }] => *id,
+            _ => panic!("typing didn't open the suggestions"),
+        };
+
+        headless.type_str("p");
+        let requests = headless.global.take_menu_requests();
+        assert!(
+            matches!(requests.as_slice(), [MenuRequest::Refresh(refreshed)] if *refreshed == id)
+        );
+        assert_eq!(headless.root.popup.as_ref().unwrap().2, vec![0, 1]);
+
+        // still the same suggestions
+        headless.type_str("ri");
+        headless.global.take_menu_requests();
+        headless.type_str("c");
+        assert!(headless.global.take_menu_requests().is_empty());
+    }
+
+    #[test]
+    fn arrow_keys_move_the_highlight_in_place() {
+        let mut headless = headless();
+        headless.type_str("ap");
+        headless.global.take_menu_requests();
+
+        headless.press_key(VirtualKeyCode::Down);
+        headless.press_key(VirtualKeyCode::Down);
+        headless.press_key(VirtualKeyCode::Down);
+        let highlights: Vec<_> = headless
+            .global
+            .take_menu_requests()
+            .into_iter()
+            .map(|request| match request {
+                MenuRequest::Highlight(_, index) => index,
+                _ => panic!("the suggestions were reopened"),
+            })
+            .collect();
+        assert_eq!(highlights, vec![Some(0), Some(1), Some(0)]);
+
+        headless.press_key(VirtualKeyCode::Return);
+        assert_eq!(headless.root.selected(), Some(0));
+        assert_eq!(headless.root.text(), "apple");
+    }
+}
@@ -0,0 +1,136 @@
+use nalgebra::{Vector2, Vector4};
+
+use crate::component::Layout;
+use crate::custom_event::CustomEvent;
+use crate::render_context::{Bitmap, ImageRect};
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+
+use glium::glutin::event::{Event, WindowEvent};
+
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+
+mod svg;
+
+use svg::Svg;
+
+/// Vector icon from an SVG document, rasterized to fit its layout at the current scale factor.
+/// Clones share the parsed document, and the bitmap until they are drawn at another size
+#[derive(Clone)]
+pub struct Icon {
+    svg: Rc<Svg>,
+    bitmap: Option<Rc<Bitmap>>,
+    /// Color `bitmap` was rasterized with
+    bitmap_color: Vector4<f32>,
+    /// Color painted where the icon uses `currentColor`
+    color: Vector4<f32>,
+    pub layout: Layout,
+
+    scale_factor: f64,
+    should_rasterize: bool,
+}
+
+impl Icon {
+    pub fn from_str(text: &str, global: &Global) -> io::Result<Self> {
+        Ok(Icon {
+            svg: Rc::new(Svg::parse(text)?),
+            bitmap: None,
+            bitmap_color: Vector4::zeros(),
+            color: Vector4::new(0.0, 0.0, 0.0, 1.0),
+            layout: Layout::default(),
+            scale_factor: global.scale_factor,
+            should_rasterize: true,
+        })
+    }
+
+    pub fn from_bytes(bytes: &[u8], global: &Global) -> io::Result<Self> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Self::from_str(text, global)
+    }
+
+    pub fn from_path(path: impl AsRef<Path>, global: &Global) -> io::Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?, global)
+    }
+
+    pub fn color(&self) -> Vector4<f32> {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Vector4<f32>) {
+        if self.color != color {
+            self.color = color;
+            self.should_rasterize = true;
+        }
+    }
+
+    /// Width over height the icon is drawn at, regardless of its layout
+    pub fn aspect_ratio(&self) -> f32 {
+        self.svg.aspect_ratio()
+    }
+
+    /// Rasterizes right away for drawing at `size`, so clones laid out at that size start
+    /// with the bitmap instead of rasterizing their own
+    pub(crate) fn rasterize_at(&mut self, size: Vector2<f32>, scale_factor: f64) {
+        self.layout.size = size;
+        self.scale_factor = scale_factor;
+        self.should_rasterize = false;
+        self.rasterize();
+    }
+
+    fn rasterize(&mut self) {
+        let size = self.layout.size * self.scale_factor as f32;
+        let (width, height) = (size.x.ceil() as u32, size.y.ceil() as u32);
+        if let Some(bitmap) = &self.bitmap {
+            if (bitmap.width, bitmap.height) == (width, height) && self.bitmap_color == self.color {
+                return;
+            }
+        }
+        self.bitmap = self.svg.rasterize(width, height, self.color).map(Rc::new);
+        self.bitmap_color = self.color;
+    }
+}
+
+impl Component for Icon {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        if let Some(bitmap) = &self.bitmap {
+            proxy.draw_image(&ImageRect {
+                layout: self.layout,
+                round_radius: 0.0,
+                bitmap,
+                image_layout: self.layout,
+                tile: false,
+                tint: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            });
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        if self.should_rasterize {
+            self.should_rasterize = false;
+            self.rasterize();
+            global.damage(self.layout);
+        }
+    }
+
+    fn handle_event(&mut self, event: &Event<'_, CustomEvent>, _global: &Global) {
+        if let Event::WindowEvent {
+            event: WindowEvent::ScaleFactorChanged { scale_factor, .. },
+            ..
+        } = event
+        {
+            self.scale_factor = *scale_factor;
+            self.should_rasterize = true;
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        if layout.size != self.layout.size {
+            self.should_rasterize = true;
+        }
+        self.layout = layout;
+    }
+}
@@ -0,0 +1,463 @@
+use crate::render_context::Bitmap;
+
+use nalgebra::Vector4;
+use svgtypes::{Paint as SvgPaint, SimplePathSegment, SimplifyingPathParser};
+use tiny_skia::{
+    FillRule, LineCap, LineJoin, Paint, Path, PathBuilder, Pixmap, Rect, Shader, Stroke, Transform,
+};
+
+use std::io;
+use std::str::FromStr;
+
+/// Color of a fill or stroke, where `None` stands for `currentColor`
+type ShapeColor = Option<Vector4<f32>>;
+
+/// Presentation attributes inherited from the enclosing groups
+#[derive(Clone)]
+struct Style {
+    fill: Option<ShapeColor>,
+    fill_rule: FillRule,
+    fill_opacity: f32,
+    stroke: Option<ShapeColor>,
+    stroke_width: f32,
+    stroke_opacity: f32,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    opacity: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: Some(Some(Vector4::new(0.0, 0.0, 0.0, 1.0))),
+            fill_rule: FillRule::Winding,
+            fill_opacity: 1.0,
+            stroke: None,
+            stroke_width: 1.0,
+            stroke_opacity: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            opacity: 1.0,
+        }
+    }
+}
+
+/// Path with its style resolved and its transform accumulated from the root
+struct Shape {
+    path: Path,
+    transform: Transform,
+    style: Style,
+}
+
+/// SVG document reduced to the shapes an icon needs: paths, basic shapes and groups with
+/// transforms, filled and stroked with solid colors
+pub struct Svg {
+    view_box: Rect,
+    shapes: Vec<Shape>,
+}
+
+impl Svg {
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let document = roxmltree::Document::parse(text).map_err(invalid_data)?;
+        let root = document.root_element();
+        if root.tag_name().name() != "svg" {
+            return Err(invalid_data("root element is not <svg>"));
+        }
+
+        let view_box = match root.attribute("viewBox") {
+            Some(view_box) => {
+                let view_box = svgtypes::ViewBox::from_str(view_box).map_err(invalid_data)?;
+                Rect::from_xywh(
+                    view_box.x as f32,
+                    view_box.y as f32,
+                    view_box.w as f32,
+                    view_box.h as f32,
+                )
+            }
+            None => Rect::from_xywh(
+                0.0,
+                0.0,
+                length(root.attribute("width")).unwrap_or(0.0),
+                length(root.attribute("height")).unwrap_or(0.0),
+            ),
+        }
+        .filter(|view_box| view_box.width() > 0.0 && view_box.height() > 0.0)
+        .ok_or_else(|| invalid_data("svg has no size"))?;
+
+        let mut svg = Svg {
+            view_box,
+            shapes: Vec::new(),
+        };
+        svg.visit(root, Transform::identity(), &Style::default())?;
+        Ok(svg)
+    }
+
+    /// Width over height of the view box
+    pub fn aspect_ratio(&self) -> f32 {
+        self.view_box.width() / self.view_box.height()
+    }
+
+    /// Renders the view box centered into a `width` by `height` bitmap, keeping its aspect ratio
+    pub fn rasterize(
+        &self,
+        width: u32,
+        height: u32,
+        current_color: Vector4<f32>,
+    ) -> Option<Bitmap> {
+        let mut pixmap = Pixmap::new(width, height)?;
+        let scale =
+            (width as f32 / self.view_box.width()).min(height as f32 / self.view_box.height());
+        let view_transform = Transform::from_translate(
+            0.5 * (width as f32 - self.view_box.width() * scale),
+            0.5 * (height as f32 - self.view_box.height() * scale),
+        )
+        .pre_scale(scale, scale)
+        .pre_translate(-self.view_box.x(), -self.view_box.y());
+
+        for shape in &self.shapes {
+            let style = &shape.style;
+            let transform = view_transform.pre_concat(shape.transform);
+            if let Some(color) = style.fill {
+                let paint = paint(
+                    color.unwrap_or(current_color),
+                    style.fill_opacity * style.opacity,
+                );
+                pixmap.fill_path(&shape.path, &paint, style.fill_rule, transform, None);
+            }
+            if let Some(color) = style.stroke {
+                let paint = paint(
+                    color.unwrap_or(current_color),
+                    style.stroke_opacity * style.opacity,
+                );
+                let stroke = Stroke {
+                    width: style.stroke_width,
+                    line_cap: style.line_cap,
+                    line_join: style.line_join,
+                    ..Stroke::default()
+                };
+                pixmap.stroke_path(&shape.path, &paint, &stroke, transform, None);
+            }
+        }
+        Some(Bitmap::from_premultiplied(width, height, pixmap.take()))
+    }
+
+    fn visit(
+        &mut self,
+        node: roxmltree::Node,
+        transform: Transform,
+        style: &Style,
+    ) -> io::Result<()> {
+        let transform = match node.attribute("transform") {
+            Some(local) => {
+                let t = svgtypes::Transform::from_str(local).map_err(invalid_data)?;
+                transform.pre_concat(Transform::from_row(
+                    t.a as f32, t.b as f32, t.c as f32, t.d as f32, t.e as f32, t.f as f32,
+                ))
+            }
+            None => transform,
+        };
+        let style = apply_style(node, style)?;
+
+        let path = match node.tag_name().name() {
+            "svg" | "g" => {
+                for child in node.children().filter(|child| child.is_element()) {
+                    self.visit(child, transform, &style)?;
+                }
+                return Ok(());
+            }
+            "path" => parse_path(node.attribute("d").unwrap_or(""))?,
+            "rect" => rect_path(node),
+            "circle" => {
+                let r = length(node.attribute("r")).unwrap_or(0.0);
+                PathBuilder::from_circle(attribute(node, "cx"), attribute(node, "cy"), r)
+            }
+            "ellipse" => {
+                let (rx, ry) = (attribute(node, "rx"), attribute(node, "ry"));
+                Rect::from_xywh(
+                    attribute(node, "cx") - rx,
+                    attribute(node, "cy") - ry,
+                    2.0 * rx,
+                    2.0 * ry,
+                )
+                .and_then(PathBuilder::from_oval)
+            }
+            "line" => {
+                let mut builder = PathBuilder::new();
+                builder.move_to(attribute(node, "x1"), attribute(node, "y1"));
+                builder.line_to(attribute(node, "x2"), attribute(node, "y2"));
+                builder.finish()
+            }
+            name @ ("polyline" | "polygon") => {
+                let mut builder = PathBuilder::new();
+                let points = svgtypes::PointsParser::from(node.attribute("points").unwrap_or(""));
+                for (i, (x, y)) in points.enumerate() {
+                    if i == 0 {
+                        builder.move_to(x as f32, y as f32);
+                    } else {
+                        builder.line_to(x as f32, y as f32);
+                    }
+                }
+                if name == "polygon" {
+                    builder.close();
+                }
+                builder.finish()
+            }
+            // everything else, such as gradients, text and filters, is outside the subset
+            _ => None,
+        };
+
+        if let Some(path) = path {
+            self.shapes.push(Shape {
+                path,
+                transform,
+                style,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn invalid_data(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+fn paint(color: Vector4<f32>, opacity: f32) -> Paint<'static> {
+    let color = tiny_skia::Color::from_rgba(
+        color.x.clamp(0.0, 1.0),
+        color.y.clamp(0.0, 1.0),
+        color.z.clamp(0.0, 1.0),
+        (color.w * opacity).clamp(0.0, 1.0),
+    )
+    .unwrap_or(tiny_skia::Color::TRANSPARENT);
+    Paint {
+        shader: Shader::SolidColor(color),
+        anti_alias: true,
+        ..Paint::default()
+    }
+}
+
+/// Plain number or length in user units, ignoring any unit
+fn length(value: Option<&str>) -> Option<f32> {
+    value
+        .and_then(|value| svgtypes::Length::from_str(value).ok())
+        .map(|length| length.number as f32)
+}
+
+fn attribute(node: roxmltree::Node, name: &str) -> f32 {
+    length(node.attribute(name)).unwrap_or(0.0)
+}
+
+/// Style of `node`, from its presentation attributes and then its `style` attribute
+fn apply_style(node: roxmltree::Node, inherited: &Style) -> io::Result<Style> {
+    let mut style = inherited.clone();
+    let declarations = node
+        .attributes()
+        .map(|attribute| (attribute.name(), attribute.value()))
+        .chain(node.attribute("style").into_iter().flat_map(|style| {
+            style.split(';').filter_map(|declaration| {
+                let (name, value) = declaration.split_once(':')?;
+                Some((name.trim(), value.trim()))
+            })
+        }));
+    for (name, value) in declarations {
+        match name {
+            "fill" => style.fill = parse_paint(value)?.unwrap_or(inherited.fill),
+            "fill-rule" => {
+                style.fill_rule = match value {
+                    "evenodd" => FillRule::EvenOdd,
+                    _ => FillRule::Winding,
+                }
+            }
+            "fill-opacity" => style.fill_opacity = number(value)?,
+            "stroke" => style.stroke = parse_paint(value)?.unwrap_or(inherited.stroke),
+            "stroke-width" => style.stroke_width = length(Some(value)).unwrap_or(1.0),
+            "stroke-opacity" => style.stroke_opacity = number(value)?,
+            "stroke-linecap" => {
+                style.line_cap = match value {
+                    "round" => LineCap::Round,
+                    "square" => LineCap::Square,
+                    _ => LineCap::Butt,
+                }
+            }
+            "stroke-linejoin" => {
+                style.line_join = match value {
+                    "round" => LineJoin::Round,
+                    "bevel" => LineJoin::Bevel,
+                    _ => LineJoin::Miter,
+                }
+            }
+            // approximates group opacity by fading each shape on its own
+            "opacity" => style.opacity = inherited.opacity * number(value)?,
+            _ => {}
+        }
+    }
+    Ok(style)
+}
+
+fn number(value: &str) -> io::Result<f32> {
+    let number = svgtypes::Number::from_str(value).map_err(invalid_data)?;
+    Ok((number.0 as f32).clamp(0.0, 1.0))
+}
+
+/// `None` for `inherit`, `Some(None)` for `none`, and otherwise the color to paint with
+fn parse_paint(value: &str) -> io::Result<Option<Option<ShapeColor>>> {
+    Ok(match SvgPaint::from_str(value).map_err(invalid_data)? {
+        SvgPaint::None => Some(None),
+        SvgPaint::Inherit => None,
+        SvgPaint::CurrentColor => Some(Some(None)),
+        SvgPaint::Color(color) => Some(Some(Some(Vector4::new(
+            color.red as f32 / 255.0,
+            color.green as f32 / 255.0,
+            color.blue as f32 / 255.0,
+            color.alpha as f32 / 255.0,
+        )))),
+        // paint servers such as gradients are outside the subset, so paint nothing
+        _ => Some(None),
+    })
+}
+
+fn parse_path(data: &str) -> io::Result<Option<Path>> {
+    let mut builder = PathBuilder::new();
+    for segment in SimplifyingPathParser::from(data) {
+        match segment.map_err(invalid_data)? {
+            SimplePathSegment::MoveTo { x, y } => builder.move_to(x as f32, y as f32),
+            SimplePathSegment::LineTo { x, y } => builder.line_to(x as f32, y as f32),
+            SimplePathSegment::CurveTo {
+                x1,
+                y1,
+                x2,
+                y2,
+                x,
+                y,
+            } => builder.cubic_to(
+                x1 as f32, y1 as f32, x2 as f32, y2 as f32, x as f32, y as f32,
+            ),
+            SimplePathSegment::Quadratic { x1, y1, x, y } => {
+                builder.quad_to(x1 as f32, y1 as f32, x as f32, y as f32)
+            }
+            SimplePathSegment::ClosePath => builder.close(),
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// `<rect>`, with corners rounded by `rx` and `ry`
+fn rect_path(node: roxmltree::Node) -> Option<Path> {
+    let (x, y) = (attribute(node, "x"), attribute(node, "y"));
+    let (width, height) = (attribute(node, "width"), attribute(node, "height"));
+    let (rx, ry) = match (length(node.attribute("rx")), length(node.attribute("ry"))) {
+        (Some(rx), Some(ry)) => (rx, ry),
+        (Some(r), None) | (None, Some(r)) => (r, r),
+        (None, None) => (0.0, 0.0),
+    };
+    let (rx, ry) = (rx.clamp(0.0, width / 2.0), ry.clamp(0.0, height / 2.0));
+    if rx <= 0.0 || ry <= 0.0 {
+        return Rect::from_xywh(x, y, width, height).map(PathBuilder::from_rect);
+    }
+
+    // quarter ellipses approximated by cubic curves
+    const KAPPA: f32 = 0.552_284_8;
+    let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+    let (right, bottom) = (x + width, y + height);
+    let mut builder = PathBuilder::new();
+    builder.move_to(x + rx, y);
+    builder.line_to(right - rx, y);
+    builder.cubic_to(right - rx + kx, y, right, y + ry - ky, right, y + ry);
+    builder.line_to(right, bottom - ry);
+    builder.cubic_to(
+        right,
+        bottom - ry + ky,
+        right - rx + kx,
+        bottom,
+        right - rx,
+        bottom,
+    );
+    builder.line_to(x + rx, bottom);
+    builder.cubic_to(x + rx - kx, bottom, x, bottom - ry + ky, x, bottom - ry);
+    builder.line_to(x, y + ry);
+    builder.cubic_to(x, y + ry - ky, x + rx - kx, y, x + rx, y);
+    builder.close();
+    builder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::TryInto;
+
+    fn blue() -> Vector4<f32> {
+        Vector4::new(0.0, 0.0, 1.0, 1.0)
+    }
+
+    /// Rasterizes `text` at 10 by 10 pixels with blue as `currentColor`
+    fn render(text: &str) -> Bitmap {
+        let svg = Svg::parse(text).expect("failed to parse");
+        svg.rasterize(10, 10, blue()).expect("empty bitmap")
+    }
+
+    fn pixel(bitmap: &Bitmap, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * bitmap.width + x) * 4) as usize;
+        bitmap.pixels()[index..index + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn documents_outside_the_subset_are_rejected() {
+        assert!(Svg::parse("<svg").is_err());
+        assert!(Svg::parse(r#"<html viewBox="0 0 1 1"/>"#).is_err());
+        assert!(Svg::parse("<svg/>").is_err());
+        assert!(Svg::parse(r#"<svg viewBox="0 0 1 1"><path d="M 0 0 L x"/></svg>"#).is_err());
+        assert!(Svg::parse(r#"<svg viewBox="0 0 1 1"><path fill="nope"/></svg>"#).is_err());
+    }
+
+    #[test]
+    fn size_comes_from_the_view_box_or_else_the_dimensions() {
+        let svg = Svg::parse(r#"<svg viewBox="0 0 20 10" width="10" height="10"/>"#).unwrap();
+        assert_eq!(svg.aspect_ratio(), 2.0);
+        let svg = Svg::parse(r#"<svg width="30px" height="10"/>"#).unwrap();
+        assert_eq!(svg.aspect_ratio(), 3.0);
+    }
+
+    #[test]
+    fn fills_default_to_black_and_current_color_to_the_icons() {
+        let rect = |fill: &str| {
+            let text = format!(
+                r#"<svg viewBox="0 0 10 10"><rect width="10" height="10" {}/></svg>"#,
+                fill
+            );
+            pixel(&render(&text), 5, 5)
+        };
+        assert_eq!(rect(""), [0, 0, 0, 255]);
+        assert_eq!(rect(r#"fill="currentColor""#), [0, 0, 255, 255]);
+        assert_eq!(rect(r#"fill="none""#)[3], 0);
+        // bitmaps hold premultiplied colors
+        let translucent = rect(r##"fill="#ff0000" fill-opacity="0.5""##);
+        assert_eq!(translucent[0], translucent[3]);
+        assert!((127..=128).contains(&translucent[3]), "{:?}", translucent);
+    }
+
+    #[test]
+    fn groups_pass_down_their_style_and_transform() {
+        let bitmap = render(
+            r##"<svg viewBox="0 0 10 10">
+                <g fill="#00ff00" transform="translate(5 0)">
+                    <rect width="5" height="5"/>
+                    <rect y="5" width="5" height="5" fill="#ff0000" style="fill: #0000ff"/>
+                </g>
+            </svg>"##,
+        );
+        assert_eq!(pixel(&bitmap, 2, 2)[3], 0);
+        assert_eq!(pixel(&bitmap, 7, 2), [0, 255, 0, 255]);
+        // the style attribute wins over presentation attributes
+        assert_eq!(pixel(&bitmap, 7, 7), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn view_box_is_centered_keeping_its_aspect_ratio() {
+        let bitmap = render(r#"<svg viewBox="0 0 10 5"><rect width="10" height="5"/></svg>"#);
+        assert_eq!(pixel(&bitmap, 5, 1)[3], 0);
+        assert_eq!(pixel(&bitmap, 5, 3), [0, 0, 0, 255]);
+        assert_eq!(pixel(&bitmap, 5, 6), [0, 0, 0, 255]);
+        assert_eq!(pixel(&bitmap, 5, 8)[3], 0);
+    }
+}
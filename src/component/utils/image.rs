@@ -0,0 +1,129 @@
+use nalgebra::{Vector2, Vector4};
+
+use crate::component::Layout;
+use crate::render_context::{Bitmap, ImageRect};
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+
+/// How an `Image` maps its bitmap onto its layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFit {
+    /// Scales to fit entirely inside the layout, keeping the aspect ratio
+    Contain,
+    /// Scales to cover the whole layout, keeping the aspect ratio and cropping the rest
+    Cover,
+    /// Scales each axis to the layout
+    Stretch,
+    /// Repeats the bitmap at one logical pixel per bitmap pixel
+    Tile,
+}
+
+pub struct Image {
+    bitmap: Rc<Bitmap>,
+    pub layout: Layout,
+    pub fit: ImageFit,
+    pub round_radius: f32,
+    pub tint: Vector4<f32>,
+
+    should_redraw: bool,
+}
+
+impl Image {
+    pub fn new(bitmap: Rc<Bitmap>) -> Self {
+        Image {
+            bitmap,
+            layout: Layout::default(),
+            fit: ImageFit::Contain,
+            round_radius: 0.0,
+            tint: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            should_redraw: true,
+        }
+    }
+
+    /// Decodes a PNG or JPEG image
+    pub fn from_bytes(bytes: &[u8], global: &Global) -> io::Result<Self> {
+        Ok(Self::new(global.decode_image(bytes)?))
+    }
+
+    pub fn from_path(path: impl AsRef<Path>, global: &Global) -> io::Result<Self> {
+        Ok(Self::new(global.load_image(path)?))
+    }
+
+    pub fn bitmap(&self) -> &Rc<Bitmap> {
+        &self.bitmap
+    }
+
+    pub fn set_bitmap(&mut self, bitmap: Rc<Bitmap>) {
+        self.bitmap = bitmap;
+        self.should_redraw = true;
+    }
+
+    pub fn request_redraw(&mut self) {
+        self.should_redraw = true;
+    }
+
+    /// Area the bitmap is mapped onto, and the part of it that is shown
+    fn placement(&self) -> (Layout, Layout) {
+        let layout = self.layout;
+        let natural_size = Vector2::new(self.bitmap.width as f32, self.bitmap.height as f32);
+        let scale = layout.size.component_div(&natural_size);
+        let centered = |scale: f32| {
+            let size = natural_size * scale;
+            Layout {
+                position: layout.position + 0.5 * (layout.size - size),
+                size,
+            }
+        };
+        match self.fit {
+            ImageFit::Contain => {
+                let image_layout = centered(scale.min());
+                (image_layout, image_layout)
+            }
+            ImageFit::Cover => (centered(scale.max()), layout),
+            ImageFit::Stretch => (layout, layout),
+            ImageFit::Tile => (
+                Layout {
+                    position: layout.position,
+                    size: natural_size,
+                },
+                layout,
+            ),
+        }
+    }
+}
+
+impl Component for Image {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        if self.bitmap.width == 0 || self.bitmap.height == 0 {
+            return;
+        }
+        let (image_layout, layout) = self.placement();
+        proxy.draw_image(&ImageRect {
+            layout,
+            round_radius: self.round_radius,
+            bitmap: &self.bitmap,
+            image_layout,
+            tile: self.fit == ImageFit::Tile,
+            tint: self.tint,
+        });
+    }
+
+    fn update(&mut self, global: &Global) {
+        if self.should_redraw {
+            self.should_redraw = false;
+            global.damage(self.layout);
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        if layout.size != self.layout.size {
+            self.should_redraw = true;
+        }
+        self.layout = layout;
+    }
+}
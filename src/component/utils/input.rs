@@ -1,7 +1,8 @@
 use super::{Plane, Text};
-use crate::custom_event::EventProxy;
+use crate::custom_event::{CustomEvent, EventProxy};
 use crate::Global;
 use crate::RenderContextProxy;
+use crate::Theme;
 use crate::{component::Layout, Component};
 
 use glium::glutin;
@@ -9,8 +10,6 @@ use glutin::event::ElementState;
 use glutin::event::Event;
 use glutin::event::WindowEvent;
 
-use nalgebra::Vector4;
-
 #[derive(Clone, Copy)]
 struct Vertex {}
 
@@ -25,15 +24,37 @@ impl Input {
         let mut text = Text::new_cursored(global);
         text.content = String::new();
 
-        let mut background = Plane::new(global);
-        background.color = Vector4::new(0.4, 0.9, 0.8, 1.0);
-        background.request_redraw();
+        let background = Plane::new(global);
         let focus = false;
 
-        Input {
+        let mut input = Input {
             text,
             background,
             focus,
+        };
+        input.apply_theme(&global.theme());
+        input
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) {
+        self.text.color = theme.palette.input_text;
+        self.text.set_cursor_color(theme.palette.input_text);
+        self.text.set_font_size(theme.typography.body_size);
+        self.background.fill = theme.palette.input_background.into();
+        self.background.round_radius = theme.radii.control;
+        self.background.request_redraw();
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focus
+    }
+
+    /// Gives or takes away keyboard focus, as a click inside or outside would
+    pub fn set_focused(&mut self, focused: bool, global: &Global) {
+        if self.focus != focused {
+            self.focus = focused;
+            self.text.set_cursor_visibility(focused);
+            global.damage(self.background.layout);
         }
     }
 
@@ -45,7 +66,7 @@ impl Input {
 impl Component for Input {
     fn draw(&self, proxy: &mut RenderContextProxy) {
         self.background.draw(proxy);
-        self.text.draw(proxy);
+        proxy.with_clip(self.background.layout, |proxy| self.text.draw(proxy));
     }
 
     fn update(&mut self, global: &Global) {
@@ -64,23 +85,29 @@ impl Component for Input {
                     if is_cursor_hovering && !self.focus {
                         self.focus = true;
                         self.text.set_cursor_visibility(true);
-                        global.request_redraw();
+                        global.damage(self.background.layout);
                     } else if !is_cursor_hovering && self.focus {
                         self.focus = false;
                         self.text.set_cursor_visibility(false);
-                        global.request_redraw();
+                        global.damage(self.background.layout);
                     }
                 }
                 WindowEvent::ReceivedCharacter(c) if self.focus => match *c {
                     '\u{8}' | '\u{7f}' => {
                         self.text.content.pop();
                     }
+                    // characters of key combinations such as Ctrl+L
+                    c if c.is_control() => {}
                     c => {
                         self.text.content.push(c);
                     }
                 },
                 _ => {}
             },
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.apply_theme(&global.theme());
+                global.damage(self.background.layout);
+            }
             _ => {}
         }
     }
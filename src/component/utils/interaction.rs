@@ -0,0 +1,91 @@
+use crate::component::Layout;
+use crate::render_context::RoundedRect;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{ElementState, MouseButton, VirtualKeyCode, WindowEvent};
+use nalgebra::{Vector2, Vector4};
+
+/// Hover, press and keyboard focus tracking shared by controls that activate on click
+#[derive(Default)]
+pub(super) struct Interaction {
+    pub hovered: bool,
+    pub focused: bool,
+    /// Pressed inside and not yet released or dragged out
+    pub pressed: bool,
+}
+
+impl Interaction {
+    /// Feeds an event hit-tested against `layout`, damaging it when the control's look changes.
+    /// Returns whether the control was activated by a click or by Space/Enter while focused
+    pub fn handle_event(&mut self, event: &WindowEvent, layout: &Layout, global: &Global) -> bool {
+        let inside = layout.contains(&global.cursor_position());
+        let before = (self.hovered, self.focused, self.pressed);
+        let mut activated = false;
+
+        match event {
+            WindowEvent::CursorMoved { .. } => {
+                self.hovered = inside;
+                // dragging out cancels the click
+                self.pressed &= inside;
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.hovered = false;
+                self.pressed = false;
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => match state {
+                ElementState::Pressed => {
+                    self.focused = inside;
+                    self.pressed = inside;
+                }
+                ElementState::Released => {
+                    activated = self.pressed && inside;
+                    self.pressed = false;
+                }
+            },
+            WindowEvent::KeyboardInput { input, .. } if self.focused => {
+                activated = input.state == ElementState::Pressed
+                    && matches!(
+                        input.virtual_keycode,
+                        Some(VirtualKeyCode::Space) | Some(VirtualKeyCode::Return)
+                    );
+            }
+            _ => {}
+        }
+
+        if activated || before != (self.hovered, self.focused, self.pressed) {
+            global.damage(*layout);
+        }
+        activated
+    }
+
+    /// Forgets hover, focus and any press in progress, e.g. when the control gets disabled
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Accent outline drawn just outside `layout` while a control has keyboard focus
+pub(super) fn draw_focus_ring(
+    proxy: &mut RenderContextProxy,
+    layout: &Layout,
+    round_radius: f32,
+    theme: &Theme,
+) {
+    const WIDTH: f32 = 2.0;
+    proxy.draw_rounded_rect(&RoundedRect {
+        layout: Layout {
+            position: layout.position - Vector2::new(WIDTH, WIDTH),
+            size: layout.size + Vector2::new(WIDTH, WIDTH) * 2.0,
+        },
+        fill: Vector4::zeros().into(),
+        round_radius: round_radius + WIDTH,
+        border_width: WIDTH,
+        border_color: theme.palette.accent,
+    });
+}
@@ -0,0 +1,682 @@
+use super::{ScrollView, Text};
+use crate::component::{Callback, Layout};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent};
+use nalgebra::{Vector2, Vector4};
+
+use std::collections::{BTreeSet, VecDeque};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Contents of a single list row
+pub enum ListRow {
+    /// Plain text styled by the list
+    Text(String),
+    Widget(Box<dyn Component>),
+}
+
+/// Items shown by a `ListView`. Rows are only asked for while they are visible
+pub trait ListSource {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn row(&self, index: usize, global: &Global) -> ListRow;
+
+    /// Height of row `index`, `None` for the list's default row height
+    fn row_height(&self, _index: usize) -> Option<f32> {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionMode {
+    Single,
+    /// Ctrl toggles single rows and Shift selects ranges
+    Multiple,
+}
+
+/// Text row with padding, vertically centered
+struct TextRow {
+    text: Text,
+    font_size: f32,
+    padding: f32,
+}
+
+impl Component for TextRow {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        self.text.draw(proxy);
+    }
+
+    fn update(&mut self, global: &Global) {
+        self.text.update(global);
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        let height = self.font_size * 1.25;
+        self.text.set_layout(Layout {
+            position: layout.position + Vector2::new(self.padding, (layout.size.y - height) / 2.0),
+            size: Vector2::new(layout.size.x - self.padding * 2.0, height),
+        });
+    }
+}
+
+/// Rows currently built, scrolled by the `ScrollView`
+struct Rows {
+    layout: Layout,
+    /// Top of every row relative to the list, followed by the bottom of the last one
+    tops: Vec<f32>,
+    /// Index of the first built row
+    first: usize,
+    built: VecDeque<Box<dyn Component>>,
+}
+
+impl Rows {
+    fn len(&self) -> usize {
+        self.tops.len() - 1
+    }
+
+    fn height(&self) -> f32 {
+        self.tops[self.len()]
+    }
+
+    /// Layout of row `index` relative to the top of the list
+    fn local_layout(&self, index: usize) -> Layout {
+        Layout {
+            position: Vector2::new(0.0, self.tops[index]),
+            size: Vector2::new(self.layout.size.x, self.tops[index + 1] - self.tops[index]),
+        }
+    }
+
+    fn row_layout(&self, index: usize) -> Layout {
+        let mut layout = self.local_layout(index);
+        layout.position += self.layout.position;
+        layout
+    }
+
+    /// Row covering `y` relative to the top of the list
+    fn index_at(&self, y: f32) -> Option<usize> {
+        if y < 0.0 || y >= self.height() {
+            return None;
+        }
+        Some(self.tops.partition_point(|&top| top <= y) - 1)
+    }
+}
+
+impl Component for Rows {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        for (i, row) in self.built.iter().enumerate() {
+            proxy.with_clip(self.row_layout(self.first + i), |proxy| row.draw(proxy));
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        for row in &mut self.built {
+            row.update(global);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        for row in &mut self.built {
+            row.handle_event(event, global);
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        for i in 0..self.built.len() {
+            let row_layout = self.row_layout(self.first + i);
+            self.built[i].set_layout(row_layout);
+        }
+    }
+}
+
+/// Scrollable list that only builds the rows in view, so it stays fast with huge sources.
+/// Call `reload` whenever the source's rows change
+pub struct ListView<S> {
+    pub source: S,
+    pub layout: Layout,
+    pub selection_mode: SelectionMode,
+    /// Invoked with the selected indices, in order, whenever the user changes the selection
+    pub on_selection_change: Callback<Vec<usize>>,
+    /// Invoked with a row's index when it is double clicked or Enter is pressed on it
+    pub on_activate: Callback<usize>,
+
+    scroll: ScrollView<Rows>,
+    row_height: f32,
+    selection: BTreeSet<usize>,
+    /// Row the keyboard acts on
+    cursor: Option<usize>,
+    /// Row Shift selections extend from
+    anchor: Option<usize>,
+    hovered: Option<usize>,
+    focused: bool,
+    last_click: Option<(usize, Instant)>,
+    theme: Rc<Theme>,
+}
+
+impl<S: ListSource> ListView<S> {
+    const PADDING: f32 = 6.0;
+    const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+    pub fn new(source: S, global: &Global) -> Self {
+        let rows = Rows {
+            layout: Layout::default(),
+            tops: vec![0.0],
+            first: 0,
+            built: VecDeque::new(),
+        };
+        let theme = global.theme();
+        let mut list = ListView {
+            source,
+            layout: Layout::default(),
+            selection_mode: SelectionMode::Single,
+            on_selection_change: Callback::new(),
+            on_activate: Callback::new(),
+            scroll: ScrollView::new(rows, global),
+            row_height: (theme.typography.body_size * 1.6).ceil(),
+            selection: BTreeSet::new(),
+            cursor: None,
+            anchor: None,
+            hovered: None,
+            focused: false,
+            last_click: None,
+            theme,
+        };
+        list.reload(global);
+        list
+    }
+
+    /// Rebuilds every row and recomputes their heights after the source changed
+    pub fn reload(&mut self, global: &Global) {
+        let len = self.source.len();
+        let mut tops = Vec::with_capacity(len + 1);
+        let mut top = 0.0;
+        tops.push(top);
+        for index in 0..len {
+            top += self.source.row_height(index).unwrap_or(self.row_height);
+            tops.push(top);
+        }
+
+        let rows = &mut self.scroll.content;
+        rows.tops = tops;
+        rows.built.clear();
+        self.selection = self.selection.range(..len).copied().collect();
+        self.cursor = self.cursor.filter(|&index| index < len);
+        self.anchor = self.anchor.filter(|&index| index < len);
+        self.hovered = None;
+
+        let height = self.scroll.content.height();
+        self.scroll
+            .set_content_size(Vector2::new(0.0, height), global);
+        self.sync_rows(global);
+    }
+
+    pub fn row_height(&self) -> f32 {
+        self.row_height
+    }
+
+    /// Height of rows the source doesn't size itself
+    pub fn set_row_height(&mut self, row_height: f32, global: &Global) {
+        self.row_height = row_height;
+        self.reload(global);
+    }
+
+    pub fn selection(&self) -> &BTreeSet<usize> {
+        &self.selection
+    }
+
+    /// Changes the selection without invoking `on_selection_change`
+    pub fn set_selection(&mut self, selection: impl IntoIterator<Item = usize>, global: &Global) {
+        let len = self.len();
+        self.selection = selection.into_iter().filter(|&index| index < len).collect();
+        if self.selection_mode == SelectionMode::Single {
+            self.selection = self.selection.iter().next().copied().into_iter().collect();
+        }
+        self.cursor = self.selection.iter().next().copied().or(self.cursor);
+        self.anchor = self.cursor;
+        global.damage(self.layout);
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// Moves the keyboard cursor without touching the selection
+    pub fn set_cursor(&mut self, cursor: Option<usize>, global: &Global) {
+        self.cursor = cursor.filter(|&index| index < self.len());
+        self.anchor = self.cursor;
+        global.damage(self.layout);
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Scrolls until row `index` is fully visible
+    pub fn scroll_to_row(&mut self, index: usize) {
+        if index < self.len() {
+            let layout = self.scroll.content.local_layout(index);
+            self.scroll.scroll_into_view(layout);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.scroll.content.len()
+    }
+
+    fn build_row(&self, index: usize, global: &Global) -> Box<dyn Component> {
+        let mut row: Box<dyn Component> = match self.source.row(index, global) {
+            ListRow::Text(content) => {
+                let font_size = self.theme.typography.body_size;
+                let mut text = Text::new(global);
+                text.content = content;
+                text.set_font_size(font_size);
+                Box::new(TextRow {
+                    text,
+                    font_size,
+                    padding: Self::PADDING,
+                })
+            }
+            ListRow::Widget(widget) => widget,
+        };
+        row.set_layout(self.scroll.content.row_layout(index));
+        row.update(global);
+        row
+    }
+
+    /// Builds the rows that scrolled into view and drops the ones that left it
+    fn sync_rows(&mut self, global: &Global) {
+        let offset = self.scroll.offset().y;
+        let rows = &self.scroll.content;
+        let (first, last) = match rows.index_at(offset) {
+            Some(first) => {
+                let bottom = offset + self.layout.size.y;
+                (first, rows.index_at(bottom).unwrap_or(rows.len() - 1))
+            }
+            None => {
+                self.scroll.content.built.clear();
+                return;
+            }
+        };
+
+        let built_end = rows.first + rows.built.len();
+        if last < rows.first || first >= built_end {
+            let rows = &mut self.scroll.content;
+            rows.built.clear();
+            rows.first = first;
+        }
+        let rows = &mut self.scroll.content;
+        while rows.first < first && !rows.built.is_empty() {
+            rows.built.pop_front();
+            rows.first += 1;
+        }
+        while rows.first + rows.built.len() > last + 1 {
+            rows.built.pop_back();
+        }
+
+        while self.scroll.content.first > first {
+            let index = self.scroll.content.first - 1;
+            let row = self.build_row(index, global);
+            let rows = &mut self.scroll.content;
+            rows.built.push_front(row);
+            rows.first = index;
+        }
+        loop {
+            let rows = &self.scroll.content;
+            let index = rows.first + rows.built.len();
+            if index > last {
+                break;
+            }
+            let row = self.build_row(index, global);
+            self.scroll.content.built.push_back(row);
+        }
+    }
+
+    fn row_at_cursor(&self, global: &Global) -> Option<usize> {
+        if !self.layout.contains(&global.cursor_position()) || self.scroll.is_over_scrollbar(global)
+        {
+            return None;
+        }
+        let y = global.cursor_position().y as f32 - self.layout.position.y + self.scroll.offset().y;
+        self.scroll.content.index_at(y)
+    }
+
+    /// Moves the keyboard cursor to `index` and selects according to the held modifiers
+    fn select(&mut self, index: usize, global: &Global) {
+        let modifiers = global.modifiers();
+        let multiple = self.selection_mode == SelectionMode::Multiple;
+        let before = self.selection.clone();
+
+        match self.anchor {
+            Some(anchor) if multiple && modifiers.shift() => {
+                let (start, end) = (anchor.min(index), anchor.max(index));
+                if !modifiers.ctrl() {
+                    self.selection.clear();
+                }
+                self.selection.extend(start..=end);
+            }
+            _ if multiple && modifiers.ctrl() => {
+                if !self.selection.remove(&index) {
+                    self.selection.insert(index);
+                }
+                self.anchor = Some(index);
+            }
+            _ => {
+                self.selection.clear();
+                self.selection.insert(index);
+                self.anchor = Some(index);
+            }
+        }
+        self.cursor = Some(index);
+        self.scroll_to_row(index);
+        global.damage(self.layout);
+
+        if self.selection != before {
+            let selection = self.selection.iter().copied().collect();
+            self.on_selection_change.call(selection, global);
+        }
+    }
+
+    fn handle_click(&mut self, global: &Global) {
+        let index = match self.row_at_cursor(global) {
+            Some(index) => index,
+            None => return,
+        };
+        let now = Instant::now();
+        let double_click = match self.last_click {
+            Some((last, time)) => {
+                last == index && now.duration_since(time) < Self::DOUBLE_CLICK_TIME
+            }
+            None => false,
+        };
+        self.select(index, global);
+        if double_click {
+            self.last_click = None;
+            self.on_activate.call(index, global);
+        } else {
+            self.last_click = Some((index, now));
+        }
+    }
+
+    fn handle_key(&mut self, key: VirtualKeyCode, global: &Global) {
+        let len = self.len();
+        if len == 0 {
+            return;
+        }
+        let modifiers = global.modifiers();
+        let cursor = self.cursor.unwrap_or(0);
+        // a page is the view's height, however many rows of varying height that covers
+        let rows = &self.scroll.content;
+        let top = rows.local_layout(cursor).position.y;
+        let page = self.layout.size.y;
+        let target = match key {
+            VirtualKeyCode::Up => cursor.saturating_sub(1),
+            VirtualKeyCode::Down => (cursor + 1).min(len - 1),
+            VirtualKeyCode::PageUp => rows
+                .index_at(top - page)
+                .unwrap_or(0)
+                .min(cursor.saturating_sub(1)),
+            VirtualKeyCode::PageDown => rows
+                .index_at(top + page)
+                .unwrap_or(len - 1)
+                .max(cursor + 1)
+                .min(len - 1),
+            VirtualKeyCode::Home => 0,
+            VirtualKeyCode::End => len - 1,
+            VirtualKeyCode::A
+                if modifiers.ctrl() && self.selection_mode == SelectionMode::Multiple =>
+            {
+                self.selection = (0..len).collect();
+                global.damage(self.layout);
+                self.on_selection_change.call((0..len).collect(), global);
+                return;
+            }
+            VirtualKeyCode::Space => cursor,
+            VirtualKeyCode::Return => {
+                if let Some(cursor) = self.cursor {
+                    self.on_activate.call(cursor, global);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        // Ctrl only moves the cursor, except with Space which toggles the row
+        if modifiers.ctrl() && key != VirtualKeyCode::Space && !modifiers.shift() {
+            self.cursor = Some(target);
+            self.scroll_to_row(target);
+            global.damage(self.layout);
+        } else {
+            self.select(target, global);
+        }
+    }
+
+    fn update_hover(&mut self, global: &Global) {
+        let hovered = self.row_at_cursor(global);
+        if self.hovered != hovered {
+            self.hovered = hovered;
+            global.damage(self.layout);
+        }
+    }
+
+    fn handle_window_event(&mut self, event: &WindowEvent, global: &Global) {
+        match event {
+            WindowEvent::CursorMoved { .. } => self.update_hover(global),
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } => {
+                let focused = self.layout.contains(&global.cursor_position());
+                if self.focused != focused {
+                    self.focused = focused;
+                    global.damage(self.layout);
+                }
+                self.handle_click(global);
+            }
+            WindowEvent::KeyboardInput { input, .. }
+                if self.focused && input.state == ElementState::Pressed =>
+            {
+                if let Some(key) = input.virtual_keycode {
+                    self.handle_key(key, global);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<S: ListSource> Component for ListView<S> {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        let rows = &self.scroll.content;
+        proxy.with_clip(self.layout, |proxy| {
+            for index in rows.first..rows.first + rows.built.len() {
+                let selected = self.selection.contains(&index);
+                let fill = if selected {
+                    let mut fill = palette.accent;
+                    fill.w *= 0.35;
+                    fill
+                } else if self.hovered == Some(index) {
+                    let mut fill = palette.control_border;
+                    fill.w *= 0.3;
+                    fill
+                } else {
+                    continue;
+                };
+                proxy.draw_rounded_rect(&RoundedRect {
+                    layout: rows.row_layout(index),
+                    fill: fill.into(),
+                    round_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: Vector4::zeros(),
+                });
+            }
+            if let (true, Some(cursor)) = (self.focused, self.cursor) {
+                proxy.draw_rounded_rect(&RoundedRect {
+                    layout: rows.row_layout(cursor),
+                    fill: Vector4::zeros().into(),
+                    round_radius: 0.0,
+                    border_width: 1.0,
+                    border_color: palette.accent,
+                });
+            }
+        });
+        self.scroll.draw(proxy);
+    }
+
+    fn update(&mut self, global: &Global) {
+        let offset = self.scroll.offset();
+        self.scroll.update(global);
+        if self.scroll.offset() != offset {
+            // rows move under a resting cursor too
+            self.update_hover(global);
+        }
+        self.sync_rows(global);
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                // text rows pick up the new style when rebuilt
+                self.scroll.content.built.clear();
+            }
+            Event::WindowEvent { event, .. } => self.handle_window_event(event, global),
+            _ => {}
+        }
+        self.scroll.handle_event(event, global);
+        self.sync_rows(global);
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.scroll.set_layout(layout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{test_font, Headless};
+
+    use glium::glutin::dpi::{LogicalPosition, PhysicalSize};
+
+    /// `len` rows 20 high, but for the `tall` ones
+    struct Numbers {
+        len: usize,
+        tall: Vec<(usize, f32)>,
+    }
+
+    impl ListSource for Numbers {
+        fn len(&self) -> usize {
+            self.len
+        }
+
+        fn row(&self, index: usize, _global: &Global) -> ListRow {
+            ListRow::Text(index.to_string())
+        }
+
+        fn row_height(&self, index: usize) -> Option<f32> {
+            self.tall
+                .iter()
+                .find(|(tall, _)| *tall == index)
+                .map(|(_, height)| *height)
+        }
+    }
+
+    /// List in a 200 by 100 view, focused by a click on its first row
+    fn headless(len: usize, tall: Vec<(usize, f32)>) -> Headless<ListView<Numbers>> {
+        let size = PhysicalSize::new(200, 100);
+        let mut headless = Headless::with_root(test_font(), size, 1.0, |global| {
+            let mut list = ListView::new(Numbers { len, tall }, global);
+            list.set_row_height(20.0, global);
+            list.set_layout(Layout {
+                position: Vector2::zeros(),
+                size: Vector2::new(200.0, 100.0),
+            });
+            list
+        });
+        headless.click(LogicalPosition::new(100.0, 5.0));
+        headless.render();
+        headless
+    }
+
+    fn built(list: &ListView<Numbers>) -> (usize, usize) {
+        let rows = &list.scroll.content;
+        (rows.first, rows.first + rows.built.len())
+    }
+
+    #[test]
+    fn rows_are_found_by_their_varying_heights() {
+        let headless = headless(4, vec![(1, 60.0)]);
+        let rows = &headless.root.scroll.content;
+        assert_eq!(rows.tops, vec![0.0, 20.0, 80.0, 100.0, 120.0]);
+        let at = |y| rows.index_at(y);
+        assert_eq!(
+            [at(-1.0), at(0.0), at(19.9), at(20.0), at(79.9), at(80.0)],
+            [None, Some(0), Some(0), Some(1), Some(1), Some(2)]
+        );
+        assert_eq!((at(119.9), at(120.0)), (Some(3), None));
+    }
+
+    #[test]
+    fn only_the_rows_in_view_are_built() {
+        let mut headless = headless(100_000, Vec::new());
+        // the row cut off by the bottom edge included
+        assert_eq!(built(&headless.root), (0, 6));
+
+        headless
+            .root
+            .scroll
+            .set_offset(Vector2::new(0.0, 1010.0), &headless.global);
+        headless.update();
+        assert_eq!(built(&headless.root), (50, 56));
+
+        headless
+            .root
+            .scroll
+            .set_offset(Vector2::new(0.0, f32::MAX), &headless.global);
+        headless.update();
+        assert_eq!(built(&headless.root), (99_995, 100_000));
+    }
+
+    #[test]
+    fn paging_moves_by_the_rows_covering_the_view() {
+        let mut headless = headless(100, Vec::new());
+        let mut page = |key| {
+            headless.press_key(key);
+            headless.root.cursor()
+        };
+        assert_eq!(page(VirtualKeyCode::PageDown), Some(5));
+        assert_eq!(page(VirtualKeyCode::PageDown), Some(10));
+        assert_eq!(page(VirtualKeyCode::PageUp), Some(5));
+        assert_eq!(page(VirtualKeyCode::PageUp), Some(0));
+        assert_eq!(page(VirtualKeyCode::End), Some(99));
+        assert_eq!(page(VirtualKeyCode::PageDown), Some(99));
+        assert_eq!(page(VirtualKeyCode::Home), Some(0));
+    }
+
+    #[test]
+    fn paging_always_moves_past_rows_taller_than_the_view() {
+        let mut headless = headless(5, vec![(1, 300.0), (2, 300.0)]);
+        let mut page = |key| {
+            headless.press_key(key);
+            headless.root.cursor()
+        };
+        assert_eq!(page(VirtualKeyCode::PageDown), Some(1));
+        assert_eq!(page(VirtualKeyCode::PageDown), Some(2));
+        assert_eq!(page(VirtualKeyCode::PageDown), Some(3));
+        assert_eq!(page(VirtualKeyCode::PageUp), Some(2));
+        assert_eq!(page(VirtualKeyCode::PageUp), Some(1));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::component::Layout;
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::Event;
+use nalgebra::{Vector2, Vector4};
+
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Bar filling up as a task progresses, or with a stripe sweeping across it while how far
+/// along the task is can't be told
+pub struct ProgressBar {
+    pub layout: Layout,
+
+    /// Fraction done from 0.0 to 1.0, `None` while indeterminate
+    progress: Option<f32>,
+    /// When the indeterminate sweep started
+    started: Instant,
+    /// Position of the sweep within its period, from 0.0 to 1.0
+    phase: f32,
+    theme: Rc<Theme>,
+}
+
+impl ProgressBar {
+    const HEIGHT: f32 = 8.0;
+    /// Share of the track covered by the indeterminate sweep
+    const SWEEP_WIDTH: f32 = 0.3;
+    const SWEEP_PERIOD: Duration = Duration::from_millis(1500);
+    const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+    pub fn new(global: &Global) -> Self {
+        ProgressBar {
+            layout: Layout::default(),
+            progress: Some(0.0),
+            started: Instant::now(),
+            phase: 0.0,
+            theme: global.theme(),
+        }
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        self.progress
+    }
+
+    /// Sets the fraction done, clamped to 0.0..=1.0, or makes the bar indeterminate with `None`
+    pub fn set_progress(&mut self, progress: Option<f32>, global: &Global) {
+        let progress = progress.map(|progress| progress.clamp(0.0, 1.0));
+        if self.progress != progress {
+            if self.progress.is_some() && progress.is_none() {
+                self.started = Instant::now();
+                self.phase = 0.0;
+            }
+            self.progress = progress;
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_indeterminate(&self) -> bool {
+        self.progress.is_none()
+    }
+
+    fn track_layout(&self) -> Layout {
+        let height = self.layout.size.y.min(Self::HEIGHT);
+        Layout {
+            position: self.layout.position + Vector2::new(0.0, (self.layout.size.y - height) / 2.0),
+            size: Vector2::new(self.layout.size.x, height),
+        }
+    }
+
+    /// Part of the track filled in
+    fn fill_layout(&self) -> Layout {
+        let track = self.track_layout();
+        let (start, width) = match self.progress {
+            // never narrower than round ends, once there is any progress at all
+            Some(progress) if progress > 0.0 => (0.0, (track.size.x * progress).max(track.size.y)),
+            Some(_) => (0.0, 0.0),
+            None => {
+                let width = track.size.x * Self::SWEEP_WIDTH;
+                // enters on the left and leaves on the right
+                (self.phase * (track.size.x + width) - width, width)
+            }
+        };
+        Layout {
+            position: track.position + Vector2::new(start, 0.0),
+            size: Vector2::new(width, track.size.y),
+        }
+    }
+}
+
+impl Component for ProgressBar {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        let track = self.track_layout();
+        let radius = track.size.y / 2.0;
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: track,
+            fill: palette.input_background.into(),
+            round_radius: radius,
+            border_width: 1.0,
+            border_color: palette.control_border,
+        });
+
+        let fill = self.fill_layout();
+        if fill.size.x > 0.0 {
+            proxy.with_clip(track, |proxy| {
+                proxy.draw_rounded_rect(&RoundedRect {
+                    layout: fill,
+                    fill: palette.accent.into(),
+                    round_radius: radius,
+                    border_width: 0.0,
+                    border_color: Vector4::zeros(),
+                });
+            });
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        // only the indeterminate sweep moves; a determinate bar waits for `set_progress`
+        if self.progress.is_none() {
+            let now = Instant::now();
+            let period = Self::SWEEP_PERIOD.as_secs_f32();
+            self.phase = now.duration_since(self.started).as_secs_f32() % period / period;
+            global.damage(self.layout);
+            global.wake_up_at(now + Self::FRAME_INTERVAL);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        if let Event::UserEvent(CustomEvent::ThemeChanged) = event {
+            self.theme = global.theme();
+            global.damage(self.layout);
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{test_font, Headless};
+
+    use glium::glutin::dpi::PhysicalSize;
+
+    fn headless() -> Headless<ProgressBar> {
+        let size = PhysicalSize::new(200, 20);
+        Headless::with_root(test_font(), size, 1.0, |global| {
+            let mut bar = ProgressBar::new(global);
+            bar.set_layout(Layout {
+                position: Vector2::zeros(),
+                size: Vector2::new(200.0, 20.0),
+            });
+            bar
+        })
+    }
+
+    #[test]
+    fn fill_follows_progress() {
+        let mut headless = headless();
+        let track = headless.root.track_layout();
+        assert_eq!(track.position, Vector2::new(0.0, 6.0));
+        assert_eq!(track.size, Vector2::new(200.0, 8.0));
+        assert_eq!(headless.root.fill_layout().size.x, 0.0);
+
+        headless.root.set_progress(Some(0.5), &headless.global);
+        let fill = headless.root.fill_layout();
+        assert_eq!(fill.position, track.position);
+        assert_eq!(fill.size, Vector2::new(100.0, 8.0));
+
+        // a sliver of progress still shows the round ends
+        headless.root.set_progress(Some(0.01), &headless.global);
+        assert_eq!(headless.root.fill_layout().size.x, 8.0);
+    }
+
+    #[test]
+    fn progress_is_clamped() {
+        let mut headless = headless();
+        headless.root.set_progress(Some(1.5), &headless.global);
+        assert_eq!(headless.root.progress(), Some(1.0));
+        assert_eq!(headless.root.fill_layout().size.x, 200.0);
+
+        headless.root.set_progress(Some(-1.0), &headless.global);
+        assert_eq!(headless.root.progress(), Some(0.0));
+        assert_eq!(headless.root.fill_layout().size.x, 0.0);
+    }
+
+    #[test]
+    fn only_the_indeterminate_sweep_wakes_up() {
+        let mut headless = headless();
+        assert_eq!(headless.update(), None);
+
+        headless.root.set_progress(None, &headless.global);
+        assert!(headless.root.is_indeterminate());
+        let before = Instant::now();
+        let wake_up = headless.update().expect("the sweep should keep animating");
+        assert!(wake_up <= Instant::now() + ProgressBar::FRAME_INTERVAL);
+        assert!(wake_up >= before);
+        // the sweep starts off the left end of the track
+        let fill = headless.root.fill_layout();
+        assert!((fill.size.x - 60.0).abs() < 1e-4);
+        assert!(fill.position.x < 0.0);
+
+        headless.root.set_progress(Some(0.3), &headless.global);
+        assert_eq!(headless.update(), None);
+    }
+}
@@ -0,0 +1,277 @@
+use super::interaction::{draw_focus_ring, Interaction};
+use super::Text;
+use crate::component::{Callback, Layout};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{ElementState, Event, VirtualKeyCode, WindowEvent};
+use nalgebra::{Vector2, Vector4};
+
+use std::rc::Rc;
+
+/// Column of labelled radio buttons of which at most one is selected.
+/// Arrow keys move the selection while the group is focused
+pub struct RadioGroup {
+    pub layout: Layout,
+    /// Invoked with the option index when the user selects another option
+    pub on_change: Callback<usize>,
+
+    labels: Vec<Text>,
+    selected: Option<usize>,
+    /// Option Space selects and arrows move from, shown with the focus ring
+    cursor: usize,
+    hovered_row: Option<usize>,
+    enabled: bool,
+    interaction: Interaction,
+    theme: Rc<Theme>,
+}
+
+impl RadioGroup {
+    const CIRCLE_SIZE: f32 = 16.0;
+    const DOT_INSET: f32 = 4.5;
+    const SPACING: f32 = 6.0;
+    /// Room left around each circle for the focus ring
+    const RING_GAP: f32 = 2.0;
+
+    pub fn new<S: AsRef<str>>(options: &[S], global: &Global) -> Self {
+        let labels = options
+            .iter()
+            .map(|option| {
+                let mut text = Text::new(global);
+                text.content = option.as_ref().to_owned();
+                text
+            })
+            .collect();
+
+        let mut group = RadioGroup {
+            layout: Layout::default(),
+            on_change: Callback::new(),
+            labels,
+            selected: None,
+            cursor: 0,
+            hovered_row: None,
+            enabled: true,
+            interaction: Interaction::default(),
+            theme: global.theme(),
+        };
+        group.apply_theme();
+        group
+    }
+
+    fn apply_theme(&mut self) {
+        let palette = &self.theme.palette;
+        let color = if self.enabled {
+            palette.text
+        } else {
+            palette.disabled_text
+        };
+        for label in &mut self.labels {
+            label.set_font_size(self.theme.typography.body_size);
+            label.color = color;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Changes the selection without invoking `on_change`
+    pub fn set_selected(&mut self, selected: Option<usize>, global: &Global) {
+        let selected = selected.filter(|&index| index < self.len());
+        if self.selected != selected {
+            self.selected = selected;
+            self.cursor = selected.unwrap_or(self.cursor);
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool, global: &Global) {
+        if self.enabled != enabled {
+            self.enabled = enabled;
+            self.interaction.reset();
+            self.hovered_row = None;
+            self.apply_theme();
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.interaction.focused
+    }
+
+    /// Height each option takes up
+    pub fn row_height(&self) -> f32 {
+        (self.theme.typography.body_size * 1.25).max(Self::CIRCLE_SIZE) + Self::SPACING
+    }
+
+    fn row_layout(&self, index: usize) -> Layout {
+        let height = self.row_height();
+        Layout {
+            position: self.layout.position + Vector2::new(0.0, height * index as f32),
+            size: Vector2::new(self.layout.size.x, height),
+        }
+    }
+
+    fn circle_layout(&self, index: usize) -> Layout {
+        let row = self.row_layout(index);
+        Layout {
+            position: row.position
+                + Vector2::new(Self::RING_GAP, (row.size.y - Self::CIRCLE_SIZE) / 2.0),
+            size: Vector2::new(Self::CIRCLE_SIZE, Self::CIRCLE_SIZE),
+        }
+    }
+
+    fn row_at_cursor(&self, global: &Global) -> Option<usize> {
+        let cursor_position = global.cursor_position();
+        (0..self.len()).find(|&index| self.row_layout(index).contains(&cursor_position))
+    }
+
+    fn select(&mut self, index: usize, global: &Global) {
+        self.cursor = index;
+        global.damage(self.layout);
+        if self.selected != Some(index) {
+            self.selected = Some(index);
+            self.on_change.call(index, global);
+        }
+    }
+}
+
+impl Component for RadioGroup {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        for index in 0..self.len() {
+            let circle = self.circle_layout(index);
+            let border_color = if self.hovered_row == Some(index) && self.enabled {
+                palette.accent
+            } else {
+                palette.control_border
+            };
+            let fill = if self.enabled {
+                palette.input_background
+            } else {
+                palette.button_disabled
+            };
+            proxy.draw_rounded_rect(&RoundedRect {
+                layout: circle,
+                fill: fill.into(),
+                round_radius: Self::CIRCLE_SIZE / 2.0,
+                border_width: 1.5,
+                border_color,
+            });
+
+            if self.selected == Some(index) {
+                let dot_color = if self.enabled {
+                    palette.accent
+                } else {
+                    palette.disabled_text
+                };
+                proxy.draw_rounded_rect(&RoundedRect {
+                    layout: Layout {
+                        position: circle.position + Vector2::new(Self::DOT_INSET, Self::DOT_INSET),
+                        size: circle.size - Vector2::new(Self::DOT_INSET, Self::DOT_INSET) * 2.0,
+                    },
+                    fill: dot_color.into(),
+                    round_radius: Self::CIRCLE_SIZE / 2.0,
+                    border_width: 0.0,
+                    border_color: Vector4::zeros(),
+                });
+            }
+
+            if self.interaction.focused && self.cursor == index {
+                draw_focus_ring(proxy, &circle, Self::CIRCLE_SIZE / 2.0, &self.theme);
+            }
+        }
+
+        proxy.with_clip(self.layout, |proxy| {
+            for label in &self.labels {
+                label.draw(proxy);
+            }
+        });
+    }
+
+    fn update(&mut self, global: &Global) {
+        for label in &mut self.labels {
+            label.update(global);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        let event = match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                self.apply_theme();
+                self.set_layout(self.layout);
+                global.damage(self.layout);
+                return;
+            }
+            Event::WindowEvent { event, .. } if self.enabled && !self.is_empty() => event,
+            _ => return,
+        };
+
+        if self.interaction.handle_event(event, &self.layout, global) {
+            match event {
+                WindowEvent::KeyboardInput { .. } => self.select(self.cursor, global),
+                _ => {
+                    if let Some(index) = self.row_at_cursor(global) {
+                        self.select(index, global);
+                    }
+                }
+            }
+        }
+
+        match event {
+            WindowEvent::CursorMoved { .. } => {
+                let hovered_row = self.row_at_cursor(global);
+                if self.hovered_row != hovered_row {
+                    self.hovered_row = hovered_row;
+                    global.damage(self.layout);
+                }
+            }
+            WindowEvent::KeyboardInput { input, .. }
+                if self.interaction.focused && input.state == ElementState::Pressed =>
+            {
+                let last = self.len() - 1;
+                match input.virtual_keycode {
+                    Some(VirtualKeyCode::Up) | Some(VirtualKeyCode::Left) => {
+                        self.select(self.cursor.saturating_sub(1), global);
+                    }
+                    Some(VirtualKeyCode::Down) | Some(VirtualKeyCode::Right) => {
+                        self.select((self.cursor + 1).min(last), global);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        let label_x = Self::RING_GAP * 2.0 + Self::CIRCLE_SIZE + Self::SPACING;
+        let font_size = self.theme.typography.body_size;
+        for index in 0..self.len() {
+            let row = self.row_layout(index);
+            self.labels[index].set_layout(Layout {
+                position: row.position
+                    + Vector2::new(label_x, (row.size.y - font_size * 1.25) / 2.0),
+                size: Vector2::new(row.size.x - label_x, font_size * 1.25),
+            });
+        }
+    }
+}
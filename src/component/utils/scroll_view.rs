@@ -0,0 +1,506 @@
+use crate::component::{Layout, Orientation};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{
+    ElementState, Event, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+};
+use nalgebra::{Vector2, Vector4};
+
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Shows a part of `content` that may be larger than the view, scrolled with the mouse wheel,
+/// touchpad or by dragging the overlay scrollbars. Mouse input outside the visible part never
+/// reaches the content
+pub struct ScrollView<C> {
+    pub content: C,
+    pub layout: Layout,
+
+    /// Full size of the content, scrolling is possible along axes where it exceeds the view
+    content_size: Vector2<f32>,
+    /// Scroll position currently shown
+    offset: Vector2<f32>,
+    /// Scroll position `offset` is easing towards
+    target: Vector2<f32>,
+    /// Fling velocity in logical pixels per second
+    velocity: Vector2<f32>,
+    /// When the touchpad last scrolled, while it is still being followed
+    tracking: Option<Instant>,
+    /// Time of the previous animation step, while animating
+    tick: Option<Instant>,
+    /// Scrollbar whose thumb is being dragged and where on the thumb it was grabbed
+    dragging: Option<(Orientation, f32)>,
+    hovered: Option<Orientation>,
+    theme: Rc<Theme>,
+}
+
+impl<C: Component> ScrollView<C> {
+    /// Distance one wheel notch scrolls
+    const LINE_HEIGHT: f32 = 48.0;
+    const SCROLLBAR_WIDTH: f32 = 6.0;
+    const SCROLLBAR_MARGIN: f32 = 2.0;
+    const MIN_THUMB_LENGTH: f32 = 24.0;
+    /// Rate at which the shown offset catches up with the target, per second
+    const SMOOTHING: f32 = 18.0;
+    /// Rate at which fling velocity decays, per second
+    const FRICTION: f32 = 4.0;
+    const MIN_VELOCITY: f32 = 20.0;
+    /// Touchpad silence after which the gesture counts as released and the view flings
+    const FLING_DELAY: Duration = Duration::from_millis(60);
+    const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+    pub fn new(content: C, global: &Global) -> Self {
+        ScrollView {
+            content,
+            layout: Layout::default(),
+            content_size: Vector2::zeros(),
+            offset: Vector2::zeros(),
+            target: Vector2::zeros(),
+            velocity: Vector2::zeros(),
+            tracking: None,
+            tick: None,
+            dragging: None,
+            hovered: None,
+            theme: global.theme(),
+        }
+    }
+
+    pub fn content_size(&self) -> Vector2<f32> {
+        self.content_size
+    }
+
+    pub fn set_content_size(&mut self, size: Vector2<f32>, global: &Global) {
+        self.content_size = size;
+        self.target = self.clamp(self.target);
+        self.offset = self.clamp(self.offset);
+        self.layout_content();
+        global.damage(self.layout);
+    }
+
+    pub fn offset(&self) -> Vector2<f32> {
+        self.offset
+    }
+
+    /// Largest offset along each axis, zero where the content fits
+    pub fn max_offset(&self) -> Vector2<f32> {
+        (self.content_size - self.layout.size).sup(&Vector2::zeros())
+    }
+
+    /// Jumps to `offset` without animating
+    pub fn set_offset(&mut self, offset: Vector2<f32>, global: &Global) {
+        self.stop();
+        self.target = self.clamp(offset);
+        self.move_to(self.target, global);
+    }
+
+    /// Scrolls smoothly to `offset`
+    pub fn scroll_to(&mut self, offset: Vector2<f32>) {
+        self.velocity = Vector2::zeros();
+        self.tracking = None;
+        self.target = self.clamp(offset);
+        self.animate();
+    }
+
+    /// Scrolls smoothly just far enough for `layout`, in content coordinates, to be visible
+    pub fn scroll_into_view(&mut self, layout: Layout) {
+        let mut target = self.target;
+        for axis in 0..2 {
+            let end = layout.position[axis] + layout.size[axis];
+            if layout.position[axis] < target[axis] {
+                target[axis] = layout.position[axis];
+            } else if end > target[axis] + self.layout.size[axis] {
+                target[axis] = end - self.layout.size[axis];
+            }
+        }
+        self.scroll_to(target);
+    }
+
+    /// Whether the cursor is over one of the scrollbars rather than the content
+    pub fn is_over_scrollbar(&self, global: &Global) -> bool {
+        self.scrollbar_at_cursor(global).is_some()
+    }
+
+    fn clamp(&self, offset: Vector2<f32>) -> Vector2<f32> {
+        offset.sup(&Vector2::zeros()).inf(&self.max_offset())
+    }
+
+    fn stop(&mut self) {
+        self.velocity = Vector2::zeros();
+        self.tracking = None;
+        self.tick = None;
+    }
+
+    fn animate(&mut self) {
+        self.tick.get_or_insert_with(Instant::now);
+    }
+
+    fn move_to(&mut self, offset: Vector2<f32>, global: &Global) {
+        if self.offset != offset {
+            self.offset = offset;
+            self.layout_content();
+            global.damage(self.layout);
+        }
+    }
+
+    fn layout_content(&mut self) {
+        self.content.set_layout(Layout {
+            position: self.layout.position - self.offset,
+            size: self.content_size.sup(&self.layout.size),
+        });
+    }
+
+    fn axis(orientation: Orientation) -> usize {
+        match orientation {
+            Orientation::Horizontal => 0,
+            Orientation::Vertical => 1,
+        }
+    }
+
+    /// Track and thumb of the scrollbar along `orientation`, if the content overflows that way
+    fn scrollbar(&self, orientation: Orientation) -> Option<(Layout, Layout)> {
+        let axis = Self::axis(orientation);
+        let max_offset = self.max_offset();
+        if max_offset[axis] <= 0.0 {
+            return None;
+        }
+        let cross = 1 - axis;
+        // leave the corner to the other scrollbar
+        let corner = if max_offset[cross] > 0.0 {
+            Self::SCROLLBAR_WIDTH + Self::SCROLLBAR_MARGIN
+        } else {
+            0.0
+        };
+
+        let mut track = Layout::default();
+        track.position[axis] = self.layout.position[axis] + Self::SCROLLBAR_MARGIN;
+        track.size[axis] = self.layout.size[axis] - Self::SCROLLBAR_MARGIN * 2.0 - corner;
+        track.position[cross] = self.layout.position[cross] + self.layout.size[cross]
+            - Self::SCROLLBAR_WIDTH
+            - Self::SCROLLBAR_MARGIN;
+        track.size[cross] = Self::SCROLLBAR_WIDTH;
+
+        let length = (track.size[axis] * self.layout.size[axis] / self.content_size[axis])
+            .max(Self::MIN_THUMB_LENGTH)
+            .min(track.size[axis]);
+        let mut thumb = track;
+        thumb.size[axis] = length;
+        thumb.position[axis] +=
+            (track.size[axis] - length) * (self.offset[axis] / max_offset[axis]).min(1.0);
+        Some((track, thumb))
+    }
+
+    fn scrollbar_at_cursor(&self, global: &Global) -> Option<(Orientation, Layout, Layout)> {
+        let cursor_position = global.cursor_position();
+        [Orientation::Vertical, Orientation::Horizontal]
+            .iter()
+            .filter_map(|&orientation| {
+                let (track, thumb) = self.scrollbar(orientation)?;
+                Some((orientation, track, thumb))
+            })
+            .find(|(_, track, _)| track.contains(&cursor_position))
+    }
+
+    fn cursor(global: &Global) -> Vector2<f32> {
+        let cursor_position = global.cursor_position();
+        Vector2::new(cursor_position.x as f32, cursor_position.y as f32)
+    }
+
+    fn drag_thumb(&mut self, orientation: Orientation, grab: f32, global: &Global) {
+        let axis = Self::axis(orientation);
+        let (track, thumb) = match self.scrollbar(orientation) {
+            Some(scrollbar) => scrollbar,
+            None => return,
+        };
+        let travel = track.size[axis] - thumb.size[axis];
+        if travel <= 0.0 {
+            return;
+        }
+        let start = Self::cursor(global)[axis] - grab - track.position[axis];
+        let mut offset = self.offset;
+        offset[axis] = (start / travel).clamp(0.0, 1.0) * self.max_offset()[axis];
+        self.set_offset(offset, global);
+    }
+
+    fn handle_wheel(&mut self, delta: &MouseScrollDelta, phase: &TouchPhase, global: &Global) {
+        match delta {
+            MouseScrollDelta::LineDelta(x, y) => {
+                let mut delta = Vector2::new(*x, *y) * Self::LINE_HEIGHT;
+                // let plain wheels scroll sideways with shift held
+                if global.modifiers().shift() && delta.x == 0.0 {
+                    delta = Vector2::new(delta.y, 0.0);
+                }
+                self.scroll_to(self.target - delta);
+            }
+            MouseScrollDelta::PixelDelta(position) => {
+                let scale_factor = global.scale_factor as f32;
+                let delta = Vector2::new(position.x as f32, position.y as f32) / scale_factor;
+                let now = Instant::now();
+                // follow the fingers directly and remember how fast they moved to fling with
+                if let Some(last) = self.tracking {
+                    let elapsed = now.duration_since(last).as_secs_f32().max(1e-3);
+                    self.velocity = (self.velocity - delta / elapsed) / 2.0;
+                } else {
+                    self.velocity = Vector2::zeros();
+                }
+                self.tracking = match phase {
+                    TouchPhase::Ended | TouchPhase::Cancelled => None,
+                    _ => Some(now),
+                };
+                self.target = self.clamp(self.offset - delta);
+                self.move_to(self.target, global);
+                self.tick = Some(now);
+            }
+        }
+    }
+
+    fn handle_window_event(&mut self, event: &WindowEvent, global: &Global) -> bool {
+        let cursor_position = global.cursor_position();
+        let inside = self.layout.contains(&cursor_position);
+        match event {
+            WindowEvent::CursorMoved { .. } => {
+                if let Some((orientation, grab)) = self.dragging {
+                    self.drag_thumb(orientation, grab, global);
+                }
+                let hovered = self
+                    .scrollbar_at_cursor(global)
+                    .map(|(orientation, _, _)| orientation);
+                if self.hovered != hovered {
+                    self.hovered = hovered;
+                    global.damage(self.layout);
+                }
+                true
+            }
+            WindowEvent::MouseWheel { delta, phase, .. } => {
+                if inside {
+                    self.handle_wheel(delta, phase, global);
+                }
+                false
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } if inside => match self.scrollbar_at_cursor(global) {
+                Some((orientation, _, thumb)) if thumb.contains(&cursor_position) => {
+                    let axis = Self::axis(orientation);
+                    let grab = Self::cursor(global)[axis] - thumb.position[axis];
+                    self.dragging = Some((orientation, grab));
+                    global.damage(self.layout);
+                    false
+                }
+                Some((orientation, _, thumb)) => {
+                    // clicking the track pages towards the cursor
+                    let axis = Self::axis(orientation);
+                    let mut target = self.target;
+                    let page = self.layout.size[axis] * 0.9;
+                    if Self::cursor(global)[axis] < thumb.position[axis] {
+                        target[axis] -= page;
+                    } else {
+                        target[axis] += page;
+                    }
+                    self.scroll_to(target);
+                    false
+                }
+                None => true,
+            },
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Left,
+                ..
+            } => {
+                if self.dragging.take().is_some() {
+                    global.damage(self.layout);
+                    return false;
+                }
+                true
+            }
+            WindowEvent::MouseInput { .. } => inside,
+            _ => true,
+        }
+    }
+}
+
+impl<C: Component> Component for ScrollView<C> {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        proxy.with_clip(self.layout, |proxy| {
+            self.content.draw(proxy);
+
+            let palette = &self.theme.palette;
+            for &orientation in &[Orientation::Vertical, Orientation::Horizontal] {
+                let (_, thumb) = match self.scrollbar(orientation) {
+                    Some(scrollbar) => scrollbar,
+                    None => continue,
+                };
+                let active = self.hovered == Some(orientation)
+                    || matches!(self.dragging, Some((dragged, _)) if dragged == orientation);
+                let color = if active {
+                    palette.accent
+                } else {
+                    palette.control_border
+                };
+                proxy.draw_rounded_rect(&RoundedRect {
+                    layout: thumb,
+                    fill: color.into(),
+                    round_radius: Self::SCROLLBAR_WIDTH / 2.0,
+                    border_width: 0.0,
+                    border_color: Vector4::zeros(),
+                });
+            }
+        });
+    }
+
+    fn update(&mut self, global: &Global) {
+        self.content.update(global);
+
+        let last = match self.tick {
+            Some(last) => last,
+            None => return,
+        };
+        let now = Instant::now();
+        if let Some(tracked) = self.tracking {
+            if now.duration_since(tracked) < Self::FLING_DELAY {
+                // come back to notice when the gesture ends
+                global.wake_up_at(tracked + Self::FLING_DELAY);
+                return;
+            }
+            self.tracking = None;
+        }
+        let dt = now.duration_since(last).as_secs_f32();
+
+        if self.velocity.norm() > Self::MIN_VELOCITY {
+            self.target = self.clamp(self.target + self.velocity * dt);
+            self.velocity *= (-Self::FRICTION * dt).exp();
+            // stop at the edges instead of pushing against them
+            for axis in 0..2 {
+                let max = self.max_offset()[axis];
+                if self.target[axis] <= 0.0 || self.target[axis] >= max {
+                    self.velocity[axis] = 0.0;
+                }
+            }
+        } else {
+            self.velocity = Vector2::zeros();
+        }
+
+        let remaining = self.target - self.offset;
+        let offset = if remaining.norm() < 0.5 {
+            self.target
+        } else {
+            self.offset + remaining * (1.0 - (-Self::SMOOTHING * dt).exp())
+        };
+        self.move_to(offset, global);
+
+        if self.offset == self.target && self.velocity == Vector2::zeros() {
+            self.tick = None;
+        } else {
+            self.tick = Some(now);
+            // keep stepping until the motion settles
+            global.wake_up_at(now + Self::FRAME_INTERVAL);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        let forward = match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                global.damage(self.layout);
+                true
+            }
+            Event::WindowEvent { event, .. } => self.handle_window_event(event, global),
+            _ => true,
+        };
+        if forward {
+            self.content.handle_event(event, global);
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.target = self.clamp(self.target);
+        self.offset = self.clamp(self.offset);
+        self.layout_content();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::Plane;
+    use crate::headless::{test_font, Headless};
+
+    use glium::glutin::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
+
+    use std::thread;
+
+    fn headless() -> Headless<ScrollView<Plane>> {
+        let size = PhysicalSize::new(200, 200);
+        let mut headless = Headless::with_root(test_font(), size, 1.0, |global| {
+            let mut view = ScrollView::new(Plane::new(global), global);
+            view.set_layout(Layout {
+                position: Vector2::zeros(),
+                size: Vector2::new(200.0, 200.0),
+            });
+            view.set_content_size(Vector2::new(200.0, 1000.0), global);
+            view
+        });
+        headless.render();
+        headless.move_cursor(LogicalPosition::new(100.0, 100.0));
+        headless
+    }
+
+    /// Runs update passes at the pace the view asks for until it stops asking
+    fn settle(headless: &mut Headless<ScrollView<Plane>>) {
+        for _ in 0..500 {
+            match headless.update() {
+                Some(wake_up) => thread::sleep(wake_up.saturating_duration_since(Instant::now())),
+                None => return,
+            }
+        }
+        panic!("scrolling never settled");
+    }
+
+    fn assert_idle(headless: &mut Headless<ScrollView<Plane>>) {
+        // draws what the last step damaged
+        headless.render();
+        assert_eq!(headless.update(), None, "wake-up scheduled once settled");
+        assert!(
+            !headless.renderer().take_redraw_request(),
+            "damage reported once settled"
+        );
+    }
+
+    #[test]
+    fn wheel_eases_to_its_target_then_goes_idle() {
+        let mut headless = headless();
+        headless.mouse_wheel(MouseScrollDelta::LineDelta(0.0, -2.0), TouchPhase::Moved);
+        assert!(
+            headless.update().is_some(),
+            "smoothing did not schedule a frame"
+        );
+
+        settle(&mut headless);
+        let line_height = ScrollView::<Plane>::LINE_HEIGHT;
+        assert_eq!(headless.root.offset(), Vector2::new(0.0, 2.0 * line_height));
+        assert_idle(&mut headless);
+    }
+
+    #[test]
+    fn fling_coasts_past_the_gesture_then_goes_idle() {
+        let mut headless = headless();
+        let swipe = MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.0, -20.0));
+        for _ in 0..5 {
+            headless.mouse_wheel(swipe, TouchPhase::Moved);
+            headless.update();
+            thread::sleep(Duration::from_millis(10));
+        }
+        headless.mouse_wheel(swipe, TouchPhase::Ended);
+
+        settle(&mut headless);
+        assert!(headless.root.offset().y > 120.0, "the view did not fling");
+        assert_idle(&mut headless);
+    }
+}
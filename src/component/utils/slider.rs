@@ -0,0 +1,344 @@
+use super::interaction::draw_focus_ring;
+use crate::component::{Callback, Layout, Orientation};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{
+    ElementState, Event, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent,
+};
+use nalgebra::{Vector2, Vector4};
+
+use std::rc::Rc;
+
+/// Picks a value in `min..=max` by dragging a thumb along a track, with the arrow keys while
+/// focused, or with the mouse wheel while hovered
+pub struct Slider {
+    pub layout: Layout,
+    pub orientation: Orientation,
+    /// Invoked with the new value whenever the user moves the thumb
+    pub on_change: Callback<f32>,
+
+    min: f32,
+    max: f32,
+    /// Values snap to `min + n * step`; zero means continuous
+    step: f32,
+    value: f32,
+    enabled: bool,
+    hovered: bool,
+    focused: bool,
+    dragging: bool,
+    theme: Rc<Theme>,
+}
+
+impl Slider {
+    const TRACK_WIDTH: f32 = 4.0;
+    const THUMB_SIZE: f32 = 16.0;
+    /// Room left around the thumb for the focus ring
+    const RING_GAP: f32 = 2.0;
+    /// Pixels of smooth scrolling that count as one wheel notch
+    const PIXELS_PER_LINE: f32 = 20.0;
+
+    pub fn new(min: f32, max: f32, global: &Global) -> Self {
+        assert!(min <= max, "slider range is empty");
+        Slider {
+            layout: Layout::default(),
+            orientation: Orientation::Horizontal,
+            on_change: Callback::new(),
+            min,
+            max,
+            step: 0.0,
+            value: min,
+            enabled: true,
+            hovered: false,
+            focused: false,
+            dragging: false,
+            theme: global.theme(),
+        }
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Snaps and clamps `value` without invoking `on_change`
+    pub fn set_value(&mut self, value: f32, global: &Global) {
+        let value = self.snap(value);
+        if self.value != value {
+            self.value = value;
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.min, self.max)
+    }
+
+    pub fn set_range(&mut self, min: f32, max: f32, global: &Global) {
+        assert!(min <= max, "slider range is empty");
+        self.min = min;
+        self.max = max;
+        self.set_value(self.value, global);
+        global.damage(self.layout);
+    }
+
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    pub fn set_step(&mut self, step: f32, global: &Global) {
+        self.step = step.max(0.0);
+        self.set_value(self.value, global);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool, global: &Global) {
+        if self.enabled != enabled {
+            self.enabled = enabled;
+            self.hovered = false;
+            self.focused = false;
+            self.dragging = false;
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let value = value.max(self.min).min(self.max);
+        if self.step > 0.0 {
+            let snapped = self.min + ((value - self.min) / self.step).round() * self.step;
+            snapped.min(self.max)
+        } else {
+            value
+        }
+    }
+
+    /// Amount the keyboard and the wheel move the value by
+    fn increment(&self) -> f32 {
+        if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 100.0
+        }
+    }
+
+    /// Position of the value along the track, from 0 at `min` to 1 at `max`
+    fn ratio(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    /// Start and length of the line the thumb's center travels along
+    fn travel(&self) -> (Vector2<f32>, f32) {
+        let inset = Self::THUMB_SIZE / 2.0 + Self::RING_GAP;
+        let start = self.layout.position + Vector2::new(inset, inset);
+        let length = match self.orientation {
+            Orientation::Horizontal => self.layout.size.x,
+            Orientation::Vertical => self.layout.size.y,
+        } - inset * 2.0;
+        (start, length.max(0.0))
+    }
+
+    fn thumb_center(&self) -> Vector2<f32> {
+        let (start, length) = self.travel();
+        match self.orientation {
+            Orientation::Horizontal => Vector2::new(
+                start.x + length * self.ratio(),
+                self.layout.position.y + self.layout.size.y / 2.0,
+            ),
+            // vertical sliders grow upwards
+            Orientation::Vertical => Vector2::new(
+                self.layout.position.x + self.layout.size.x / 2.0,
+                start.y + length * (1.0 - self.ratio()),
+            ),
+        }
+    }
+
+    fn thumb_layout(&self) -> Layout {
+        let half = Self::THUMB_SIZE / 2.0;
+        Layout {
+            position: self.thumb_center() - Vector2::new(half, half),
+            size: Vector2::new(Self::THUMB_SIZE, Self::THUMB_SIZE),
+        }
+    }
+
+    /// Track rect from the start up to `ratio` of its length
+    fn track_layout(&self, ratio: f32) -> Layout {
+        let (start, length) = self.travel();
+        let half = Self::TRACK_WIDTH / 2.0;
+        let center = self.layout.position + self.layout.size / 2.0;
+        match self.orientation {
+            Orientation::Horizontal => Layout {
+                position: Vector2::new(start.x, center.y - half),
+                size: Vector2::new(length * ratio, Self::TRACK_WIDTH),
+            },
+            Orientation::Vertical => Layout {
+                position: Vector2::new(center.x - half, start.y + length * (1.0 - ratio)),
+                size: Vector2::new(Self::TRACK_WIDTH, length * ratio),
+            },
+        }
+    }
+
+    fn value_at_cursor(&self, global: &Global) -> f32 {
+        let cursor_position = global.cursor_position();
+        let cursor = Vector2::new(cursor_position.x as f32, cursor_position.y as f32);
+        let (start, length) = self.travel();
+        if length <= 0.0 {
+            return self.value;
+        }
+        let ratio = match self.orientation {
+            Orientation::Horizontal => (cursor.x - start.x) / length,
+            Orientation::Vertical => 1.0 - (cursor.y - start.y) / length,
+        };
+        self.min + ratio.clamp(0.0, 1.0) * (self.max - self.min)
+    }
+
+    /// Moves to `value` on behalf of the user, invoking `on_change` if it changed
+    fn change(&mut self, value: f32, global: &Global) {
+        let value = self.snap(value);
+        if self.value != value {
+            self.value = value;
+            global.damage(self.layout);
+            self.on_change.call(value, global);
+        }
+    }
+
+    fn handle_window_event(&mut self, event: &WindowEvent, global: &Global) {
+        let inside = self.layout.contains(&global.cursor_position());
+        match event {
+            WindowEvent::CursorMoved { .. } => {
+                if self.hovered != inside {
+                    self.hovered = inside;
+                    global.damage(self.layout);
+                }
+                if self.dragging {
+                    self.change(self.value_at_cursor(global), global);
+                }
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => match state {
+                ElementState::Pressed => {
+                    if self.focused != inside {
+                        self.focused = inside;
+                        global.damage(self.layout);
+                    }
+                    if inside {
+                        self.dragging = true;
+                        self.change(self.value_at_cursor(global), global);
+                    }
+                }
+                ElementState::Released => {
+                    if self.dragging {
+                        self.dragging = false;
+                        global.damage(self.layout);
+                    }
+                }
+            },
+            WindowEvent::MouseWheel { delta, .. } if inside => {
+                let notches = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => y + x,
+                    MouseScrollDelta::PixelDelta(position) => {
+                        (position.y + position.x) as f32 / Self::PIXELS_PER_LINE
+                    }
+                };
+                self.change(self.value + notches * self.increment(), global);
+            }
+            WindowEvent::KeyboardInput { input, .. }
+                if self.focused && input.state == ElementState::Pressed =>
+            {
+                let increment = self.increment();
+                let value = match input.virtual_keycode {
+                    Some(VirtualKeyCode::Right) | Some(VirtualKeyCode::Up) => {
+                        self.value + increment
+                    }
+                    Some(VirtualKeyCode::Left) | Some(VirtualKeyCode::Down) => {
+                        self.value - increment
+                    }
+                    Some(VirtualKeyCode::PageUp) => self.value + increment * 10.0,
+                    Some(VirtualKeyCode::PageDown) => self.value - increment * 10.0,
+                    Some(VirtualKeyCode::Home) => self.min,
+                    Some(VirtualKeyCode::End) => self.max,
+                    _ => return,
+                };
+                self.change(value, global);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Component for Slider {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        let radius = Self::TRACK_WIDTH / 2.0;
+        let (filled, thumb_border) = if self.enabled {
+            let border = if self.hovered || self.dragging {
+                palette.accent
+            } else {
+                palette.control_border
+            };
+            (palette.accent, border)
+        } else {
+            (palette.disabled_text, palette.button_disabled)
+        };
+
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: self.track_layout(1.0),
+            fill: palette.control_border.into(),
+            round_radius: radius,
+            border_width: 0.0,
+            border_color: Vector4::zeros(),
+        });
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: self.track_layout(self.ratio()),
+            fill: filled.into(),
+            round_radius: radius,
+            border_width: 0.0,
+            border_color: Vector4::zeros(),
+        });
+
+        let thumb = self.thumb_layout();
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: thumb,
+            fill: palette.input_background.into(),
+            round_radius: Self::THUMB_SIZE / 2.0,
+            border_width: 1.5,
+            border_color: thumb_border,
+        });
+        if self.focused {
+            draw_focus_ring(proxy, &thumb, Self::THUMB_SIZE / 2.0, &self.theme);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                global.damage(self.layout);
+            }
+            Event::WindowEvent { event, .. } if self.enabled => {
+                self.handle_window_event(event, global);
+            }
+            _ => {}
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+    }
+}
@@ -0,0 +1,215 @@
+use super::{Button, Input};
+use crate::component::{Callback, Layout, Mailbox};
+use crate::custom_event::EventProxy;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+
+use glium::glutin::event::{ElementState, Event, MouseScrollDelta, VirtualKeyCode, WindowEvent};
+use nalgebra::Vector2;
+
+/// Numeric `Input` with increment/decrement buttons. Typed text is committed on Enter or when
+/// focus leaves, clamped to the range, and reverted if it doesn't parse
+pub struct SpinBox {
+    pub input: Input,
+    pub layout: Layout,
+    /// Invoked with the new value whenever the user changes it
+    pub on_change: Callback<f32>,
+
+    increment: Button,
+    decrement: Button,
+    /// Steps posted by the buttons, applied once they are done handling the event
+    steps: Mailbox<f32>,
+    min: f32,
+    max: f32,
+    step: f32,
+    /// Digits shown after the decimal point
+    decimals: usize,
+    value: f32,
+}
+
+impl SpinBox {
+    const BUTTON_WIDTH: f32 = 20.0;
+
+    pub fn new(min: f32, max: f32, global: &Global) -> Self {
+        assert!(min <= max, "spin box range is empty");
+        let steps = Mailbox::new();
+
+        let mut increment = Button::new(global);
+        increment.text.content = "+".to_owned();
+        increment.on_click.post(&steps, |()| 1.0);
+        let mut decrement = Button::new(global);
+        decrement.text.content = "-".to_owned();
+        decrement.on_click.post(&steps, |()| -1.0);
+
+        let mut spin_box = SpinBox {
+            input: Input::new(global),
+            layout: Layout::default(),
+            on_change: Callback::new(),
+            increment,
+            decrement,
+            steps,
+            min,
+            max,
+            step: 1.0,
+            decimals: 0,
+            value: 0.0_f32.max(min).min(max),
+        };
+        spin_box.show_value();
+        spin_box
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Clamps `value` without invoking `on_change`
+    pub fn set_value(&mut self, value: f32) {
+        self.value = self.clamp(value);
+        self.show_value();
+    }
+
+    pub fn set_range(&mut self, min: f32, max: f32) {
+        assert!(min <= max, "spin box range is empty");
+        self.min = min;
+        self.max = max;
+        self.set_value(self.value);
+    }
+
+    /// Amount the buttons, arrow keys and wheel change the value by
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step;
+    }
+
+    pub fn set_decimals(&mut self, decimals: usize) {
+        self.decimals = decimals;
+        self.set_value(self.value);
+    }
+
+    fn clamp(&self, value: f32) -> f32 {
+        let factor = 10_f32.powi(self.decimals as i32);
+        let value = (value * factor).round() / factor;
+        value.max(self.min).min(self.max)
+    }
+
+    fn show_value(&mut self) {
+        self.input.text.content = format!("{:.*}", self.decimals, self.value);
+    }
+
+    /// Moves to `value` on behalf of the user, invoking `on_change` if it changed
+    fn change(&mut self, value: f32, global: &Global) {
+        let value = self.clamp(value);
+        let changed = self.value != value;
+        self.value = value;
+        self.show_value();
+        if changed {
+            self.on_change.call(value, global);
+        }
+    }
+
+    /// Takes the typed text as the new value, or restores the old text if it isn't a number
+    fn commit(&mut self, global: &Global) {
+        match self.input.text.content.trim().parse::<f32>() {
+            Ok(value) if value.is_finite() => self.change(value, global),
+            _ => self.show_value(),
+        }
+    }
+
+    fn accepts(&self, c: char) -> bool {
+        c.is_ascii_digit()
+            || (c == '.' && self.decimals > 0)
+            || (c == '-' && self.min < 0.0)
+            || c == '\u{8}'
+            || c == '\u{7f}'
+    }
+
+    /// Decrement and increment buttons, side by side after the input
+    fn button_layouts(&self) -> (Layout, Layout) {
+        let layout = self.layout;
+        let size = Vector2::new(Self::BUTTON_WIDTH, layout.size.y);
+        let x = layout.position.x + layout.size.x - Self::BUTTON_WIDTH * 2.0;
+        (
+            Layout {
+                position: Vector2::new(x, layout.position.y),
+                size,
+            },
+            Layout {
+                position: Vector2::new(x + Self::BUTTON_WIDTH, layout.position.y),
+                size,
+            },
+        )
+    }
+}
+
+impl Component for SpinBox {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        self.input.draw(proxy);
+        self.increment.draw(proxy);
+        self.decrement.draw(proxy);
+    }
+
+    fn update(&mut self, global: &Global) {
+        self.input.update(global);
+        self.increment.update(global);
+        self.decrement.update(global);
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        let was_focused = self.input.is_focused();
+        let mut forward = true;
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
+                WindowEvent::ReceivedCharacter(c) if was_focused => {
+                    // only characters that can be part of a number reach the input
+                    forward = self.accepts(*c);
+                    if *c == '\r' {
+                        self.commit(global);
+                    }
+                }
+                WindowEvent::KeyboardInput { input, .. }
+                    if was_focused && input.state == ElementState::Pressed =>
+                {
+                    match input.virtual_keycode {
+                        Some(VirtualKeyCode::Up) => self.steps.post(1.0),
+                        Some(VirtualKeyCode::Down) => self.steps.post(-1.0),
+                        _ => {}
+                    }
+                }
+                WindowEvent::MouseWheel {
+                    delta: MouseScrollDelta::LineDelta(_, y),
+                    ..
+                } if *y != 0.0 && self.layout.contains(&global.cursor_position()) => {
+                    self.steps.post(y.signum());
+                }
+                _ => {}
+            }
+        }
+
+        if forward {
+            self.input.handle_event(event, global);
+        }
+        self.increment.handle_event(event, global);
+        self.decrement.handle_event(event, global);
+
+        if was_focused && !self.input.is_focused() {
+            self.commit(global);
+        }
+        let steps: f32 = self.steps.drain().into_iter().sum();
+        if steps != 0.0 {
+            // pending edits count as the starting point
+            self.commit(global);
+            self.change(self.value + steps * self.step, global);
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.input.set_layout(Layout {
+            position: layout.position,
+            size: layout.size - Vector2::new(Self::BUTTON_WIDTH * 2.0, 0.0),
+        });
+        let (decrement, increment) = self.button_layouts();
+        self.increment.set_layout(increment);
+        self.decrement.set_layout(decrement);
+    }
+}
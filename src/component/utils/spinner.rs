@@ -0,0 +1,170 @@
+use crate::component::Layout;
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::Event;
+use nalgebra::{Vector2, Vector4};
+
+use std::f32::consts::PI;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Ring of dots with a highlight running around it while something is busy. Draws nothing
+/// while stopped
+pub struct Spinner {
+    pub layout: Layout,
+
+    /// When the spinner started, while spinning
+    started: Option<Instant>,
+    /// Dot currently highlighted
+    step: usize,
+    theme: Rc<Theme>,
+}
+
+impl Spinner {
+    const DOTS: usize = 8;
+    /// Time for the highlight to go around once
+    const PERIOD: Duration = Duration::from_millis(960);
+    /// Dot diameter relative to the spinner's size
+    const DOT_SIZE: f32 = 0.2;
+    /// Opacity of the dot furthest behind the highlight
+    const MIN_ALPHA: f32 = 0.2;
+
+    pub fn new(global: &Global) -> Self {
+        Spinner {
+            layout: Layout::default(),
+            started: None,
+            step: 0,
+            theme: global.theme(),
+        }
+    }
+
+    pub fn is_spinning(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn set_spinning(&mut self, spinning: bool, global: &Global) {
+        if self.is_spinning() != spinning {
+            self.started = if spinning { Some(Instant::now()) } else { None };
+            self.step = 0;
+            global.damage(self.layout);
+        }
+    }
+
+    fn step_duration() -> Duration {
+        Self::PERIOD / Self::DOTS as u32
+    }
+
+    fn dot_layout(&self, index: usize) -> Layout {
+        let size = self.layout.size.x.min(self.layout.size.y);
+        let dot = size * Self::DOT_SIZE;
+        let center = self.layout.position + self.layout.size / 2.0;
+        // first dot at the top, going clockwise
+        let angle = index as f32 / Self::DOTS as f32 * 2.0 * PI - PI / 2.0;
+        let offset = Vector2::new(angle.cos(), angle.sin()) * (size - dot) / 2.0;
+        Layout {
+            position: center + offset - Vector2::repeat(dot / 2.0),
+            size: Vector2::new(dot, dot),
+        }
+    }
+}
+
+impl Component for Spinner {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        if !self.is_spinning() {
+            return;
+        }
+        let accent = self.theme.palette.accent;
+        for index in 0..Self::DOTS {
+            // dots fade the further behind the highlight they are
+            let behind = (self.step + Self::DOTS - index) % Self::DOTS;
+            let fade = 1.0 - behind as f32 / Self::DOTS as f32;
+            let mut fill = accent;
+            fill.w *= Self::MIN_ALPHA + (1.0 - Self::MIN_ALPHA) * fade;
+            let layout = self.dot_layout(index);
+            proxy.draw_rounded_rect(&RoundedRect {
+                layout,
+                fill: fill.into(),
+                round_radius: layout.size.x / 2.0,
+                border_width: 0.0,
+                border_color: Vector4::zeros(),
+            });
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        // wake up once per step instead of redrawing every frame
+        if let Some(started) = self.started {
+            let step_duration = Self::step_duration();
+            let steps = (started.elapsed().as_nanos() / step_duration.as_nanos()) as u32;
+            let step = steps as usize % Self::DOTS;
+            if self.step != step {
+                self.step = step;
+                global.damage(self.layout);
+            }
+            global.wake_up_at(started + step_duration * (steps + 1));
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        if let Event::UserEvent(CustomEvent::ThemeChanged) = event {
+            self.theme = global.theme();
+            global.damage(self.layout);
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{test_font, Headless};
+
+    use glium::glutin::dpi::PhysicalSize;
+
+    fn headless() -> Headless<Spinner> {
+        let size = PhysicalSize::new(40, 40);
+        Headless::with_root(test_font(), size, 1.0, |global| {
+            let mut spinner = Spinner::new(global);
+            spinner.set_layout(Layout {
+                position: Vector2::zeros(),
+                size: Vector2::new(40.0, 40.0),
+            });
+            spinner
+        })
+    }
+
+    #[test]
+    fn dots_go_clockwise_from_the_top() {
+        let headless = headless();
+        let top = headless.root.dot_layout(0);
+        assert_eq!(top.size, Vector2::new(8.0, 8.0));
+        assert!((top.position - Vector2::new(16.0, 0.0)).norm() < 1e-4);
+        let right = headless.root.dot_layout(Spinner::DOTS / 4);
+        assert!((right.position - Vector2::new(32.0, 16.0)).norm() < 1e-4);
+    }
+
+    #[test]
+    fn wakes_up_only_while_spinning() {
+        let mut headless = headless();
+        assert_eq!(headless.update(), None);
+
+        headless.root.set_spinning(true, &headless.global);
+        assert!(headless.root.is_spinning());
+        let wake_up = headless
+            .update()
+            .expect("a spinning spinner should wake up");
+        assert!(wake_up <= Instant::now() + Spinner::step_duration());
+
+        headless.root.set_spinning(false, &headless.global);
+        assert!(!headless.root.is_spinning());
+        assert_eq!(headless.update(), None);
+    }
+}
@@ -0,0 +1,187 @@
+use super::interaction::{draw_focus_ring, Interaction};
+use super::Text;
+use crate::component::{Callback, Layout};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::Event;
+use nalgebra::{Vector2, Vector4};
+
+use std::rc::Rc;
+
+/// Labelled on/off toggle with a sliding thumb
+pub struct Switch {
+    pub label: Text,
+    pub layout: Layout,
+    /// Invoked with the new state when the user flips the switch
+    pub on_change: Callback<bool>,
+
+    on: bool,
+    enabled: bool,
+    interaction: Interaction,
+    theme: Rc<Theme>,
+}
+
+impl Switch {
+    const TRACK_SIZE: (f32, f32) = (32.0, 18.0);
+    const THUMB_INSET: f32 = 2.0;
+    const SPACING: f32 = 6.0;
+    /// Room left around the track for the focus ring
+    const RING_GAP: f32 = 2.0;
+
+    pub fn new(label: &str, global: &Global) -> Self {
+        let mut text = Text::new(global);
+        text.content = label.to_owned();
+
+        let mut switch = Switch {
+            label: text,
+            layout: Layout::default(),
+            on_change: Callback::new(),
+            on: false,
+            enabled: true,
+            interaction: Interaction::default(),
+            theme: global.theme(),
+        };
+        switch.apply_theme();
+        switch
+    }
+
+    fn apply_theme(&mut self) {
+        let palette = &self.theme.palette;
+        self.label.set_font_size(self.theme.typography.body_size);
+        self.label.color = if self.enabled {
+            palette.text
+        } else {
+            palette.disabled_text
+        };
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Changes the state without invoking `on_change`
+    pub fn set_on(&mut self, on: bool, global: &Global) {
+        if self.on != on {
+            self.on = on;
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool, global: &Global) {
+        if self.enabled != enabled {
+            self.enabled = enabled;
+            self.interaction.reset();
+            self.apply_theme();
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.interaction.focused
+    }
+
+    fn track_layout(&self) -> Layout {
+        let (width, height) = Self::TRACK_SIZE;
+        Layout {
+            position: self.layout.position
+                + Vector2::new(Self::RING_GAP, (self.layout.size.y - height) / 2.0),
+            size: Vector2::new(width, height),
+        }
+    }
+
+    fn thumb_layout(&self) -> Layout {
+        let track = self.track_layout();
+        let size = track.size.y - Self::THUMB_INSET * 2.0;
+        let x = if self.on {
+            track.size.x - Self::THUMB_INSET - size
+        } else {
+            Self::THUMB_INSET
+        };
+        Layout {
+            position: track.position + Vector2::new(x, Self::THUMB_INSET),
+            size: Vector2::new(size, size),
+        }
+    }
+}
+
+impl Component for Switch {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        let track = self.track_layout();
+        let track_color = match (self.enabled, self.on) {
+            (false, _) => palette.button_disabled,
+            (true, true) => palette.accent,
+            (true, false) => palette.control_border,
+        };
+        let radius = track.size.y / 2.0;
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: track,
+            fill: track_color.into(),
+            round_radius: radius,
+            border_width: 0.0,
+            border_color: Vector4::zeros(),
+        });
+
+        let thumb = self.thumb_layout();
+        let border_color = if self.interaction.hovered && self.enabled {
+            palette.accent
+        } else {
+            Vector4::zeros()
+        };
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: thumb,
+            fill: palette.input_background.into(),
+            round_radius: thumb.size.y / 2.0,
+            border_width: 1.0,
+            border_color,
+        });
+
+        if self.interaction.focused {
+            draw_focus_ring(proxy, &track, radius, &self.theme);
+        }
+        proxy.with_clip(self.layout, |proxy| self.label.draw(proxy));
+    }
+
+    fn update(&mut self, global: &Global) {
+        self.label.update(global);
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                self.apply_theme();
+                self.set_layout(self.layout);
+                global.damage(self.layout);
+            }
+            Event::WindowEvent { event, .. } if self.enabled => {
+                let activated = self.interaction.handle_event(event, &self.layout, global);
+                if activated {
+                    self.on = !self.on;
+                    self.on_change.call(self.on, global);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        let label_x = Self::RING_GAP * 2.0 + Self::TRACK_SIZE.0 + Self::SPACING;
+        let font_size = self.theme.typography.body_size;
+        self.label.set_layout(Layout {
+            position: layout.position
+                + Vector2::new(label_x, (layout.size.y - font_size * 1.25) / 2.0),
+            size: Vector2::new(layout.size.x - label_x, font_size * 1.25),
+        });
+    }
+}
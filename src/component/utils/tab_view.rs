@@ -0,0 +1,691 @@
+use super::{Icon, Text};
+use crate::component::{Callback, Layout};
+use crate::custom_event::{CustomEvent, EventProxy};
+use crate::render_context::RoundedRect;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+use crate::Theme;
+
+use glium::glutin::event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent};
+use nalgebra::{Vector2, Vector4};
+
+use std::rc::Rc;
+
+const CLOSE: &str = r#"<svg viewBox="0 0 16 16"><path d="M4.5 4.5 L11.5 11.5 M11.5 4.5 L4.5 11.5" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/></svg>"#;
+
+struct Tab {
+    label: Text,
+    close: Option<Icon>,
+    content: Box<dyn Component>,
+    /// Header width, fitted to the label
+    width: f32,
+}
+
+/// Tab being dragged along the header strip
+struct TabDrag {
+    index: usize,
+    /// Where the cursor grabbed the header, relative to its left edge
+    grab: f32,
+    /// Index the tab had when the drag started
+    origin: usize,
+    press_x: f32,
+    cursor_x: f32,
+    /// Whether the cursor has moved far enough to count as a drag rather than a click
+    moved: bool,
+}
+
+/// Header strip of labelled tabs above the content of the selected one. Headers can be
+/// dragged to reorder them and scrolled with the wheel when they overflow
+pub struct TabView {
+    pub layout: Layout,
+    /// Invoked with the index of the tab the user selected
+    pub on_change: Callback<usize>,
+    /// Invoked with a tab's index when its close button is clicked. The tab is left to the
+    /// handler to remove; without a handler it is removed right away
+    pub on_close: Callback<usize>,
+    /// Invoked with the old and new index of a tab the user dragged to another position
+    pub on_reorder: Callback<(usize, usize)>,
+
+    tabs: Vec<Tab>,
+    selected: Option<usize>,
+    /// How far the headers are scrolled to the left
+    scroll: f32,
+    drag: Option<TabDrag>,
+    /// Tab whose close button is pressed, it closes if the button is released there too
+    pressed_close: Option<usize>,
+    /// Hovered header, and whether the cursor is on its close button
+    hovered: Option<(usize, bool)>,
+    theme: Rc<Theme>,
+}
+
+impl TabView {
+    const PADDING: f32 = 12.0;
+    const SPACING: f32 = 2.0;
+    const CLOSE_SIZE: f32 = 16.0;
+    const MIN_TAB_WIDTH: f32 = 48.0;
+    const MAX_TAB_WIDTH: f32 = 240.0;
+    const INDICATOR_HEIGHT: f32 = 2.0;
+    /// Distance the cursor moves before a press on a header turns into a drag
+    const DRAG_THRESHOLD: f32 = 4.0;
+    /// Distance one wheel notch scrolls the headers
+    const LINE_WIDTH: f32 = 40.0;
+
+    pub fn new(global: &Global) -> Self {
+        TabView {
+            layout: Layout::default(),
+            on_change: Callback::new(),
+            on_close: Callback::new(),
+            on_reorder: Callback::new(),
+            tabs: Vec::new(),
+            selected: None,
+            scroll: 0.0,
+            drag: None,
+            pressed_close: None,
+            hovered: None,
+            theme: global.theme(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tabs.is_empty()
+    }
+
+    /// Appends a tab, returning its index. The first tab added gets selected
+    pub fn add_tab(
+        &mut self,
+        label: &str,
+        content: Box<dyn Component>,
+        closable: bool,
+        global: &Global,
+    ) -> usize {
+        let index = self.len();
+        self.insert_tab(index, label, content, closable, global);
+        index
+    }
+
+    pub fn insert_tab(
+        &mut self,
+        index: usize,
+        label: &str,
+        content: Box<dyn Component>,
+        closable: bool,
+        global: &Global,
+    ) {
+        let close = if closable {
+            Some(Icon::from_str(CLOSE, global).expect("invalid built-in icon"))
+        } else {
+            None
+        };
+        let mut tab = Tab {
+            label: Text::new(global),
+            close,
+            content,
+            width: 0.0,
+        };
+        tab.label.content = label.to_owned();
+        tab.content.set_layout(self.content_layout());
+        self.tabs.insert(index, tab);
+        self.style_tab(index);
+
+        self.selected = match self.selected {
+            Some(selected) if selected >= index => Some(selected + 1),
+            Some(selected) => Some(selected),
+            None => Some(index),
+        };
+        self.relayout();
+        global.damage(self.layout);
+    }
+
+    /// Removes tab `index` and hands back its content
+    pub fn remove_tab(&mut self, index: usize, global: &Global) -> Box<dyn Component> {
+        let tab = self.tabs.remove(index);
+        self.drag = None;
+        self.pressed_close = None;
+        self.hovered = None;
+        self.selected = match self.selected {
+            _ if self.tabs.is_empty() => None,
+            Some(selected) if selected > index || selected == self.len() => Some(selected - 1),
+            selected => selected,
+        };
+        self.scroll = self.scroll.min(self.max_scroll());
+        self.relayout();
+        global.damage(self.layout);
+        tab.content
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Switches tabs without invoking `on_change`
+    pub fn set_selected(&mut self, index: usize, global: &Global) {
+        if index < self.len() && self.selected != Some(index) {
+            self.selected = Some(index);
+            self.scroll_into_view(index);
+            global.damage(self.layout);
+        }
+    }
+
+    pub fn label(&self, index: usize) -> &str {
+        &self.tabs[index].label.content
+    }
+
+    pub fn set_label(&mut self, index: usize, label: &str, global: &Global) {
+        self.tabs[index].label.content = label.to_owned();
+        self.style_tab(index);
+        self.relayout();
+        global.damage(self.layout);
+    }
+
+    pub fn content(&self, index: usize) -> &dyn Component {
+        self.tabs[index].content.as_ref()
+    }
+
+    pub fn content_mut(&mut self, index: usize) -> &mut dyn Component {
+        self.tabs[index].content.as_mut()
+    }
+
+    fn style_tab(&mut self, index: usize) {
+        let theme = &self.theme;
+        let tab = &mut self.tabs[index];
+        tab.label.set_font_size(theme.typography.body_size);
+        tab.label.color = theme.palette.text;
+        if let Some(close) = &mut tab.close {
+            close.set_color(theme.palette.text);
+        }
+        let width = tab.label.width().ceil() + Self::PADDING * 2.0 + Self::close_width(tab);
+        tab.width = width.clamp(Self::MIN_TAB_WIDTH, Self::MAX_TAB_WIDTH);
+    }
+
+    fn header_height(&self) -> f32 {
+        (self.theme.typography.body_size * 1.25 + Self::PADDING).ceil()
+    }
+
+    fn header_layout(&self) -> Layout {
+        Layout {
+            position: self.layout.position,
+            size: Vector2::new(self.layout.size.x, self.header_height()),
+        }
+    }
+
+    fn content_layout(&self) -> Layout {
+        let header_height = self.header_height();
+        Layout {
+            position: self.layout.position + Vector2::new(0.0, header_height),
+            size: Vector2::new(
+                self.layout.size.x,
+                (self.layout.size.y - header_height).max(0.0),
+            ),
+        }
+    }
+
+    /// Left edge of tab `index` relative to the unscrolled strip
+    fn tab_offset(&self, index: usize) -> f32 {
+        self.tabs[..index]
+            .iter()
+            .map(|tab| tab.width + Self::SPACING)
+            .sum()
+    }
+
+    fn strip_width(&self) -> f32 {
+        self.tab_offset(self.len()) - Self::SPACING
+    }
+
+    fn max_scroll(&self) -> f32 {
+        (self.strip_width() - self.layout.size.x).max(0.0)
+    }
+
+    /// Where tab `index` is drawn, following the cursor while it is dragged
+    fn tab_layout(&self, index: usize) -> Layout {
+        let x = match &self.drag {
+            Some(drag) if drag.moved && drag.index == index => (drag.cursor_x - drag.grab)
+                .max(self.layout.position.x)
+                .min(self.layout.position.x + self.layout.size.x - self.tabs[index].width),
+            _ => self.layout.position.x + self.tab_offset(index) - self.scroll,
+        };
+        Layout {
+            position: Vector2::new(x, self.layout.position.y),
+            size: Vector2::new(self.tabs[index].width, self.header_height()),
+        }
+    }
+
+    fn close_layout(&self, index: usize) -> Layout {
+        let tab = self.tab_layout(index);
+        Layout {
+            position: tab.position
+                + Vector2::new(
+                    tab.size.x - Self::PADDING / 2.0 - Self::CLOSE_SIZE,
+                    (tab.size.y - Self::CLOSE_SIZE) / 2.0,
+                ),
+            size: Vector2::new(Self::CLOSE_SIZE, Self::CLOSE_SIZE),
+        }
+    }
+
+    /// Room the close button takes up at the right of a header
+    fn close_width(tab: &Tab) -> f32 {
+        if tab.close.is_some() {
+            Self::CLOSE_SIZE + Self::PADDING / 2.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Part of header `index` its label is drawn in
+    fn label_clip(&self, index: usize) -> Layout {
+        let mut layout = self.tab_layout(index);
+        layout.size.x -= Self::close_width(&self.tabs[index]) + Self::PADDING / 2.0;
+        layout
+    }
+
+    fn relayout(&mut self) {
+        let font_size = self.theme.typography.body_size;
+        for index in 0..self.len() {
+            let layout = self.tab_layout(index);
+            let close = self.close_layout(index);
+            let tab = &mut self.tabs[index];
+            // wide enough to never wrap, `label_clip` cuts off what doesn't fit
+            let width = tab.label.width().ceil() + 1.0;
+            tab.label.set_layout(Layout {
+                position: layout.position
+                    + Vector2::new(Self::PADDING, (layout.size.y - font_size * 1.25) / 2.0),
+                size: Vector2::new(width, font_size * 1.25),
+            });
+            if let Some(icon) = &mut tab.close {
+                icon.set_layout(close);
+            }
+        }
+    }
+
+    fn layout_contents(&mut self) {
+        let content = self.content_layout();
+        for tab in &mut self.tabs {
+            tab.content.set_layout(content);
+        }
+    }
+
+    fn scroll_into_view(&mut self, index: usize) {
+        let start = self.tab_offset(index);
+        let end = start + self.tabs[index].width;
+        if start < self.scroll {
+            self.scroll = start;
+        } else if end > self.scroll + self.layout.size.x {
+            self.scroll = end - self.layout.size.x;
+        }
+        self.scroll = self.scroll.max(0.0).min(self.max_scroll());
+        self.relayout();
+    }
+
+    fn tab_at_cursor(&self, global: &Global) -> Option<(usize, bool)> {
+        let cursor_position = global.cursor_position();
+        if !self.header_layout().contains(&cursor_position) {
+            return None;
+        }
+        let index =
+            (0..self.len()).find(|&index| self.tab_layout(index).contains(&cursor_position))?;
+        let on_close =
+            self.tabs[index].close.is_some() && self.close_layout(index).contains(&cursor_position);
+        Some((index, on_close))
+    }
+
+    fn select(&mut self, index: usize, global: &Global) {
+        if self.selected != Some(index) {
+            self.set_selected(index, global);
+            self.on_change.call(index, global);
+        }
+    }
+
+    /// Moves the dragged tab to the cursor, reordering as it passes its neighbours
+    fn drag(&mut self, global: &Global) {
+        let drag = match &self.drag {
+            Some(drag) => drag,
+            None => return,
+        };
+        let index = drag.index;
+        let left = drag.cursor_x - drag.grab;
+        let center = left + self.tabs[index].width / 2.0 + self.scroll - self.layout.position.x;
+
+        // swap with a neighbour once the dragged tab's center passes the middle of it
+        let mut target = index;
+        while target > 0 {
+            let neighbour = target - 1;
+            let middle = self.tab_offset(neighbour) + self.tabs[neighbour].width / 2.0;
+            if center >= middle {
+                break;
+            }
+            target = neighbour;
+        }
+        while target + 1 < self.len() {
+            let neighbour = target + 1;
+            let middle = self.tab_offset(neighbour) + self.tabs[neighbour].width / 2.0;
+            if center <= middle {
+                break;
+            }
+            target = neighbour;
+        }
+        if target != index {
+            let tab = self.tabs.remove(index);
+            self.tabs.insert(target, tab);
+            if let Some(drag) = &mut self.drag {
+                drag.index = target;
+            }
+            self.selected = self.selected.map(|selected| {
+                if selected == index {
+                    target
+                } else if index < selected && selected <= target {
+                    selected - 1
+                } else if target <= selected && selected < index {
+                    selected + 1
+                } else {
+                    selected
+                }
+            });
+        }
+        self.relayout();
+        global.damage(self.header_layout());
+    }
+
+    fn handle_window_event(&mut self, event: &WindowEvent, global: &Global) {
+        let cursor_position = global.cursor_position();
+        let cursor_x = cursor_position.x as f32;
+        match event {
+            WindowEvent::CursorMoved { .. } => {
+                if let Some(drag) = &mut self.drag {
+                    drag.cursor_x = cursor_x;
+                    drag.moved |= (cursor_x - drag.press_x).abs() >= Self::DRAG_THRESHOLD;
+                    if drag.moved {
+                        self.drag(global);
+                    }
+                }
+                let hovered = self.tab_at_cursor(global);
+                if self.hovered != hovered {
+                    self.hovered = hovered;
+                    global.damage(self.header_layout());
+                }
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } => match self.tab_at_cursor(global) {
+                Some((index, true)) => self.pressed_close = Some(index),
+                Some((index, false)) => {
+                    self.select(index, global);
+                    self.drag = Some(TabDrag {
+                        index,
+                        grab: cursor_x - self.tab_layout(index).position.x,
+                        origin: index,
+                        press_x: cursor_x,
+                        cursor_x,
+                        moved: false,
+                    });
+                }
+                None => {}
+            },
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Left,
+                ..
+            } => {
+                if let Some(drag) = self.drag.take() {
+                    self.scroll_into_view(drag.index);
+                    global.damage(self.header_layout());
+                    if drag.index != drag.origin {
+                        self.on_reorder.call((drag.origin, drag.index), global);
+                    }
+                } else if let Some(index) = self.pressed_close.take() {
+                    if self.tab_at_cursor(global) != Some((index, true)) {
+                        return;
+                    }
+                    if self.on_close.is_set() {
+                        self.on_close.call(index, global);
+                    } else {
+                        self.remove_tab(index, global);
+                    }
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. }
+                if self.header_layout().contains(&cursor_position) =>
+            {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x + y) * Self::LINE_WIDTH,
+                    MouseScrollDelta::PixelDelta(position) => {
+                        (position.x + position.y) as f32 / global.scale_factor as f32
+                    }
+                };
+                let scroll = (self.scroll - delta).max(0.0).min(self.max_scroll());
+                if scroll != self.scroll {
+                    self.scroll = scroll;
+                    self.relayout();
+                    global.damage(self.header_layout());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Component for TabView {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        let palette = &self.theme.palette;
+        let header = self.header_layout();
+        proxy.draw_rounded_rect(&RoundedRect {
+            layout: header,
+            fill: palette.surface.into(),
+            round_radius: 0.0,
+            border_width: 0.0,
+            border_color: Vector4::zeros(),
+        });
+
+        proxy.with_clip(header, |proxy| {
+            let dragged = self
+                .drag
+                .as_ref()
+                .filter(|drag| drag.moved)
+                .map(|drag| drag.index);
+            // the dragged header goes last so it floats above the others
+            let order = (0..self.len())
+                .filter(|&index| Some(index) != dragged)
+                .chain(dragged);
+            for index in order {
+                let tab = &self.tabs[index];
+                let layout = self.tab_layout(index);
+                let selected = self.selected == Some(index);
+                let fill = if selected {
+                    palette.window_background
+                } else if matches!(self.hovered, Some((hovered, _)) if hovered == index) {
+                    palette.button_hovered
+                } else {
+                    palette.button_background
+                };
+                proxy.draw_rounded_rect(&RoundedRect {
+                    layout,
+                    fill: fill.into(),
+                    round_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: Vector4::zeros(),
+                });
+                if selected {
+                    proxy.draw_rounded_rect(&RoundedRect {
+                        layout: Layout {
+                            position: layout.position
+                                + Vector2::new(0.0, layout.size.y - Self::INDICATOR_HEIGHT),
+                            size: Vector2::new(layout.size.x, Self::INDICATOR_HEIGHT),
+                        },
+                        fill: palette.accent.into(),
+                        round_radius: 0.0,
+                        border_width: 0.0,
+                        border_color: Vector4::zeros(),
+                    });
+                }
+
+                proxy.with_clip(self.label_clip(index), |proxy| tab.label.draw(proxy));
+                proxy.with_clip(layout, |proxy| {
+                    if let Some(close) = &tab.close {
+                        if self.hovered == Some((index, true)) {
+                            proxy.draw_rounded_rect(&RoundedRect {
+                                layout: close.layout,
+                                fill: palette.button_pressed.into(),
+                                round_radius: Self::CLOSE_SIZE / 2.0,
+                                border_width: 0.0,
+                                border_color: Vector4::zeros(),
+                            });
+                        }
+                        close.draw(proxy);
+                    }
+                });
+            }
+        });
+
+        if let Some(selected) = self.selected {
+            let content = self.content_layout();
+            proxy.with_clip(content, |proxy| self.tabs[selected].content.draw(proxy));
+        }
+    }
+
+    fn update(&mut self, global: &Global) {
+        for tab in &mut self.tabs {
+            tab.label.update(global);
+            if let Some(close) = &mut tab.close {
+                close.update(global);
+            }
+            tab.content.update(global);
+        }
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        match event {
+            Event::UserEvent(CustomEvent::ThemeChanged) => {
+                self.theme = global.theme();
+                for index in 0..self.len() {
+                    self.style_tab(index);
+                }
+                self.scroll = self.scroll.min(self.max_scroll());
+                self.relayout();
+                self.layout_contents();
+                global.damage(self.layout);
+            }
+            Event::WindowEvent { event, .. } => self.handle_window_event(event, global),
+            _ => {}
+        }
+
+        match event {
+            // hidden tabs still follow theme and config changes
+            Event::UserEvent(_) => {
+                for tab in &mut self.tabs {
+                    tab.content.handle_event(event, global);
+                }
+            }
+            _ => {
+                if let Some(selected) = self.selected {
+                    self.tabs[selected].content.handle_event(event, global);
+                }
+            }
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.scroll = self.scroll.min(self.max_scroll());
+        self.relayout();
+        self.layout_contents();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::Plane;
+    use crate::headless::{test_font, Headless};
+
+    use glium::glutin::dpi::{LogicalPosition, PhysicalSize};
+
+    use std::cell::RefCell;
+
+    fn headless() -> (Headless<TabView>, Rc<RefCell<Vec<(usize, usize)>>>) {
+        let size = PhysicalSize::new(400, 200);
+        let mut headless = Headless::with_root(test_font(), size, 1.0, |global| {
+            let mut tabs = TabView::new(global);
+            for label in ["one", "two", "three"].iter() {
+                tabs.add_tab(label, Box::new(Plane::new(global)), false, global);
+            }
+            tabs.set_layout(Layout {
+                position: Vector2::zeros(),
+                size: Vector2::new(400.0, 200.0),
+            });
+            tabs
+        });
+        let reorders = Rc::new(RefCell::new(Vec::new()));
+        let log = reorders.clone();
+        headless
+            .root
+            .on_reorder
+            .set(move |reorder, _| log.borrow_mut().push(reorder));
+        headless.render();
+        (headless, reorders)
+    }
+
+    fn labels(tabs: &TabView) -> Vec<&str> {
+        tabs.tabs
+            .iter()
+            .map(|tab| tab.label.content.as_str())
+            .collect()
+    }
+
+    /// Presses on the middle of header `index` and releases `dx` further along
+    fn drag(headless: &mut Headless<TabView>, index: usize, dx: f32) {
+        let layout = headless.root.tab_layout(index);
+        let center = layout.position + layout.size / 2.0;
+        let (x, y) = (center.x as f64, center.y as f64);
+        headless.move_cursor(LogicalPosition::new(x, y));
+        headless.mouse_input(ElementState::Pressed, MouseButton::Left);
+        for step in 1..=4 {
+            let x = x + dx as f64 * step as f64 / 4.0;
+            headless.move_cursor(LogicalPosition::new(x, y));
+        }
+        headless.mouse_input(ElementState::Released, MouseButton::Left);
+    }
+
+    #[test]
+    fn dragging_a_header_past_its_neighbours_reorders_the_tabs() {
+        let (mut headless, reorders) = headless();
+        let strip = headless.root.strip_width();
+
+        drag(&mut headless, 0, strip);
+        assert_eq!(labels(&headless.root), ["two", "three", "one"]);
+        assert_eq!(headless.root.selected(), Some(2));
+
+        drag(&mut headless, 2, -strip);
+        assert_eq!(labels(&headless.root), ["one", "two", "three"]);
+        assert_eq!(headless.root.selected(), Some(0));
+        assert_eq!(*reorders.borrow(), [(0, 2), (2, 0)]);
+    }
+
+    #[test]
+    fn a_header_swaps_once_its_center_passes_the_neighbours_middle() {
+        let (mut headless, reorders) = headless();
+        let (one, two) = (headless.root.tab_layout(0), headless.root.tab_layout(1));
+        // the center of "one" stops short of the middle of "two", then just passes it
+        let to_middle = two.position.x + two.size.x / 2.0 - (one.position.x + one.size.x / 2.0);
+
+        drag(&mut headless, 0, to_middle - 1.0);
+        assert_eq!(labels(&headless.root), ["one", "two", "three"]);
+        drag(&mut headless, 0, to_middle + 1.0);
+        assert_eq!(labels(&headless.root), ["two", "one", "three"]);
+        assert_eq!(*reorders.borrow(), [(0, 1)]);
+    }
+
+    #[test]
+    fn small_moves_select_without_dragging() {
+        let (mut headless, reorders) = headless();
+
+        drag(&mut headless, 1, TabView::DRAG_THRESHOLD - 1.0);
+        assert_eq!(labels(&headless.root), ["one", "two", "three"]);
+        assert_eq!(headless.root.selected(), Some(1));
+        assert!(reorders.borrow().is_empty());
+    }
+}
@@ -1,64 +1,43 @@
+use crate::render_context::GlyphRun;
 use crate::Global;
 use crate::RenderContextProxy;
 use crate::{component::Layout, Component};
 
-use glium::Blend;
-use glium::Surface;
-use glium::VertexBuffer;
-use glium::{index::NoIndices, program::Program};
-use glium::{uniform, DrawParameters};
-use glium::{uniforms::MagnifySamplerFilter, Display};
-use nalgebra::Vector4;
+use nalgebra::{Vector2, Vector4};
 
 use std::ops::{Deref, DerefMut};
 
 mod raw_text;
 
-use raw_text::FontRenderInfo;
 use raw_text::RawText;
 
 pub struct Text {
-    display: Display,
-
     inner: RawText<'static>,
     inner_edited: bool,
 
-    program: Program,
-    vbo: Option<VertexBuffer<FontRenderInfo>>,
-
     pub color: Vector4<f32>,
     pub layout: Layout,
 }
 
 impl Text {
-    const VSRC: &'static str = include_str!("text/text.vert");
-    const FSRC: &'static str = include_str!("text/text.frag");
-
     fn from_raw_text(raw_text: RawText<'static>, global: &Global) -> Self {
-        let display = global.display().clone();
-        let program = Program::from_source(&display, Self::VSRC, Self::FSRC, None)
-            .unwrap_or_else(|err| panic!(format!("{:#?}", err)));
-        let vbo = None;
-        let color = Vector4::new(0.0, 0.0, 0.0, 1.0);
+        let color = global.theme().palette.text;
 
         Self {
-            display,
             inner: raw_text,
             inner_edited: true,
-            program,
-            vbo,
             color,
             layout: Layout::default(),
         }
     }
 
     pub fn new(global: &Global) -> Self {
-        let raw_text = RawText::from_internal(global.display(), &global.font);
+        let raw_text = RawText::from_internal(&global.font);
         Self::from_raw_text(raw_text, global)
     }
 
     pub fn new_cursored(global: &Global) -> Self {
-        let raw_text = RawText::from_internal(global.display(), &global.font).with_cursor(global);
+        let raw_text = RawText::from_internal(&global.font).with_cursor(global);
         Self::from_raw_text(raw_text, global)
     }
 
@@ -66,20 +45,24 @@ impl Text {
         self.inner.set_cursor_visibility(visibility)
     }
 
+    pub fn set_cursor_color(&mut self, color: Vector4<f32>) -> bool {
+        self.inner.set_cursor_color(color)
+    }
+
     pub fn set_font_size(&mut self, font_size: f32) {
+        self.inner_edited = true;
         self.inner.set_font_size(font_size);
     }
 
-    pub fn update_vbo(&mut self) {
+    pub fn update_glyphs(&mut self, global: &Global) {
+        // follow font changes made through `Global::set_config`
+        if self.inner.set_font(&global.font) {
+            self.inner_edited = true;
+        }
         if self.inner_edited {
             self.inner_edited = false;
-            self.inner.update_cache();
-            let render_info = self.inner.to_font_render_info();
-            self.vbo = Some(
-                VertexBuffer::new(&self.display, &render_info)
-                    .expect("failed to create vertex buffer"),
-            );
-            self.display.gl_window().window().request_redraw();
+            self.inner.update_cache(global.scale_factor);
+            global.damage(self.layout);
         }
     }
 
@@ -90,47 +73,37 @@ impl Text {
 
 impl Component for Text {
     fn draw(&self, proxy: &mut RenderContextProxy) {
-        let vbo = self.vbo.as_ref().expect("vbo not initialized");
-        let resolution: [f32; 2] = proxy.frame_buffer_size().into();
-        let scale_factor = proxy.scale_factor() as f32;
-        let position = self.layout.position;
-        let color: [f32; 4] = self.color.into();
-
-        proxy
-            .frame()
-            .draw(
-                vbo,
-                NoIndices(glium::index::PrimitiveType::TrianglesList),
-                &self.program,
-                &uniform! {
-                    u_resolution: resolution,
-                    u_color: color,
-                    u_glyph_texture: self.inner.create_texture(MagnifySamplerFilter::Linear),
-                    u_scale_factor: scale_factor,
-                    u_position: [position.x, position.y],
-                },
-                &DrawParameters {
-                    blend: Blend::alpha_blending(),
-                    ..DrawParameters::default()
-                },
-            )
-            .expect("failed to draw text");
+        proxy.draw_glyphs(&GlyphRun {
+            position: self.layout.position,
+            font: self.inner.font(),
+            glyphs: self.inner.glyphs(),
+            color: self.color,
+        });
         if let Some(cursor) = self.inner.cursor.as_ref() {
             cursor.draw(proxy);
         }
     }
 
-    fn update(&mut self, _global: &Global) {
-        self.update_vbo();
+    fn update(&mut self, global: &Global) {
+        self.update_glyphs(global);
     }
 
     fn set_layout(&mut self, layout: Layout) {
+        if layout.size.x != self.layout.size.x {
+            self.inner_edited = true;
+        }
         self.inner.set_wrap_bound(layout.size.x as u32);
         self.layout = layout;
         if let Some(cursor) = self.inner.cursor.as_mut() {
             cursor.set_layout(layout);
         }
     }
+
+    /// Size of the content on a single line, wide enough to never wrap
+    fn intrinsic_size(&self) -> Vector2<f32> {
+        let size = self.measure(None);
+        Vector2::new(size.x.ceil() + 1.0, size.y.ceil())
+    }
 }
 
 impl Deref for Text {
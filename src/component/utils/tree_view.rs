@@ -0,0 +1,599 @@
+use super::{Icon, ListRow, ListSource, ListView, SelectionMode, Text};
+use crate::component::{Callback, Layout, Mailbox};
+use crate::custom_event::EventProxy;
+use crate::Component;
+use crate::Global;
+use crate::RenderContextProxy;
+
+use glium::glutin::event::{ElementState, Event, MouseButton, VirtualKeyCode, WindowEvent};
+use nalgebra::Vector2;
+
+use std::cell::RefCell;
+
+const COLLAPSED: &str =
+    r#"<svg viewBox="0 0 16 16"><polygon points="6,4 11,8 6,12" fill="currentColor"/></svg>"#;
+const EXPANDED: &str =
+    r#"<svg viewBox="0 0 16 16"><polygon points="4,6 12,6 8,11" fill="currentColor"/></svg>"#;
+
+/// Hierarchy shown by a `TreeView`. A node's children are only asked for the first time it is
+/// expanded
+pub trait TreeProvider {
+    type Node: Clone + PartialEq + 'static;
+
+    fn roots(&mut self) -> Vec<Self::Node>;
+
+    /// Whether `node` gets a disclosure triangle, before its children are loaded
+    fn has_children(&self, node: &Self::Node) -> bool;
+
+    fn children(&mut self, node: &Self::Node) -> Vec<Self::Node>;
+
+    fn label(&self, node: &Self::Node) -> String;
+}
+
+struct TreeNode<N> {
+    node: N,
+    depth: usize,
+    parent: Option<usize>,
+    expandable: bool,
+    expanded: bool,
+    /// Ids of the children, once loaded
+    children: Option<Vec<usize>>,
+}
+
+/// Loaded nodes, with the visible ones flattened into list rows
+struct TreeRows<P: TreeProvider> {
+    provider: P,
+    /// Every loaded node, indexed by id
+    nodes: Vec<TreeNode<P::Node>>,
+    /// Node id shown in each row
+    rows: Vec<usize>,
+    /// Ids of nodes whose disclosure triangle was clicked
+    toggles: Mailbox<usize>,
+    /// Collapsed and expanded disclosure triangles, parsed once and cloned into rows
+    disclosures: RefCell<[Icon; 2]>,
+}
+
+impl<P: TreeProvider> TreeRows<P> {
+    fn load_roots(&mut self) {
+        self.nodes.clear();
+        let roots = self.provider.roots();
+        self.rows = self.add_nodes(roots, 0, None);
+    }
+
+    fn add_nodes(
+        &mut self,
+        nodes: Vec<P::Node>,
+        depth: usize,
+        parent: Option<usize>,
+    ) -> Vec<usize> {
+        nodes
+            .into_iter()
+            .map(|node| {
+                let id = self.nodes.len();
+                self.nodes.push(TreeNode {
+                    expandable: self.provider.has_children(&node),
+                    node,
+                    depth,
+                    parent,
+                    expanded: false,
+                    children: None,
+                });
+                id
+            })
+            .collect()
+    }
+
+    fn load_children(&mut self, id: usize) {
+        if self.nodes[id].children.is_none() {
+            let children = self.provider.children(&self.nodes[id].node);
+            let depth = self.nodes[id].depth + 1;
+            let children = self.add_nodes(children, depth, Some(id));
+            self.nodes[id].children = Some(children);
+        }
+    }
+
+    /// Appends the descendants of `id` that are shown while it is expanded
+    fn visible_descendants(&self, id: usize, rows: &mut Vec<usize>) {
+        for &child in self.nodes[id].children.iter().flatten() {
+            rows.push(child);
+            if self.nodes[child].expanded {
+                self.visible_descendants(child, rows);
+            }
+        }
+    }
+
+    fn row_of(&self, id: usize) -> Option<usize> {
+        self.rows.iter().position(|&row| row == id)
+    }
+}
+
+impl<P: TreeProvider> ListSource for TreeRows<P> {
+    fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn row(&self, index: usize, global: &Global) -> ListRow {
+        let id = self.rows[index];
+        let node = &self.nodes[id];
+        let theme = global.theme();
+
+        let mut label = Text::new(global);
+        label.content = self.provider.label(&node.node);
+        label.set_font_size(theme.typography.body_size);
+        let disclosure = if node.expandable {
+            let mut disclosures = self.disclosures.borrow_mut();
+            let icon = &mut disclosures[node.expanded as usize];
+            icon.set_color(theme.palette.text);
+            icon.rasterize_at(
+                Vector2::repeat(TreeRow::DISCLOSURE_SIZE),
+                global.scale_factor,
+            );
+            Some(icon.clone())
+        } else {
+            None
+        };
+
+        ListRow::Widget(Box::new(TreeRow {
+            label,
+            disclosure,
+            font_size: theme.typography.body_size,
+            depth: node.depth,
+            id,
+            toggles: self.toggles.clone(),
+        }))
+    }
+}
+
+/// Indented row with a disclosure triangle for nodes that have children
+struct TreeRow {
+    label: Text,
+    disclosure: Option<Icon>,
+    font_size: f32,
+    depth: usize,
+    id: usize,
+    toggles: Mailbox<usize>,
+}
+
+impl TreeRow {
+    const INDENT: f32 = 16.0;
+    const DISCLOSURE_SIZE: f32 = 16.0;
+    const PADDING: f32 = 4.0;
+}
+
+impl Component for TreeRow {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        if let Some(disclosure) = &self.disclosure {
+            disclosure.draw(proxy);
+        }
+        self.label.draw(proxy);
+    }
+
+    fn update(&mut self, global: &Global) {
+        if let Some(disclosure) = &mut self.disclosure {
+            disclosure.update(global);
+        }
+        self.label.update(global);
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        if let (
+            Some(disclosure),
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                ..
+            },
+        ) = (&self.disclosure, event)
+        {
+            if disclosure.layout.contains(&global.cursor_position()) {
+                self.toggles.post(self.id);
+            }
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        let indent = Self::PADDING + Self::INDENT * self.depth as f32;
+        let center_y = layout.position.y + layout.size.y / 2.0;
+        if let Some(disclosure) = &mut self.disclosure {
+            let half = Self::DISCLOSURE_SIZE / 2.0;
+            disclosure.set_layout(Layout {
+                position: Vector2::new(layout.position.x + indent, center_y - half),
+                size: Vector2::new(Self::DISCLOSURE_SIZE, Self::DISCLOSURE_SIZE),
+            });
+        }
+        let label_x = indent + Self::DISCLOSURE_SIZE + Self::PADDING;
+        let height = self.font_size * 1.25;
+        self.label.set_layout(Layout {
+            position: Vector2::new(layout.position.x + label_x, center_y - height / 2.0),
+            size: Vector2::new(layout.size.x - label_x, height),
+        });
+    }
+}
+
+/// Expandable hierarchy of nodes, built on a `ListView` so only visible rows exist. Left
+/// collapses the node under the keyboard cursor or moves to its parent, Right expands it or
+/// moves to its first child
+pub struct TreeView<P: TreeProvider> {
+    pub layout: Layout,
+    /// Invoked with the selected nodes whenever the user changes the selection
+    pub on_selection_change: Callback<Vec<P::Node>>,
+    /// Invoked with a node when it is double clicked or Enter is pressed on it
+    pub on_activate: Callback<P::Node>,
+    /// Invoked with a node and whether it is now expanded
+    pub on_expand: Callback<(P::Node, bool)>,
+
+    list: ListView<TreeRows<P>>,
+    selections: Mailbox<Vec<usize>>,
+    activations: Mailbox<usize>,
+}
+
+impl<P: TreeProvider> TreeView<P> {
+    pub fn new(provider: P, global: &Global) -> Self {
+        let mut rows = TreeRows {
+            provider,
+            nodes: Vec::new(),
+            rows: Vec::new(),
+            toggles: Mailbox::new(),
+            disclosures: RefCell::new([
+                Icon::from_str(COLLAPSED, global).expect("invalid built-in icon"),
+                Icon::from_str(EXPANDED, global).expect("invalid built-in icon"),
+            ]),
+        };
+        rows.load_roots();
+
+        let selections = Mailbox::new();
+        let activations = Mailbox::new();
+        let mut list = ListView::new(rows, global);
+        list.on_selection_change
+            .post(&selections, |selection| selection);
+        list.on_activate.post(&activations, |index| index);
+
+        TreeView {
+            layout: Layout::default(),
+            on_selection_change: Callback::new(),
+            on_activate: Callback::new(),
+            on_expand: Callback::new(),
+            list,
+            selections,
+            activations,
+        }
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.list.source.provider
+    }
+
+    pub fn provider_mut(&mut self) -> &mut P {
+        &mut self.list.source.provider
+    }
+
+    /// Asks the provider for the roots again, collapsing every node
+    pub fn reload(&mut self, global: &Global) {
+        self.list.source.load_roots();
+        self.list.set_selection(None, global);
+        self.list.set_cursor(None, global);
+        self.list.reload(global);
+    }
+
+    pub fn selection_mode(&self) -> SelectionMode {
+        self.list.selection_mode
+    }
+
+    pub fn set_selection_mode(&mut self, selection_mode: SelectionMode) {
+        self.list.selection_mode = selection_mode;
+    }
+
+    pub fn selection(&self) -> Vec<P::Node> {
+        self.nodes_at(self.list.selection().iter().copied())
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.list.is_focused()
+    }
+
+    /// Expands or collapses a loaded node without invoking `on_expand`
+    pub fn set_expanded(&mut self, node: &P::Node, expanded: bool, global: &Global) {
+        let tree = &self.list.source;
+        if let Some(id) = tree.nodes.iter().position(|loaded| loaded.node == *node) {
+            self.expand(id, expanded, global);
+        }
+    }
+
+    pub fn is_expanded(&self, node: &P::Node) -> bool {
+        self.list
+            .source
+            .nodes
+            .iter()
+            .any(|loaded| loaded.expanded && loaded.node == *node)
+    }
+
+    fn nodes_at(&self, rows: impl Iterator<Item = usize>) -> Vec<P::Node> {
+        let tree = &self.list.source;
+        rows.map(|row| tree.nodes[tree.rows[row]].node.clone())
+            .collect()
+    }
+
+    /// Shows or hides the children of node `id`, keeping the selection on the same nodes.
+    /// Returns whether anything changed
+    fn expand(&mut self, id: usize, expanded: bool, global: &Global) -> bool {
+        let tree = &mut self.list.source;
+        let node = &tree.nodes[id];
+        if !node.expandable || node.expanded == expanded {
+            return false;
+        }
+
+        let selected: Vec<usize> = self
+            .list
+            .selection()
+            .iter()
+            .map(|&row| self.list.source.rows[row])
+            .collect();
+        let cursor = self.list.cursor().map(|row| self.list.source.rows[row]);
+
+        let tree = &mut self.list.source;
+        if expanded {
+            tree.load_children(id);
+        }
+        tree.nodes[id].expanded = expanded;
+        if let Some(row) = tree.row_of(id) {
+            if expanded {
+                let mut descendants = Vec::new();
+                tree.visible_descendants(id, &mut descendants);
+                tree.rows.splice(row + 1..row + 1, descendants);
+            } else {
+                let depth = tree.nodes[id].depth;
+                let end = tree.rows[row + 1..]
+                    .iter()
+                    .position(|&child| tree.nodes[child].depth <= depth)
+                    .map_or(tree.rows.len(), |count| row + 1 + count);
+                tree.rows.drain(row + 1..end);
+            }
+        }
+        self.list.reload(global);
+
+        // collapsing moves the selection off hidden nodes
+        let tree = &self.list.source;
+        let rows: Vec<usize> = selected.iter().filter_map(|&id| tree.row_of(id)).collect();
+        let cursor = cursor
+            .and_then(|id| tree.row_of(id))
+            .or_else(|| tree.row_of(id));
+        let lost = rows.len() != selected.len();
+        self.list.set_selection(rows.iter().copied(), global);
+        self.list.set_cursor(cursor, global);
+        if lost {
+            let selection = self.nodes_at(rows.into_iter());
+            self.on_selection_change.call(selection, global);
+        }
+        true
+    }
+
+    /// Toggles node `id` on behalf of the user, invoking `on_expand`
+    fn toggle(&mut self, id: usize, expanded: bool, global: &Global) {
+        if self.expand(id, expanded, global) {
+            let node = self.list.source.nodes[id].node.clone();
+            self.on_expand.call((node, expanded), global);
+        }
+    }
+
+    /// Selects `row` on behalf of the user and brings it into view
+    fn select_row(&mut self, row: usize, global: &Global) {
+        self.list.set_selection(Some(row), global);
+        self.list.scroll_to_row(row);
+        let selection = self.nodes_at(Some(row).into_iter());
+        self.on_selection_change.call(selection, global);
+    }
+
+    fn handle_key(&mut self, key: VirtualKeyCode, global: &Global) {
+        let row = match self.list.cursor() {
+            Some(row) => row,
+            None => return,
+        };
+        let id = self.list.source.rows[row];
+        let node = &self.list.source.nodes[id];
+        match key {
+            VirtualKeyCode::Right if node.expandable && !node.expanded => {
+                self.toggle(id, true, global);
+            }
+            VirtualKeyCode::Right if node.expanded && row + 1 < self.list.source.len() => {
+                let child = self.list.source.rows[row + 1];
+                if self.list.source.nodes[child].parent == Some(id) {
+                    self.select_row(row + 1, global);
+                }
+            }
+            VirtualKeyCode::Left if node.expanded => self.toggle(id, false, global),
+            VirtualKeyCode::Left => {
+                if let Some(parent) = node
+                    .parent
+                    .and_then(|parent| self.list.source.row_of(parent))
+                {
+                    self.select_row(parent, global);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<P: TreeProvider> Component for TreeView<P> {
+    fn draw(&self, proxy: &mut RenderContextProxy) {
+        self.list.draw(proxy);
+    }
+
+    fn update(&mut self, global: &Global) {
+        self.list.update(global);
+    }
+
+    fn handle_event(&mut self, event: EventProxy, global: &Global) {
+        self.list.handle_event(event, global);
+
+        // selections refer to the rows before any toggle below moves them
+        for selection in self.selections.drain() {
+            let selection = self.nodes_at(selection.into_iter());
+            self.on_selection_change.call(selection, global);
+        }
+        for row in self.activations.drain() {
+            let node = self.nodes_at(Some(row).into_iter()).remove(0);
+            self.on_activate.call(node, global);
+        }
+        for id in self.list.source.toggles.drain() {
+            let expanded = !self.list.source.nodes[id].expanded;
+            self.toggle(id, expanded, global);
+        }
+
+        if let Event::WindowEvent {
+            event: WindowEvent::KeyboardInput { input, .. },
+            ..
+        } = event
+        {
+            if self.list.is_focused() && input.state == ElementState::Pressed {
+                if let Some(key) = input.virtual_keycode {
+                    self.handle_key(key, global);
+                }
+            }
+        }
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.list.set_layout(layout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{test_font, Headless};
+
+    use glium::glutin::dpi::{LogicalPosition, PhysicalSize};
+
+    use std::rc::Rc;
+
+    /// Paths such as `a/1`, noting the nodes whose children were asked for
+    struct Paths {
+        loaded: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl TreeProvider for Paths {
+        type Node = String;
+
+        fn roots(&mut self) -> Vec<String> {
+            vec!["a".into(), "b".into()]
+        }
+
+        fn has_children(&self, node: &String) -> bool {
+            node != "b" && node.matches('/').count() < 2
+        }
+
+        fn children(&mut self, node: &String) -> Vec<String> {
+            self.loaded.borrow_mut().push(node.clone());
+            (1..=2).map(|i| format!("{}/{}", node, i)).collect()
+        }
+
+        fn label(&self, node: &String) -> String {
+            node.clone()
+        }
+    }
+
+    fn headless() -> (Headless<TreeView<Paths>>, Rc<RefCell<Vec<String>>>) {
+        let loaded = Rc::new(RefCell::new(Vec::new()));
+        let provider = Paths {
+            loaded: loaded.clone(),
+        };
+        let size = PhysicalSize::new(200, 200);
+        let mut headless = Headless::with_root(test_font(), size, 1.0, |global| {
+            let mut tree = TreeView::new(provider, global);
+            tree.set_layout(Layout {
+                position: Vector2::zeros(),
+                size: Vector2::new(200.0, 200.0),
+            });
+            tree
+        });
+        headless.render();
+        (headless, loaded)
+    }
+
+    fn rows(tree: &TreeView<Paths>) -> Vec<&str> {
+        let rows = &tree.list.source;
+        rows.rows
+            .iter()
+            .map(|&id| rows.nodes[id].node.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn children_are_loaded_on_first_expanding_only() {
+        let (mut headless, loaded) = headless();
+        assert!(loaded.borrow().is_empty());
+
+        let (tree, global) = (&mut headless.root, &headless.global);
+        tree.set_expanded(&"a".into(), true, global);
+        assert_eq!(rows(tree), ["a", "a/1", "a/2", "b"]);
+        tree.set_expanded(&"a/2".into(), true, global);
+        assert_eq!(rows(tree), ["a", "a/1", "a/2", "a/2/1", "a/2/2", "b"]);
+        assert_eq!(*loaded.borrow(), ["a", "a/2"]);
+
+        // collapsing hides the whole subtree, expanding again shows it as it was
+        tree.set_expanded(&"a".into(), false, global);
+        assert_eq!(rows(tree), ["a", "b"]);
+        tree.set_expanded(&"a".into(), true, global);
+        assert_eq!(rows(tree), ["a", "a/1", "a/2", "a/2/1", "a/2/2", "b"]);
+        assert_eq!(*loaded.borrow(), ["a", "a/2"]);
+
+        // leaves and nodes without children never ask
+        tree.set_expanded(&"b".into(), true, global);
+        tree.set_expanded(&"a/2/1".into(), true, global);
+        assert_eq!(*loaded.borrow(), ["a", "a/2"]);
+    }
+
+    #[test]
+    fn arrow_keys_walk_the_tree() {
+        let (mut headless, _) = headless();
+        let expanded = Rc::new(RefCell::new(Vec::new()));
+        let log = expanded.clone();
+        headless
+            .root
+            .on_expand
+            .set(move |(node, expanded), _| log.borrow_mut().push((node, expanded)));
+        headless.click(LogicalPosition::new(100.0, 5.0));
+        assert_eq!(headless.root.selection(), ["a"]);
+
+        let mut press = |key| {
+            headless.press_key(key);
+            headless.root.selection()
+        };
+        assert_eq!(press(VirtualKeyCode::Right), ["a"]);
+        assert_eq!(press(VirtualKeyCode::Right), ["a/1"]);
+        assert_eq!(press(VirtualKeyCode::Right), ["a/1"]);
+        assert_eq!(press(VirtualKeyCode::Right), ["a/1/1"]);
+        // a leaf goes on to its parent, which collapses before going on to its own
+        assert_eq!(press(VirtualKeyCode::Left), ["a/1"]);
+        assert_eq!(press(VirtualKeyCode::Left), ["a/1"]);
+        assert_eq!(press(VirtualKeyCode::Left), ["a"]);
+        assert_eq!(press(VirtualKeyCode::Down), ["a/1"]);
+        assert_eq!(
+            *expanded.borrow(),
+            [
+                ("a".to_owned(), true),
+                ("a/1".to_owned(), true),
+                ("a/1".to_owned(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn collapsing_moves_the_selection_off_hidden_rows() {
+        let (mut headless, _) = headless();
+        let (tree, global) = (&mut headless.root, &headless.global);
+        tree.set_expanded(&"a".into(), true, global);
+        tree.list.set_selection(Some(2), global);
+        assert_eq!(tree.selection(), ["a/2"]);
+
+        tree.set_expanded(&"a".into(), false, global);
+        assert!(tree.selection().is_empty());
+        assert_eq!(tree.list.cursor(), Some(0));
+    }
+}
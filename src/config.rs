@@ -0,0 +1,426 @@
+use crate::custom_event::CustomEvent;
+use crate::theme::{Palette, Theme};
+
+use glium::glutin::event::{ModifiersState, VirtualKeyCode};
+use glium::glutin::event_loop::EventLoopProxy;
+use nalgebra::{Vector2, Vector4};
+use serde::Deserialize;
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+mod keybinding;
+
+pub use keybinding::KeyBinding;
+
+/// Desktop settings read from `$XDG_CONFIG_HOME/wm/config.toml`, every key being optional
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub background: BackgroundConfig,
+    pub font: FontConfig,
+    pub theme: ThemeConfig,
+    pub window: WindowConfig,
+    pub tooltip: TooltipConfig,
+    pub keybindings: KeyBindings,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackgroundConfig {
+    /// Overrides the theme's desktop background
+    pub color: Option<Color>,
+    /// Image drawn over the color, relative to the config directory
+    pub wallpaper: Option<PathBuf>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FontConfig {
+    /// TrueType/OpenType file used instead of the built-in font
+    pub path: Option<PathBuf>,
+    pub title_size: Option<f32>,
+    pub body_size: Option<f32>,
+
+    #[serde(skip)]
+    pub face: Option<Rc<rusttype::Font<'static>>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeBase {
+    #[default]
+    Light,
+    Dark,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub base: ThemeBase,
+    pub palette: PaletteConfig,
+}
+
+/// Palette entries overriding the base theme's
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaletteConfig {
+    pub desktop_background: Option<Color>,
+    pub window_background: Option<Color>,
+    pub window_title: Option<Color>,
+    pub window_shadow: Option<Color>,
+    pub surface: Option<Color>,
+    pub text: Option<Color>,
+    pub button_background: Option<Color>,
+    pub button_hovered: Option<Color>,
+    pub button_pressed: Option<Color>,
+    pub button_disabled: Option<Color>,
+    pub button_text: Option<Color>,
+    pub disabled_text: Option<Color>,
+    pub input_background: Option<Color>,
+    pub input_text: Option<Color>,
+    pub control_border: Option<Color>,
+    pub accent: Option<Color>,
+}
+
+impl PaletteConfig {
+    fn apply(&self, palette: &mut Palette) {
+        macro_rules! apply {
+            ($($name:ident),*) => {
+                $(if let Some(color) = self.$name {
+                    palette.$name = color.0;
+                })*
+            };
+        }
+        apply!(
+            desktop_background,
+            window_background,
+            window_title,
+            window_shadow,
+            surface,
+            text,
+            button_background,
+            button_hovered,
+            button_pressed,
+            button_disabled,
+            button_text,
+            disabled_text,
+            input_background,
+            input_text,
+            control_border,
+            accent
+        );
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    /// Logical size new windows open with
+    pub width: f32,
+    pub height: f32,
+}
+
+impl WindowConfig {
+    pub fn size(&self) -> Vector2<f32> {
+        Vector2::new(self.width, self.height)
+    }
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig {
+            width: 180.0,
+            height: 240.0,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TooltipConfig {
+    /// Milliseconds the pointer rests over a component before its tooltip shows
+    pub delay: u64,
+}
+
+impl TooltipConfig {
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay)
+    }
+}
+
+impl Default for TooltipConfig {
+    fn default() -> Self {
+        TooltipConfig { delay: 500 }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    pub close_window: KeyBinding,
+    pub next_window: KeyBinding,
+    pub spawn_window: KeyBinding,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            close_window: KeyBinding::new(ModifiersState::CTRL, VirtualKeyCode::W),
+            next_window: KeyBinding::new(ModifiersState::ALT, VirtualKeyCode::Tab),
+            spawn_window: KeyBinding::new(ModifiersState::CTRL, VirtualKeyCode::N),
+        }
+    }
+}
+
+/// Color written as `#rrggbb` or `#rrggbbaa`
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Color(pub Vector4<f32>);
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid color `{}`, expected #rrggbb or #rrggbbaa", text);
+        let hex = text.strip_prefix('#').ok_or_else(invalid)?;
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |i: usize| {
+            hex.get(i * 2..i * 2 + 2)
+                .map_or(1.0, |c| u8::from_str_radix(c, 16).unwrap() as f32 / 255.0)
+        };
+        Ok(Color(Vector4::new(
+            channel(0),
+            channel(1),
+            channel(2),
+            channel(3),
+        )))
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Invalid(PathBuf, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            ConfigError::Parse(path, err) => write!(f, "{}: {}", path.display(), err),
+            ConfigError::Invalid(path, message) => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/wm/config.toml`, falling back to `~/.config/wm/config.toml`
+    pub fn path() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_home.join("wm").join("config.toml"))
+    }
+
+    /// Reads and validates the file at `path`, loading the font it names
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text =
+            std::fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_owned(), err))?;
+        let mut config: Config =
+            toml::from_str(&text).map_err(|err| ConfigError::Parse(path.to_owned(), err))?;
+
+        // relative paths are taken from the directory the config lives in
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        if let Some(wallpaper) = &mut config.background.wallpaper {
+            *wallpaper = base.join(&wallpaper);
+        }
+        if let Some(font_path) = &mut config.font.path {
+            *font_path = base.join(&font_path);
+        }
+
+        config
+            .validate()
+            .map_err(|message| ConfigError::Invalid(path.to_owned(), message))?;
+
+        if let Some(font_path) = &config.font.path {
+            let bytes =
+                std::fs::read(font_path).map_err(|err| ConfigError::Io(font_path.clone(), err))?;
+            let face = rusttype::Font::try_from_vec(bytes).ok_or_else(|| {
+                ConfigError::Invalid(font_path.clone(), "not a TrueType or OpenType font".into())
+            })?;
+            config.font.face = Some(Rc::new(face));
+        }
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(wallpaper) = &self.background.wallpaper {
+            if !wallpaper.is_file() {
+                return Err(format!(
+                    "background.wallpaper: {} is not a file",
+                    wallpaper.display()
+                ));
+            }
+        }
+
+        let positive = [
+            ("window.width", Some(self.window.width)),
+            ("window.height", Some(self.window.height)),
+            ("font.title_size", self.font.title_size),
+            ("font.body_size", self.font.body_size),
+        ];
+        for (key, value) in positive.iter() {
+            if let Some(value) = value {
+                if !(value.is_finite() && *value > 0.0) {
+                    return Err(format!("{} must be a positive number, got {}", key, value));
+                }
+            }
+        }
+
+        let bindings = [
+            ("keybindings.close_window", self.keybindings.close_window),
+            ("keybindings.next_window", self.keybindings.next_window),
+            ("keybindings.spawn_window", self.keybindings.spawn_window),
+        ];
+        for (i, (key, binding)) in bindings.iter().enumerate() {
+            if let Some((other, _)) = bindings[..i].iter().find(|(_, other)| other == binding) {
+                return Err(format!(
+                    "{} and {} are both bound to {}",
+                    other, key, binding
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Base theme with the configured colors and font sizes applied
+    pub fn theme(&self) -> Theme {
+        let mut theme = match self.theme.base {
+            ThemeBase::Light => Theme::light(),
+            ThemeBase::Dark => Theme::dark(),
+        };
+        self.theme.palette.apply(&mut theme.palette);
+        if let Some(color) = self.background.color {
+            theme.palette.desktop_background = color.0;
+        }
+        if let Some(size) = self.font.title_size {
+            theme.typography.title_size = size;
+            theme.title_height = theme.title_height.max(size);
+        }
+        if let Some(size) = self.font.body_size {
+            theme.typography.body_size = size;
+        }
+        theme
+    }
+}
+
+/// Polls `path` from a background thread and sends `CustomEvent::ConfigFileChanged` when it is
+/// created, modified or removed
+pub fn watch(path: PathBuf, proxy: EventLoopProxy<CustomEvent>) -> thread::JoinHandle<()> {
+    const INTERVAL: Duration = Duration::from_millis(500);
+
+    let modified = |path: &Path| -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+    };
+
+    thread::spawn(move || {
+        let mut last_modified = modified(&path);
+        loop {
+            thread::sleep(INTERVAL);
+            let current = modified(&path);
+            if current != last_modified {
+                last_modified = current;
+                if proxy.send_event(CustomEvent::ConfigFileChanged).is_err() {
+                    // the event loop is gone
+                    break;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Config, String> {
+        let config: Config = toml::from_str(text).map_err(|err| err.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    #[test]
+    fn colors_parse_with_and_without_alpha() {
+        let color = |text: &str| Color::try_from(text.to_owned()).map(|color| color.0);
+        assert_eq!(color("#ff0000"), Ok(Vector4::new(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(color("#00FF0000"), Ok(Vector4::new(0.0, 1.0, 0.0, 0.0)));
+        for invalid in ["ff0000", "#ff00", "#ff00000", "#gg0000", "#ff0000ff00"].iter() {
+            assert!(color(invalid).is_err(), "{} parsed", invalid);
+        }
+
+        let config = parse("[theme.palette]\naccent = \"#336699\"").unwrap();
+        assert_eq!(
+            config.theme().palette.accent,
+            Vector4::new(0.2, 0.4, 0.6, 1.0)
+        );
+    }
+
+    #[test]
+    fn sizes_must_be_positive() {
+        assert!(parse("[window]\nwidth = 320.0\nheight = 200.0").is_ok());
+        assert!(parse("[window]\nwidth = 0.0").is_err());
+        assert!(parse("[window]\nheight = -1.0").is_err());
+        assert!(parse("[font]\nbody_size = nan").is_err());
+        assert!(parse("[font]\ntitle_size = inf").is_err());
+    }
+
+    #[test]
+    fn a_key_bound_twice_is_rejected() {
+        let err = parse("[keybindings]\nclose_window = \"Ctrl+N\"").err();
+        assert_eq!(
+            err.as_deref(),
+            Some("keybindings.close_window and keybindings.spawn_window are both bound to Ctrl+N")
+        );
+        assert!(parse("[keybindings]\nclose_window = \"Ctrl+Shift+N\"").is_ok());
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(parse("[window]\nwidht = 320.0").is_err());
+        assert!(parse("[colors]\naccent = \"#336699\"").is_err());
+        assert!(parse("[keybindings]\nquit = \"Ctrl+Q\"").is_err());
+    }
+
+    #[test]
+    fn key_bindings_print_as_they_parse() {
+        for text in [
+            "Ctrl+Shift+7",
+            "Alt+Backspace",
+            "Super+F12",
+            "Ctrl+PageUp",
+            "Return",
+        ]
+        .iter()
+        {
+            let binding = KeyBinding::try_from(text.to_string()).unwrap();
+            assert_eq!(binding.to_string(), *text);
+        }
+    }
+}
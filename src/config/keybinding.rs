@@ -0,0 +1,131 @@
+use glium::glutin::event::{ModifiersState, VirtualKeyCode};
+
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Keys `0` to `9`, by digit
+const DIGITS: [VirtualKeyCode; 10] = [
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+];
+
+/// Key combination such as `Ctrl+Shift+W`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct KeyBinding {
+    pub modifiers: ModifiersState,
+    pub key: VirtualKeyCode,
+}
+
+impl KeyBinding {
+    pub fn new(modifiers: ModifiersState, key: VirtualKeyCode) -> Self {
+        KeyBinding { modifiers, key }
+    }
+
+    pub fn matches(&self, key: VirtualKeyCode, modifiers: ModifiersState) -> bool {
+        self.key == key && self.modifiers == modifiers
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let mut modifiers = ModifiersState::empty();
+        let mut key = None;
+        for part in text.split('+').map(str::trim) {
+            if key.is_some() {
+                return Err(format!("`{}`: the key must come last", text));
+            }
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= ModifiersState::CTRL,
+                "shift" => modifiers |= ModifiersState::SHIFT,
+                "alt" => modifiers |= ModifiersState::ALT,
+                "super" | "logo" | "meta" => modifiers |= ModifiersState::LOGO,
+                name => {
+                    key = Some(
+                        parse_key(name)
+                            .ok_or_else(|| format!("`{}`: unknown key `{}`", text, part))?,
+                    );
+                }
+            }
+        }
+        let key = key.ok_or_else(|| format!("`{}`: no key given", text))?;
+        Ok(KeyBinding { modifiers, key })
+    }
+}
+
+/// Writes the names parsing accepts, e.g. `Ctrl+Shift+7` or `Alt+Backspace`
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (ModifiersState::CTRL, "Ctrl"),
+            (ModifiersState::SHIFT, "Shift"),
+            (ModifiersState::ALT, "Alt"),
+            (ModifiersState::LOGO, "Super"),
+        ]
+        .iter()
+        {
+            if self.modifiers.contains(*modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match DIGITS.iter().position(|&digit| digit == self.key) {
+            Some(digit) => write!(f, "{}", digit),
+            None if self.key == VirtualKeyCode::Back => write!(f, "Backspace"),
+            // the other names parsed are those of the variants
+            None => write!(f, "{:?}", self.key),
+        }
+    }
+}
+
+/// Parses a lowercased key name
+fn parse_key(name: &str) -> Option<VirtualKeyCode> {
+    use VirtualKeyCode::*;
+
+    const LETTERS: [VirtualKeyCode; 26] = [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    ];
+    const FUNCTIONS: [VirtualKeyCode; 12] = [F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12];
+
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.clone().next()) {
+        if c.is_ascii_lowercase() {
+            return Some(LETTERS[(c as u8 - b'a') as usize]);
+        }
+        if c.is_ascii_digit() {
+            return Some(DIGITS[(c as u8 - b'0') as usize]);
+        }
+    }
+    if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse::<usize>().ok()) {
+        return FUNCTIONS.get(n.wrapping_sub(1)).copied();
+    }
+
+    let key = match name {
+        "tab" => Tab,
+        "space" => Space,
+        "enter" | "return" => Return,
+        "escape" | "esc" => Escape,
+        "backspace" => Back,
+        "delete" => Delete,
+        "insert" => Insert,
+        "home" => Home,
+        "end" => End,
+        "pageup" => PageUp,
+        "pagedown" => PageDown,
+        "up" => Up,
+        "down" => Down,
+        "left" => Left,
+        "right" => Right,
+        _ => return None,
+    };
+    Some(key)
+}
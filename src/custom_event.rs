@@ -1,11 +1,33 @@
 use crate::component::desktop::window_manager::WindowId;
+use crate::component::menu::MenuId;
 use glium::glutin::event;
+use glium::glutin::event_loop::{EventLoopClosed, EventLoopProxy};
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
 
 pub type EventProxy<'a> = &'a event::Event<'a, CustomEvent>;
 
 #[derive(Clone)]
 pub enum CustomEvent {
     WindowEvent(WindowEvent),
+    /// `Global` switched to another theme, so widgets should restyle themselves
+    ThemeChanged,
+    /// The config file was edited and should be reloaded
+    ConfigFileChanged,
+    /// `Global` took a new `Config`, so settings outside the theme should be re-read
+    ConfigChanged,
+    /// A popup menu opened through `Global::open_menu` closed
+    MenuClosed(MenuId),
+    /// Left or Right was pressed in the top level of an open menu, asking the menu bar that
+    /// opened it to move to the previous or next one
+    MenuStep {
+        id: MenuId,
+        forward: bool,
+    },
+    /// A dialog opened through `Global::open_dialog` closed, after reporting its result
+    DialogClosed(WindowId),
 }
 
 #[derive(Clone)]
@@ -14,3 +36,37 @@ pub enum WindowEvent {
     Show(WindowId),
     Hide(WindowId),
 }
+
+/// Destination of `CustomEvent`s sent by components
+pub trait EventSink {
+    fn send_event(&self, event: CustomEvent) -> Result<(), EventLoopClosed<CustomEvent>>;
+}
+
+impl EventSink for EventLoopProxy<CustomEvent> {
+    fn send_event(&self, event: CustomEvent) -> Result<(), EventLoopClosed<CustomEvent>> {
+        EventLoopProxy::send_event(self, event)
+    }
+}
+
+/// In-process event queue used in place of an event loop, e.g. when running headless
+#[derive(Clone, Default)]
+pub struct EventQueue {
+    events: Rc<RefCell<VecDeque<CustomEvent>>>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pop(&self) -> Option<CustomEvent> {
+        self.events.borrow_mut().pop_front()
+    }
+}
+
+impl EventSink for EventQueue {
+    fn send_event(&self, event: CustomEvent) -> Result<(), EventLoopClosed<CustomEvent>> {
+        self.events.borrow_mut().push_back(event);
+        Ok(())
+    }
+}
@@ -1,58 +1,106 @@
-use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::time::Instant;
 
-use crate::{custom_event::CustomEvent, RenderContext};
+use crate::{
+    component::desktop::application::Application,
+    component::menu::{Menu, MenuId, MenuOptions, MenuRequest},
+    component::tooltip::TooltipRequest,
+    component::Layout,
+    custom_event::{CustomEvent, EventSink},
+    render_context::Bitmap,
+    Config, RenderContext, Theme,
+};
 
 use glium::glutin::event_loop::EventLoopClosed;
 use glium::glutin::{
     dpi::{LogicalPosition, PhysicalPosition},
-    event::Event,
-    event_loop::EventLoopProxy,
+    event::{Event, ModifiersState},
 };
-use glium::Display;
+use nalgebra::Vector2;
+
+/// Where a cached bitmap was decoded from
+#[derive(PartialEq, Eq, Hash)]
+enum ImageKey {
+    Path(PathBuf),
+    /// Hash of the encoded bytes
+    Bytes(u64),
+}
 
 pub struct Global {
     pub font: Rc<rusttype::Font<'static>>,
-    pub render_context: RenderContext<'static>,
+    /// Font given to `new`, used whenever the config names none
+    default_font: Rc<rusttype::Font<'static>>,
+    pub render_context: RenderContext,
     cursor_position: PhysicalPosition<f64>,
     pub scale_factor: f64,
-    pub event_loop_proxy: EventLoopProxy<CustomEvent>,
+    event_sink: Box<dyn EventSink>,
+    redraw_requests: Cell<u64>,
+    images: RefCell<HashMap<ImageKey, Weak<Bitmap>>>,
+    theme: RefCell<Rc<Theme>>,
+    config: Rc<Config>,
+    modifiers: ModifiersState,
+    menu_requests: RefCell<Vec<MenuRequest>>,
+    menu_ids: Cell<u64>,
+    tooltip: RefCell<Option<TooltipRequest>>,
+    wake_up: Cell<Option<Instant>>,
+    dialog_requests: RefCell<Vec<Box<dyn Application>>>,
 }
 
 impl Global {
     pub fn new(
         font: rusttype::Font<'static>,
-        render_context: RenderContext<'static>,
-        event_loop_proxy: EventLoopProxy<CustomEvent>,
+        render_context: RenderContext,
+        event_sink: impl EventSink + 'static,
     ) -> Self {
         let font = Rc::new(font);
         let scale_factor = render_context.scale_factor();
         let cursor_position = PhysicalPosition::new(0.0, 0.0);
+        let event_sink = Box::new(event_sink);
 
         Global {
+            default_font: font.clone(),
             font,
             render_context,
             scale_factor,
             cursor_position,
-            event_loop_proxy,
+            event_sink,
+            redraw_requests: Cell::new(0),
+            images: RefCell::new(HashMap::new()),
+            theme: RefCell::new(Rc::new(Theme::default())),
+            config: Rc::new(Config::default()),
+            modifiers: ModifiersState::empty(),
+            menu_requests: RefCell::new(Vec::new()),
+            menu_ids: Cell::new(0),
+            tooltip: RefCell::new(None),
+            wake_up: Cell::new(None),
+            dialog_requests: RefCell::new(Vec::new()),
         }
     }
 
     pub fn send_event(&self, event: CustomEvent) -> Result<(), EventLoopClosed<CustomEvent>> {
-        self.event_loop_proxy.send_event(event)
+        self.event_sink.send_event(event)
     }
 
     pub fn handle_event(&mut self, event: &Event<'_, CustomEvent>) {
-        match event {
-            Event::WindowEvent { event, .. } => match event {
+        if let Event::WindowEvent { event, .. } = event {
+            match event {
                 glium::glutin::event::WindowEvent::CursorMoved { position, .. } => {
                     self.cursor_position = *position;
                 }
                 glium::glutin::event::WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                     self.scale_factor = *scale_factor;
                 }
+                glium::glutin::event::WindowEvent::ModifiersChanged(modifiers) => {
+                    self.modifiers = *modifiers;
+                }
                 _ => {}
-            },
-            _ => {}
+            }
         }
     }
 
@@ -60,11 +108,175 @@ impl Global {
         self.cursor_position.to_logical(self.scale_factor)
     }
 
+    /// Modifier keys currently held down
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
     pub fn request_redraw(&self) {
-        self.display().gl_window().window().request_redraw();
+        self.redraw_requests.set(self.redraw_requests.get() + 1);
+        self.render_context.request_redraw();
+    }
+
+    /// Requests a redraw limited to `layout`
+    pub fn damage(&self, layout: Layout) {
+        self.redraw_requests.set(self.redraw_requests.get() + 1);
+        self.render_context.damage(layout);
+    }
+
+    pub fn theme(&self) -> Rc<Theme> {
+        self.theme.borrow().clone()
+    }
+
+    /// Switches the theme and has every widget restyle itself
+    pub fn set_theme(&self, theme: Theme) {
+        *self.theme.borrow_mut() = Rc::new(theme);
+        let _ = self.send_event(CustomEvent::ThemeChanged);
+        self.request_redraw();
+    }
+
+    pub fn config(&self) -> Rc<Config> {
+        self.config.clone()
+    }
+
+    /// Applies `config`'s font and theme and lets components pick up the rest
+    pub fn set_config(&mut self, config: Config) {
+        self.font = config
+            .font
+            .face
+            .clone()
+            .unwrap_or_else(|| self.default_font.clone());
+        self.set_theme(config.theme());
+        self.config = Rc::new(config);
+        let _ = self.send_event(CustomEvent::ConfigChanged);
+    }
+
+    /// Decodes the image at `path`, sharing the bitmap with every other live user of the file
+    pub fn load_image(&self, path: impl AsRef<Path>) -> io::Result<Rc<Bitmap>> {
+        let path = path.as_ref();
+        let key = ImageKey::Path(path.canonicalize()?);
+        self.cached_image(key, || Bitmap::decode(&std::fs::read(path)?))
+    }
+
+    /// Decodes an encoded PNG or JPEG image, sharing the bitmap with identical images
+    pub fn decode_image(&self, bytes: &[u8]) -> io::Result<Rc<Bitmap>> {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        let key = ImageKey::Bytes(hasher.finish());
+        self.cached_image(key, || Bitmap::decode(bytes))
+    }
+
+    fn cached_image(
+        &self,
+        key: ImageKey,
+        decode: impl FnOnce() -> io::Result<Bitmap>,
+    ) -> io::Result<Rc<Bitmap>> {
+        let mut images = self.images.borrow_mut();
+        if let Some(bitmap) = images.get(&key).and_then(Weak::upgrade) {
+            return Ok(bitmap);
+        }
+        let bitmap = Rc::new(decode()?);
+        images.retain(|_, bitmap| bitmap.strong_count() > 0);
+        images.insert(key, Rc::downgrade(&bitmap));
+        Ok(bitmap)
+    }
+
+    /// Opens `menu` above every window with its top left corner at `position`, replacing any
+    /// menu already open
+    pub fn open_menu(&self, menu: Rc<RefCell<Menu>>, position: Vector2<f32>) -> MenuId {
+        self.open_menu_with(menu, position, MenuOptions::default())
+    }
+
+    /// Opens `menu` like `open_menu`, shown as `options` ask
+    pub fn open_menu_with(
+        &self,
+        menu: Rc<RefCell<Menu>>,
+        position: Vector2<f32>,
+        options: MenuOptions,
+    ) -> MenuId {
+        let id = MenuId(self.menu_ids.get());
+        self.menu_ids.set(id.0 + 1);
+        self.menu_requests.borrow_mut().push(MenuRequest::Open {
+            id,
+            menu,
+            position,
+            options,
+        });
+        id
+    }
+
+    /// Closes menu `id` if it is still open
+    pub fn close_menu(&self, id: MenuId) {
+        self.menu_requests.borrow_mut().push(MenuRequest::Close(id));
+    }
+
+    /// Highlights item `index` of menu `id` if it is still open, as if the pointer rested on it
+    pub fn highlight_menu_item(&self, id: MenuId, index: Option<usize>) {
+        self.menu_requests
+            .borrow_mut()
+            .push(MenuRequest::Highlight(id, index));
+    }
+
+    /// Shows the changes made to the items of menu `id` since it opened, if it is still open
+    pub fn refresh_menu(&self, id: MenuId) {
+        self.menu_requests
+            .borrow_mut()
+            .push(MenuRequest::Refresh(id));
+    }
+
+    /// Opens `menu` at the cursor, as done in response to a right click
+    pub fn open_context_menu(&self, menu: Menu) -> MenuId {
+        let cursor_position = self.cursor_position();
+        let position = Vector2::new(cursor_position.x as f32, cursor_position.y as f32);
+        self.open_menu(Rc::new(RefCell::new(menu)), position)
+    }
+
+    pub(crate) fn take_menu_requests(&self) -> Vec<MenuRequest> {
+        std::mem::take(&mut *self.menu_requests.borrow_mut())
+    }
+
+    /// Opens `dialog` in a window attached to the window of the app calling this, which takes
+    /// no input until the dialog closes. `CustomEvent::DialogClosed` follows its closing
+    pub fn open_dialog(&self, dialog: impl Application) {
+        self.dialog_requests.borrow_mut().push(Box::new(dialog));
+    }
+
+    pub(crate) fn take_dialog_requests(&self) -> Vec<Box<dyn Application>> {
+        std::mem::take(&mut *self.dialog_requests.borrow_mut())
+    }
+
+    /// Shows `text` in a bubble once the pointer rests over `layout`. Components call this
+    /// while handling `CursorMoved` with the cursor inside `layout`, and again on every move
+    pub fn set_tooltip(&self, layout: Layout, text: &str) {
+        *self.tooltip.borrow_mut() = Some(TooltipRequest {
+            layout,
+            text: text.to_owned(),
+        });
+    }
+
+    pub(crate) fn replace_tooltip(
+        &self,
+        tooltip: Option<TooltipRequest>,
+    ) -> Option<TooltipRequest> {
+        self.tooltip.replace(tooltip)
+    }
+
+    /// Has `update` run again by `instant` even if no event arrives, for timers
+    pub fn wake_up_at(&self, instant: Instant) {
+        let instant = self
+            .wake_up
+            .get()
+            .map_or(instant, |wake_up| wake_up.min(instant));
+        self.wake_up.set(Some(instant));
+    }
+
+    /// Earliest time asked for through `wake_up_at` since the last call
+    pub fn take_wake_up(&self) -> Option<Instant> {
+        self.wake_up.take()
     }
 
-    pub fn display(&self) -> &Display {
-        self.render_context.display()
+    /// Number of redraws requested so far, for telling whether a component asked for one
+    pub fn redraw_requests(&self) -> u64 {
+        self.redraw_requests.get()
     }
 }
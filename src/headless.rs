@@ -0,0 +1,292 @@
+use crate::custom_event::{CustomEvent, EventQueue};
+use crate::render_context::SoftwareRenderer;
+use crate::{Component, Desktop, Global, RenderContext};
+
+use glium::glutin::dpi::{LogicalPosition, PhysicalSize};
+use glium::glutin::event::{
+    DeviceId, ElementState, Event, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta,
+    TouchPhase, VirtualKeyCode, WindowEvent,
+};
+use glium::glutin::window::WindowId;
+
+use std::time::Instant;
+
+pub mod golden;
+
+/// Drives a component in-process without an event loop, a `Desktop` unless given another, with
+/// the software renderer unless given another
+pub struct Headless<C = Desktop> {
+    pub global: Global,
+    pub root: C,
+    events: EventQueue,
+}
+
+impl Headless {
+    pub fn new(font: rusttype::Font<'static>, size: PhysicalSize<u32>, scale_factor: f64) -> Self {
+        Self::with_root(font, size, scale_factor, Desktop::new)
+    }
+}
+
+impl<C: Component> Headless<C> {
+    /// Drives the component built by `root` instead of a desktop
+    pub fn with_root(
+        font: rusttype::Font<'static>,
+        size: PhysicalSize<u32>,
+        scale_factor: f64,
+        root: impl FnOnce(&Global) -> C,
+    ) -> Self {
+        let render_context =
+            RenderContext::with_renderer(SoftwareRenderer::new(size, scale_factor));
+        Self::with_render_context(font, render_context, root)
+    }
+
+    /// Draws with `render_context`, e.g. one on a hidden window to run the GL shaders
+    pub fn with_render_context(
+        font: rusttype::Font<'static>,
+        render_context: RenderContext,
+        root: impl FnOnce(&Global) -> C,
+    ) -> Self {
+        let events = EventQueue::new();
+        let global = Global::new(font, render_context, events.clone());
+        let root = root(&global);
+
+        Headless {
+            global,
+            root,
+            events,
+        }
+    }
+
+    pub fn send_window_event(&mut self, event: WindowEvent<'static>) {
+        let event = Event::WindowEvent {
+            // SAFETY: the id is only compared against, never used to reach a platform window
+            window_id: unsafe { WindowId::dummy() },
+            event,
+        };
+        self.dispatch(&event);
+    }
+
+    #[allow(deprecated)]
+    pub fn move_cursor(&mut self, position: LogicalPosition<f64>) {
+        self.send_window_event(WindowEvent::CursorMoved {
+            device_id: Self::device_id(),
+            position: position.to_physical(self.global.scale_factor),
+            modifiers: ModifiersState::empty(),
+        });
+    }
+
+    #[allow(deprecated)]
+    pub fn mouse_input(&mut self, state: ElementState, button: MouseButton) {
+        self.send_window_event(WindowEvent::MouseInput {
+            device_id: Self::device_id(),
+            state,
+            button,
+            modifiers: ModifiersState::empty(),
+        });
+    }
+
+    #[allow(deprecated)]
+    pub fn mouse_wheel(&mut self, delta: MouseScrollDelta, phase: TouchPhase) {
+        self.send_window_event(WindowEvent::MouseWheel {
+            device_id: Self::device_id(),
+            delta,
+            phase,
+            modifiers: ModifiersState::empty(),
+        });
+    }
+
+    pub fn click(&mut self, position: LogicalPosition<f64>) {
+        self.move_cursor(position);
+        self.mouse_input(ElementState::Pressed, MouseButton::Left);
+        self.mouse_input(ElementState::Released, MouseButton::Left);
+    }
+
+    /// Presses and releases `key`, with whatever modifiers were last reported
+    #[allow(deprecated)]
+    pub fn press_key(&mut self, key: VirtualKeyCode) {
+        for state in [ElementState::Pressed, ElementState::Released].iter() {
+            self.send_window_event(WindowEvent::KeyboardInput {
+                device_id: Self::device_id(),
+                input: KeyboardInput {
+                    scancode: 0,
+                    state: *state,
+                    virtual_keycode: Some(key),
+                    modifiers: self.global.modifiers(),
+                },
+                is_synthetic: false,
+            });
+        }
+    }
+
+    pub fn type_str(&mut self, s: &str) {
+        for c in s.chars() {
+            self.send_window_event(WindowEvent::ReceivedCharacter(c));
+        }
+    }
+
+    /// Runs the update pass, as the event loop does once it has cleared a batch of events, and
+    /// returns when a component asked to be woken up
+    pub fn update(&mut self) -> Option<Instant> {
+        self.dispatch(&Event::MainEventsCleared);
+        self.root.update(&self.global);
+        self.global.take_wake_up()
+    }
+
+    /// Runs the update pass and renders a frame if anything requested a redraw
+    pub fn render(&mut self) {
+        self.update();
+        let redraw = match self.global.render_context.renderer::<SoftwareRenderer>() {
+            Some(renderer) => renderer.take_redraw_request(),
+            // other renderers ask a window for the redraw, which nothing here waits on
+            None => true,
+        };
+        if redraw {
+            let mut proxy = self.global.render_context.create_proxy();
+            self.root.draw(&mut proxy);
+        }
+    }
+
+    /// Panics unless drawing with the software renderer
+    pub fn renderer(&self) -> &SoftwareRenderer {
+        self.global
+            .render_context
+            .renderer()
+            .expect("headless render context must use the software renderer")
+    }
+
+    fn dispatch(&mut self, event: &Event<'_, CustomEvent>) {
+        self.global.handle_event(event);
+        self.root.handle_event(event, &self.global);
+        while let Some(custom_event) = self.events.pop() {
+            let event = Event::UserEvent(custom_event);
+            self.global.handle_event(&event);
+            self.root.handle_event(&event, &self.global);
+        }
+    }
+
+    fn device_id() -> DeviceId {
+        // SAFETY: as with `WindowId::dummy`, the id is never used to reach a device
+        unsafe { DeviceId::dummy() }
+    }
+}
+
+/// Font the tests draw text with
+#[cfg(test)]
+pub(crate) fn test_font() -> rusttype::Font<'static> {
+    rusttype::Font::try_from_bytes(include_bytes!("../resource/GenRyuMinJP-Regular.ttf"))
+        .expect("failed to load font")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::Layout;
+    use crate::render_context::RoundedRect;
+
+    use nalgebra::{Vector2, Vector4};
+
+    const SIZE: PhysicalSize<u32> = PhysicalSize {
+        width: 640,
+        height: 420,
+    };
+
+    fn headless() -> Headless {
+        let mut headless = Headless::new(test_font(), SIZE, 1.0);
+        headless.render();
+        headless
+    }
+
+    /// Pixels within `left..right` and `top..bottom` differing between two frames
+    fn changed_pixels(
+        before: &[u8],
+        after: &SoftwareRenderer,
+        (left, top, right, bottom): (u32, u32, u32, u32),
+    ) -> usize {
+        let mut changed = 0;
+        for y in top..bottom {
+            for x in left..right {
+                let index = ((y * SIZE.width + x) * 4) as usize;
+                if before[index..index + 4] != after.pixel(x, y) {
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+
+    #[test]
+    fn rounded_rect_corners_are_anti_aliased() {
+        let mut headless = headless();
+        {
+            let mut proxy = headless.global.render_context.create_proxy();
+            proxy.clear(Vector4::new(1.0, 1.0, 1.0, 1.0));
+            proxy.draw_rounded_rect(&RoundedRect {
+                layout: Layout {
+                    position: Vector2::new(100.0, 100.0),
+                    size: Vector2::new(100.0, 100.0),
+                },
+                fill: Vector4::new(0.0, 0.0, 0.0, 1.0).into(),
+                round_radius: 20.0,
+                border_width: 0.0,
+                border_color: Vector4::zeros(),
+            });
+        }
+        let renderer = headless.renderer();
+
+        // the very corner lies outside the rounding, the center and straight edges inside it
+        assert_eq!(renderer.pixel(100, 100), [255, 255, 255, 255]);
+        assert_eq!(renderer.pixel(150, 150), [0, 0, 0, 255]);
+        assert_eq!(renderer.pixel(150, 100), [0, 0, 0, 255]);
+        assert_eq!(renderer.pixel(100, 150), [0, 0, 0, 255]);
+        assert_eq!(renderer.pixel(99, 150), [255, 255, 255, 255]);
+
+        // walking into the shape along the top row, the arc fades in instead of stepping
+        let reds: Vec<u8> = (100..125).map(|x| renderer.pixel(x, 101)[0]).collect();
+        assert!(reds.windows(2).all(|pair| pair[0] >= pair[1]));
+        let partial = reds.iter().filter(|&&red| red > 0 && red < 255).count();
+        assert!(partial >= 1, "corner arc is not anti-aliased: {:?}", reds);
+    }
+
+    #[test]
+    fn clicking_the_input_and_typing_shows_the_text() {
+        let mut headless = headless();
+        let window = (50, 50, 230, 290);
+        let before = headless.renderer().pixels().to_vec();
+
+        headless.type_str("hello");
+        headless.render();
+        assert_eq!(
+            changed_pixels(&before, headless.renderer(), window),
+            0,
+            "typing reached the input before it was focused"
+        );
+
+        headless.click(LogicalPosition::new(140.0, 180.0));
+        headless.type_str("hello");
+        headless.render();
+        assert!(changed_pixels(&before, headless.renderer(), window) > 50);
+    }
+
+    #[test]
+    fn dragging_the_title_moves_the_window() {
+        let mut headless = headless();
+        let background = headless.renderer().pixel(630, 410);
+        // inside the window only before the drag, and only after it
+        let (old, new) = ((70, 200), (400, 300));
+        assert_ne!(headless.renderer().pixel(old.0, old.1), background);
+        assert_eq!(headless.renderer().pixel(new.0, new.1), background);
+
+        headless.move_cursor(LogicalPosition::new(70.0, 62.0));
+        headless.mouse_input(ElementState::Pressed, MouseButton::Left);
+        // the window follows the cursor in the update pass, once per batch of events
+        headless.move_cursor(LogicalPosition::new(170.0, 112.0));
+        headless.render();
+        headless.move_cursor(LogicalPosition::new(270.0, 162.0));
+        headless.render();
+        headless.mouse_input(ElementState::Released, MouseButton::Left);
+        headless.render();
+
+        assert_eq!(headless.renderer().pixel(old.0, old.1), background);
+        assert_ne!(headless.renderer().pixel(new.0, new.1), background);
+    }
+}
@@ -0,0 +1,236 @@
+use super::Headless;
+use crate::render_context::{Screenshot, SoftwareRenderer};
+use crate::{Desktop, RenderContext};
+
+use glium::glutin::dpi::PhysicalSize;
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Setting this variable makes missing or mismatching references get (re)written
+pub const UPDATE_ENV: &str = "WM_UPDATE_GOLDEN";
+
+/// Difference allowed between a rendered frame and its reference
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    /// Largest per-channel difference for which a pixel still matches
+    pub channel: u8,
+    /// Number of pixels allowed to differ by more than `channel`
+    pub pixels: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            channel: 2,
+            pixels: 0,
+        }
+    }
+}
+
+pub struct Comparison {
+    pub mismatched_pixels: usize,
+    pub max_difference: u8,
+    /// Mismatching pixels in red over a faded copy of the reference
+    pub diff: Screenshot,
+}
+
+impl Comparison {
+    pub fn is_within(&self, tolerance: Tolerance) -> bool {
+        self.mismatched_pixels <= tolerance.pixels
+    }
+}
+
+#[derive(Debug)]
+pub enum GoldenError {
+    Io(io::Error),
+    MissingReference(PathBuf),
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    Mismatch {
+        mismatched_pixels: usize,
+        max_difference: u8,
+        diff_path: PathBuf,
+    },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Io(err) => write!(f, "{}", err),
+            GoldenError::MissingReference(path) => write!(
+                f,
+                "reference image {} does not exist, rerun with {}=1 to create it",
+                path.display(),
+                UPDATE_ENV
+            ),
+            GoldenError::SizeMismatch { expected, actual } => write!(
+                f,
+                "expected a {}x{} frame, rendered {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+            GoldenError::Mismatch {
+                mismatched_pixels,
+                max_difference,
+                diff_path,
+            } => write!(
+                f,
+                "{} pixels differ from the reference (max channel difference {}), see {}",
+                mismatched_pixels,
+                max_difference,
+                diff_path.display()
+            ),
+        }
+    }
+}
+
+impl Error for GoldenError {}
+
+impl From<io::Error> for GoldenError {
+    fn from(err: io::Error) -> Self {
+        GoldenError::Io(err)
+    }
+}
+
+pub fn compare(actual: &Screenshot, expected: &Screenshot, tolerance: Tolerance) -> Comparison {
+    let mut mismatched_pixels = 0;
+    let mut max_difference = 0;
+    let mut diff = Vec::with_capacity(expected.pixels.len());
+
+    for (a, e) in actual
+        .pixels
+        .chunks_exact(4)
+        .zip(expected.pixels.chunks_exact(4))
+    {
+        let difference = a
+            .iter()
+            .zip(e)
+            .map(|(&a, &e)| (a as i16 - e as i16).unsigned_abs() as u8)
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+        if difference > tolerance.channel {
+            mismatched_pixels += 1;
+            diff.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let luma = (e[0] as u32 * 3 + e[1] as u32 * 6 + e[2] as u32) / 10;
+            let faded = (128 + luma / 2) as u8;
+            diff.extend_from_slice(&[faded, faded, faded, 255]);
+        }
+    }
+
+    Comparison {
+        mismatched_pixels,
+        max_difference,
+        diff: Screenshot::new(expected.width, expected.height, diff),
+    }
+}
+
+/// Renders scripted scenes headlessly and compares them against stored reference images.
+/// `check` draws with the software renderer, which only mirrors the shaders; `check_with` a
+/// render context on a GL window runs the scenes through the shaders themselves
+pub struct GoldenHarness {
+    font: rusttype::Font<'static>,
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+    reference_dir: PathBuf,
+    output_dir: PathBuf,
+    tolerance: Tolerance,
+}
+
+impl GoldenHarness {
+    pub fn new(
+        font: rusttype::Font<'static>,
+        size: PhysicalSize<u32>,
+        scale_factor: f64,
+        reference_dir: impl AsRef<Path>,
+    ) -> Self {
+        let reference_dir = reference_dir.as_ref().to_path_buf();
+        let output_dir = reference_dir.join("failures");
+
+        GoldenHarness {
+            font,
+            size,
+            scale_factor,
+            reference_dir,
+            output_dir,
+            tolerance: Tolerance::default(),
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets where the rendered frame and diff image are written on failure
+    pub fn with_output_dir(mut self, output_dir: impl AsRef<Path>) -> Self {
+        self.output_dir = output_dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Renders a fresh desktop after running `script` on it and compares the frame with `<name>.png`
+    pub fn check(&self, name: &str, script: impl FnOnce(&mut Headless)) -> Result<(), GoldenError> {
+        let renderer = SoftwareRenderer::new(self.size, self.scale_factor);
+        self.check_with(name, RenderContext::with_renderer(renderer), script)
+    }
+
+    /// Like `check`, drawing with `render_context` instead of the software renderer. Its frame
+    /// buffer size and scale factor should match the harness's for the references to apply
+    pub fn check_with(
+        &self,
+        name: &str,
+        render_context: RenderContext,
+        script: impl FnOnce(&mut Headless),
+    ) -> Result<(), GoldenError> {
+        let mut headless =
+            Headless::with_render_context(self.font.clone(), render_context, Desktop::new);
+        headless.render();
+        script(&mut headless);
+        headless.render();
+        let actual = headless.global.render_context.read_frame();
+
+        let reference_path = self.reference_dir.join(format!("{}.png", name));
+        let update = env::var_os(UPDATE_ENV).is_some();
+        if !reference_path.exists() {
+            if update {
+                fs::create_dir_all(&self.reference_dir)?;
+                actual.save_png(&reference_path)?;
+                return Ok(());
+            }
+            return Err(GoldenError::MissingReference(reference_path));
+        }
+
+        let expected = Screenshot::load_png(&reference_path)?;
+        if (expected.width, expected.height) != (actual.width, actual.height) {
+            return Err(GoldenError::SizeMismatch {
+                expected: (expected.width, expected.height),
+                actual: (actual.width, actual.height),
+            });
+        }
+        let comparison = compare(&actual, &expected, self.tolerance);
+        if comparison.is_within(self.tolerance) {
+            return Ok(());
+        }
+        if update {
+            actual.save_png(&reference_path)?;
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.output_dir)?;
+        let diff_path = self.output_dir.join(format!("{}.diff.png", name));
+        actual.save_png(self.output_dir.join(format!("{}.actual.png", name)))?;
+        comparison.diff.save_png(&diff_path)?;
+        Err(GoldenError::Mismatch {
+            mismatched_pixels: comparison.mismatched_pixels,
+            max_difference: comparison.max_difference,
+            diff_path,
+        })
+    }
+}
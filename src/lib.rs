@@ -1,9 +1,14 @@
 pub mod component;
+pub mod config;
 pub mod custom_event;
 mod global;
-mod render_context;
+pub mod headless;
+pub mod render_context;
+pub mod theme;
 
 pub use component::desktop::Desktop;
 pub use component::Component;
+pub use config::Config;
 pub use global::Global;
 pub use render_context::{RenderContext, RenderContextProxy};
+pub use theme::Theme;
@@ -7,8 +7,10 @@ use glutin::event_loop::ControlFlow;
 
 use rusttype::Font;
 
+use wm::config;
 use wm::custom_event::{self, CustomEvent};
 use wm::Component;
+use wm::Config;
 use wm::Desktop;
 use wm::Global;
 use wm::RenderContext;
@@ -28,10 +30,26 @@ fn main() {
         .expect("failed to load font");
     let mut global = Global::new(font, render_context, event_loop_proxy);
 
+    let config_path = Config::path();
+    if let Some(path) = &config_path {
+        if path.exists() {
+            match Config::load(path) {
+                Ok(config) => global.set_config(config),
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+        config::watch(path.clone(), event_loop.create_proxy());
+    }
+
     let mut desktop = Desktop::new(&global);
+    let mut wake_up = None;
 
     event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Wait;
+        // sleep until the next event, or until a component's timer is due
+        *control_flow = match wake_up {
+            Some(instant) => ControlFlow::WaitUntil(instant),
+            None => ControlFlow::Wait,
+        };
 
         global.handle_event(&event);
         desktop.handle_event(&event, &global);
@@ -49,8 +67,24 @@ fn main() {
                 custom_event::WindowEvent::Show(window_id),
             )) => {}
 
+            // keep the last good config when the edited one fails to load, and go back to the
+            // defaults once it is removed
+            Event::UserEvent(CustomEvent::ConfigFileChanged) => {
+                if let Some(path) = &config_path {
+                    if !path.exists() {
+                        global.set_config(Config::default());
+                    } else {
+                        match Config::load(path) {
+                            Ok(config) => global.set_config(config),
+                            Err(err) => eprintln!("{}", err),
+                        }
+                    }
+                }
+            }
+
             Event::MainEventsCleared => {
                 desktop.update(&global);
+                wake_up = global.take_wake_up();
             }
 
             // rendering
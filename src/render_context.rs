@@ -1,137 +1,453 @@
+use crate::component::Layout;
+
+use glium::glutin::dpi::PhysicalSize;
 use glium::Display;
-use glium::Frame;
-use glium::{
-    framebuffer::{DefaultFramebuffer, SimpleFrameBuffer, ToColorAttachment, ValidationError},
-    glutin::dpi::PhysicalSize,
-};
 
-pub struct RenderContext<'a> {
-    rctx: RawRenderContext<'a>,
-}
+use nalgebra::{Vector2, Vector4};
+use rusttype::PositionedGlyph;
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+mod bitmap;
+mod glium_renderer;
+mod screenshot;
+mod software;
 
-pub struct RenderContextProxy<'a, 'b> {
-    rctx: &'b mut RawRenderContext<'a>,
+pub use bitmap::{Bitmap, BitmapId};
+pub use glium_renderer::GliumRenderer;
+pub use screenshot::Screenshot;
+pub use software::SoftwareRenderer;
+
+/// How the inside of a shape is painted, with points relative to the top-left corner of its layout
+#[derive(Clone, Copy, Debug)]
+pub enum Fill {
+    Solid(Vector4<f32>),
+    /// Blends from `from_color` at `from` to `to_color` at `to`
+    LinearGradient {
+        from: Vector2<f32>,
+        to: Vector2<f32>,
+        from_color: Vector4<f32>,
+        to_color: Vector4<f32>,
+    },
+    /// Blends from `inner_color` at `center` to `outer_color` at `radius` away from it
+    RadialGradient {
+        center: Vector2<f32>,
+        radius: f32,
+        inner_color: Vector4<f32>,
+        outer_color: Vector4<f32>,
+    },
 }
 
-impl<'a> RenderContext<'a> {
-    pub fn new(display: Display) -> Self {
-        let rctx = RawRenderContext::new(display);
-        RenderContext { rctx }
+impl Fill {
+    /// Color at `point`, relative to the top-left corner of the layout
+    pub fn color_at(&self, point: Vector2<f32>) -> Vector4<f32> {
+        let (t, from_color, to_color) = match *self {
+            Fill::Solid(color) => return color,
+            Fill::LinearGradient {
+                from,
+                to,
+                from_color,
+                to_color,
+            } => {
+                let direction = to - from;
+                let t = (point - from).dot(&direction) / direction.norm_squared().max(1e-6);
+                (t, from_color, to_color)
+            }
+            Fill::RadialGradient {
+                center,
+                radius,
+                inner_color,
+                outer_color,
+            } => (
+                (point - center).norm() / radius.max(1e-6),
+                inner_color,
+                outer_color,
+            ),
+        };
+        // interpolate premultiplied, so transparent stops do not darken the gradient
+        let premultiply = |c: Vector4<f32>| Vector4::new(c.x * c.w, c.y * c.w, c.z * c.w, c.w);
+        let color = premultiply(from_color).lerp(&premultiply(to_color), t.clamp(0.0, 1.0));
+        if color.w > 0.0 {
+            Vector4::new(
+                color.x / color.w,
+                color.y / color.w,
+                color.z / color.w,
+                color.w,
+            )
+        } else {
+            Vector4::zeros()
+        }
     }
+}
 
-    pub fn create_proxy<'b>(&'b mut self) -> RenderContextProxy<'a, 'b> {
-        RenderContextProxy::new(&mut self.rctx)
+impl From<Vector4<f32>> for Fill {
+    fn from(color: Vector4<f32>) -> Self {
+        Fill::Solid(color)
     }
+}
 
-    pub fn display(&self) -> &Display {
-        self.rctx.display()
+/// Anti-aliased rounded rectangle in logical coordinates
+#[derive(Clone, Copy, Debug)]
+pub struct RoundedRect {
+    pub layout: Layout,
+    pub fill: Fill,
+    pub round_radius: f32,
+    /// Width of the border drawn inside the edge, none if zero
+    pub border_width: f32,
+    pub border_color: Vector4<f32>,
+}
+
+/// Soft shadow cast by a rounded rectangle
+#[derive(Clone, Copy, Debug)]
+pub struct Shadow {
+    pub layout: Layout,
+    pub round_radius: f32,
+    pub offset: Vector2<f32>,
+    pub blur_radius: f32,
+    /// Distance the shadow grows beyond the rectangle, or shrinks into it when inset
+    pub spread: f32,
+    pub color: Vector4<f32>,
+    /// Cast inside the rectangle instead of around it
+    pub inset: bool,
+}
+
+impl Shadow {
+    /// Area the shadow can reach
+    pub fn bounds(&self) -> Layout {
+        if self.inset {
+            return self.layout;
+        }
+        let extent = self.spread.max(0.0) + self.blur_radius;
+        let extent = Vector2::new(extent, extent);
+        Layout {
+            position: self.layout.position + self.offset - extent,
+            size: self.layout.size + 2.0 * extent,
+        }
     }
+}
 
-    pub fn scale_factor(&self) -> f64 {
-        self.rctx.scale_factor()
+/// Bitmap mapped onto `image_layout` and shown within the rounded rectangle `layout`
+#[derive(Clone, Copy)]
+pub struct ImageRect<'a> {
+    pub layout: Layout,
+    pub round_radius: f32,
+    pub bitmap: &'a Rc<Bitmap>,
+    pub image_layout: Layout,
+    /// Repeats the bitmap beyond `image_layout` instead of leaving it empty
+    pub tile: bool,
+    /// Multiplied with the bitmap colors
+    pub tint: Vector4<f32>,
+}
+
+/// Blurs what has already been drawn beneath a rounded rectangle
+#[derive(Clone, Copy, Debug)]
+pub struct BackdropBlur {
+    pub layout: Layout,
+    pub round_radius: f32,
+    pub blur_radius: f32,
+}
+
+/// Area of the frame that has to be redrawn
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Damage {
+    None,
+    /// Union of the damaged rectangles
+    Partial(Layout),
+    Full,
+}
+
+impl Damage {
+    pub fn add(&mut self, layout: Layout) {
+        *self = match *self {
+            Damage::None => Damage::Partial(layout),
+            Damage::Partial(damaged) => Damage::Partial(damaged.union(&layout)),
+            Damage::Full => Damage::Full,
+        };
     }
 }
 
-impl<'a, 'b> RenderContextProxy<'a, 'b> {
-    pub fn new(rctx: &'b mut RawRenderContext<'a>) -> Self {
-        RenderContextProxy { rctx }
+pub type LayerId = usize;
+
+/// How an offscreen layer is drawn onto the frame
+#[derive(Clone, Copy, Debug)]
+pub struct Composite {
+    pub layout: Layout,
+    pub opacity: f32,
+    /// Fraction by which the layer is darkened
+    pub dim: f32,
+}
+
+/// Rectangle in physical pixels, measured from the top-left corner of the frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl PixelRect {
+    /// Smallest pixel rectangle covering `layout`
+    pub fn from_layout(layout: &Layout, scale_factor: f64) -> Self {
+        let scale_factor = scale_factor as f32;
+        let min = layout.position * scale_factor;
+        let max = (layout.position + layout.size) * scale_factor;
+        PixelRect {
+            left: min.x.floor() as i32,
+            top: min.y.floor() as i32,
+            right: max.x.ceil() as i32,
+            bottom: max.y.ceil() as i32,
+        }
     }
 
-    pub fn frame_buffer_size(&self) -> PhysicalSize<u32> {
-        self.rctx.frame_buffer_size()
+    pub fn width(&self) -> i32 {
+        (self.right - self.left).max(0)
     }
 
-    pub fn frame(&mut self) -> &mut Frame {
-        self.rctx.frame()
+    pub fn height(&self) -> i32 {
+        (self.bottom - self.top).max(0)
     }
 
-    pub fn simple_framebuffer(
-        &mut self,
-        t: impl ToColorAttachment<'a>,
-    ) -> Result<&mut SimpleFrameBuffer<'a>, ValidationError> {
-        self.rctx.simple_framebuffer(t)
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
     }
 
-    pub fn default_framebuffer(&mut self) -> &mut DefaultFramebuffer {
-        self.rctx.default_framebuffer()
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.left <= x && x < self.right && self.top <= y && y < self.bottom
     }
 
-    pub fn scale_factor(&self) -> f64 {
-        self.rctx.scale_factor()
+    pub fn intersection(&self, other: &PixelRect) -> PixelRect {
+        PixelRect {
+            left: self.left.max(other.left),
+            top: self.top.max(other.top),
+            right: self.right.min(other.right),
+            bottom: self.bottom.min(other.bottom),
+        }
     }
 }
 
-impl Drop for RenderContextProxy<'_, '_> {
-    fn drop(&mut self) {
-        self.rctx.finalize();
-    }
+/// Glyphs laid out in physical pixels, relative to `position` given in logical coordinates
+pub struct GlyphRun<'a> {
+    pub position: Vector2<f32>,
+    /// Font the glyphs were laid out with
+    pub font: &'a Rc<rusttype::Font<'static>>,
+    pub glyphs: &'a [PositionedGlyph<'static>],
+    pub color: Vector4<f32>,
+}
+
+/// Backend that rasterizes the primitives components are drawn with
+pub trait Renderer: Any {
+    fn frame_buffer_size(&self) -> PhysicalSize<u32>;
+    fn scale_factor(&self) -> f64;
+    fn request_redraw(&self);
+
+    /// Restricts subsequent draws to `clip`, or lifts the restriction with `None`
+    fn set_clip(&mut self, clip: Option<Layout>);
+
+    fn clear(&mut self, color: Vector4<f32>);
+    fn draw_rounded_rect(&mut self, rect: &RoundedRect);
+    fn draw_glyphs(&mut self, run: &GlyphRun);
+    fn draw_shadow(&mut self, shadow: &Shadow);
+    fn draw_backdrop_blur(&mut self, blur: &BackdropBlur);
+    /// Renderers may keep the bitmap uploaded until its last `Rc` is dropped
+    fn draw_image(&mut self, image: &ImageRect);
+
+    /// Whether layer `id` holds contents rendered at the size of `layout`
+    fn has_layer(&self, id: LayerId, layout: &Layout) -> bool;
+    /// Redirects drawing into layer `id`, cleared and sized to cover `layout`
+    fn begin_layer(&mut self, id: LayerId, layout: &Layout);
+    fn end_layer(&mut self);
+    /// Layers that are not composited during a frame are released when it finishes
+    fn composite_layer(&mut self, id: LayerId, composite: &Composite);
+
+    /// Presents everything drawn since the last call
+    fn finish(&mut self);
+
+    /// Reads back the last finished frame
+    fn read_frame(&self) -> Screenshot;
+}
+
+pub struct RenderContext {
+    renderer: Box<dyn Renderer>,
+    damage: RefCell<Damage>,
+    last_damage: Damage,
+    /// Frame buffer size and scale factor the last frame was drawn with
+    last_frame: Option<(PhysicalSize<u32>, f64)>,
 }
 
-pub struct RawRenderContext<'a> {
-    display: Display,
-    frame: Option<Frame>,
-    default_framebuffer: Option<DefaultFramebuffer>,
-    simple_framebuffer: Option<SimpleFrameBuffer<'a>>,
+pub struct RenderContextProxy<'a> {
+    renderer: &'a mut dyn Renderer,
+    clip_stack: Vec<Layout>,
+    layer_stack: Vec<Vec<Layout>>,
 }
 
-impl<'a> RawRenderContext<'a> {
+impl RenderContext {
     pub fn new(display: Display) -> Self {
-        RawRenderContext {
-            display,
-            frame: None,
-            default_framebuffer: None,
-            simple_framebuffer: None,
+        Self::with_renderer(GliumRenderer::new(display))
+    }
+
+    pub fn with_renderer(renderer: impl Renderer) -> Self {
+        let renderer = Box::new(renderer);
+        RenderContext {
+            renderer,
+            damage: RefCell::new(Damage::None),
+            last_damage: Damage::None,
+            last_frame: None,
         }
     }
 
+    /// Starts a frame that only redraws the damage accumulated since the previous one
+    pub fn create_proxy(&mut self) -> RenderContextProxy<'_> {
+        let frame = (self.frame_buffer_size(), self.scale_factor());
+        let resized = self.last_frame.replace(frame) != Some(frame);
+        let damage = match self.damage.replace(Damage::None) {
+            _ if resized => Damage::Full,
+            // nothing asked for this frame, so the window system did
+            Damage::None => Damage::Full,
+            damage => damage,
+        };
+        self.last_damage = damage;
+
+        let mut proxy = RenderContextProxy::new(self.renderer.as_mut());
+        if let Damage::Partial(layout) = damage {
+            proxy.push_clip(layout);
+        }
+        proxy
+    }
+
+    /// Marks `layout` as changed and schedules a redraw
+    pub fn damage(&self, layout: Layout) {
+        self.damage.borrow_mut().add(layout);
+        self.renderer.request_redraw();
+    }
+
+    /// Damage the last frame was drawn with
+    pub fn last_damage(&self) -> Damage {
+        self.last_damage
+    }
+
+    pub fn renderer<R: Renderer>(&self) -> Option<&R> {
+        let renderer: &dyn Any = self.renderer.as_ref();
+        renderer.downcast_ref()
+    }
+
+    pub fn renderer_mut<R: Renderer>(&mut self) -> Option<&mut R> {
+        let renderer: &mut dyn Any = self.renderer.as_mut();
+        renderer.downcast_mut()
+    }
+
     pub fn frame_buffer_size(&self) -> PhysicalSize<u32> {
-        self.display().gl_window().window().inner_size()
+        self.renderer.frame_buffer_size()
     }
 
-    pub fn frame(&mut self) -> &mut Frame {
-        if let Some(ref mut frame) = self.frame {
-            frame
-        } else {
-            let frame = self.display.draw();
-            self.frame = Some(frame);
-            self.frame.as_mut().unwrap()
-        }
+    pub fn scale_factor(&self) -> f64 {
+        self.renderer.scale_factor()
     }
 
-    pub fn default_framebuffer(&mut self) -> &mut DefaultFramebuffer {
-        if let Some(ref mut default_framebuffer) = self.default_framebuffer {
-            default_framebuffer
-        } else {
-            let default_framebuffer = DefaultFramebuffer::back_left(&self.display);
-            self.default_framebuffer = Some(default_framebuffer);
-            self.default_framebuffer.as_mut().unwrap()
-        }
+    /// Schedules a redraw of the whole frame
+    pub fn request_redraw(&self) {
+        *self.damage.borrow_mut() = Damage::Full;
+        self.renderer.request_redraw();
     }
 
-    pub fn simple_framebuffer(
-        &mut self,
-        t: impl ToColorAttachment<'a>,
-    ) -> Result<&mut SimpleFrameBuffer<'a>, ValidationError> {
-        if let Some(ref mut simple_framebuffer) = self.simple_framebuffer {
-            Ok(simple_framebuffer)
-        } else {
-            let simple_framebuffer = SimpleFrameBuffer::new(&self.display, t)?;
-            self.simple_framebuffer = Some(simple_framebuffer);
-            Ok(self.simple_framebuffer.as_mut().unwrap())
+    pub fn read_frame(&self) -> Screenshot {
+        self.renderer.read_frame()
+    }
+}
+
+impl<'a> RenderContextProxy<'a> {
+    pub fn new(renderer: &'a mut dyn Renderer) -> Self {
+        RenderContextProxy {
+            renderer,
+            clip_stack: Vec::new(),
+            layer_stack: Vec::new(),
         }
     }
 
-    pub fn display(&self) -> &Display {
-        &self.display
+    pub fn frame_buffer_size(&self) -> PhysicalSize<u32> {
+        self.renderer.frame_buffer_size()
     }
 
     pub fn scale_factor(&self) -> f64 {
-        self.display.gl_window().window().scale_factor()
+        self.renderer.scale_factor()
     }
 
-    fn finalize(&mut self) {
-        if let Some(frame) = self.frame.take() {
-            frame.finish().expect("failed to finish frame");
-        }
+    /// Clips subsequent draws to `layout`, intersected with the current clip
+    pub fn push_clip(&mut self, layout: Layout) {
+        let clip = match self.clip_stack.last() {
+            Some(current) => current.intersection(&layout),
+            None => layout,
+        };
+        self.clip_stack.push(clip);
+        self.renderer.set_clip(Some(clip));
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.clip_stack.pop();
+        self.renderer.set_clip(self.clip_stack.last().copied());
+    }
+
+    pub fn clip(&self) -> Option<Layout> {
+        self.clip_stack.last().copied()
+    }
+
+    pub fn with_clip(&mut self, layout: Layout, f: impl FnOnce(&mut Self)) {
+        self.push_clip(layout);
+        f(self);
+        self.pop_clip();
+    }
+
+    pub fn clear(&mut self, color: Vector4<f32>) {
+        self.renderer.clear(color);
+    }
+
+    pub fn draw_rounded_rect(&mut self, rect: &RoundedRect) {
+        self.renderer.draw_rounded_rect(rect);
+    }
+
+    pub fn draw_glyphs(&mut self, run: &GlyphRun) {
+        self.renderer.draw_glyphs(run);
+    }
+
+    pub fn draw_shadow(&mut self, shadow: &Shadow) {
+        self.renderer.draw_shadow(shadow);
+    }
+
+    pub fn draw_image(&mut self, image: &ImageRect) {
+        self.renderer.draw_image(image);
+    }
+
+    /// Samples up to `blur_radius` beyond the clip, so outside a layer a partial redraw can
+    /// blur pixels left over from the previous frame
+    pub fn draw_backdrop_blur(&mut self, blur: &BackdropBlur) {
+        self.renderer.draw_backdrop_blur(blur);
+    }
+
+    pub fn has_layer(&self, id: LayerId, layout: &Layout) -> bool {
+        self.renderer.has_layer(id, layout)
+    }
+
+    /// Draws into layer `id` instead of the frame, starting from an empty clip stack
+    pub fn with_layer(&mut self, id: LayerId, layout: Layout, f: impl FnOnce(&mut Self)) {
+        self.layer_stack.push(std::mem::take(&mut self.clip_stack));
+        self.renderer.set_clip(None);
+        self.renderer.begin_layer(id, &layout);
+        f(self);
+        self.renderer.end_layer();
+        self.clip_stack = self.layer_stack.pop().unwrap_or_default();
+        self.renderer.set_clip(self.clip());
+    }
+
+    pub fn composite_layer(&mut self, id: LayerId, composite: &Composite) {
+        self.renderer.composite_layer(id, composite);
+    }
+}
+
+impl Drop for RenderContextProxy<'_> {
+    fn drop(&mut self) {
+        self.renderer.set_clip(None);
+        self.renderer.finish();
     }
 }
@@ -0,0 +1,64 @@
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub type BitmapId = u64;
+
+fn next_id() -> BitmapId {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Decoded image kept as premultiplied RGBA8, uploaded by renderers the first time it is drawn
+pub struct Bitmap {
+    id: BitmapId,
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Wraps straight-alpha RGBA8 pixels, row-major from the top-left corner
+    pub fn new(width: u32, height: u32, mut pixels: Vec<u8>) -> Self {
+        for pixel in pixels.chunks_exact_mut(4) {
+            let alpha = pixel[3] as u32;
+            for c in &mut pixel[..3] {
+                *c = ((*c as u32 * alpha + 127) / 255) as u8;
+            }
+        }
+        Self::from_premultiplied(width, height, pixels)
+    }
+
+    /// Wraps RGBA8 pixels whose colors are already multiplied by their alpha
+    pub fn from_premultiplied(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width as usize * height as usize * 4,
+            "pixel buffer does not match the image size"
+        );
+        Bitmap {
+            id: next_id(),
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Decodes a PNG or JPEG image
+    pub fn decode(bytes: &[u8]) -> io::Result<Self> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?
+            .to_rgba8();
+        let (width, height) = image.dimensions();
+        Ok(Self::new(width, height, image.into_raw()))
+    }
+
+    /// Identifies the bitmap for as long as it is alive
+    pub fn id(&self) -> BitmapId {
+        self.id
+    }
+
+    /// Premultiplied RGBA8 pixels, row-major from the top-left corner
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+}
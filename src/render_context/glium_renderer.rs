@@ -0,0 +1,840 @@
+use super::{
+    BackdropBlur, Bitmap, BitmapId, Composite, Fill, GlyphRun, ImageRect, LayerId, PixelRect,
+    Renderer, RoundedRect, Screenshot, Shadow,
+};
+use crate::component::Layout;
+
+use glium::draw_parameters::{BlendingFunction, LinearBlendingFactor};
+use glium::framebuffer::SimpleFrameBuffer;
+use glium::glutin::dpi::PhysicalSize;
+use glium::texture::{ClientFormat, MipmapsOption, RawImage2d};
+use glium::texture::{SrgbFormat, SrgbTexture2d, Texture2d};
+use glium::uniforms::{MagnifySamplerFilter, SamplerWrapFunction};
+use glium::{
+    implement_vertex, index::NoIndices, index::PrimitiveType, uniform, Blend, BlitTarget, Display,
+    DrawParameters, Program, Rect, Surface, VertexBuffer,
+};
+
+use nalgebra::{Vector2, Vector4};
+use rusttype::gpu_cache::Cache;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+#[derive(Clone, Copy)]
+struct Vertex {
+    a_position: [f32; 2],
+}
+implement_vertex!(Vertex, a_position);
+
+#[derive(Clone, Copy, Debug)]
+struct FontRenderInfo {
+    a_uv: [f32; 2],
+    a_position: [i32; 2],
+}
+implement_vertex!(FontRenderInfo, a_uv, a_position);
+
+/// Offscreen render target whose top-left corner sits at `origin` in logical coordinates
+struct Layer {
+    texture: Texture2d,
+    origin: Vector2<f32>,
+    used: bool,
+}
+
+/// Uniform values the rounded rect shader paints a fill with
+struct FillUniforms {
+    kind: i32,
+    from: [f32; 2],
+    to: [f32; 2],
+    from_color: [f32; 4],
+    to_color: [f32; 4],
+}
+
+impl From<&Fill> for FillUniforms {
+    fn from(fill: &Fill) -> Self {
+        match *fill {
+            Fill::Solid(color) => FillUniforms {
+                kind: 0,
+                from: [0.0, 0.0],
+                to: [0.0, 0.0],
+                from_color: color.into(),
+                to_color: color.into(),
+            },
+            Fill::LinearGradient {
+                from,
+                to,
+                from_color,
+                to_color,
+            } => FillUniforms {
+                kind: 1,
+                from: from.into(),
+                to: to.into(),
+                from_color: from_color.into(),
+                to_color: to_color.into(),
+            },
+            Fill::RadialGradient {
+                center,
+                radius,
+                inner_color,
+                outer_color,
+            } => FillUniforms {
+                kind: 2,
+                from: center.into(),
+                to: [radius, 0.0],
+                from_color: inner_color.into(),
+                to_color: outer_color.into(),
+            },
+        }
+    }
+}
+
+/// Draws `$draw` with `$surface` bound to the current render target
+macro_rules! with_target {
+    ($self:ident, $surface:ident => $draw:expr) => {
+        match $self.targets.last() {
+            Some(id) => {
+                let layer = $self.layers.get(id).expect("layer not begun");
+                let $surface = &mut SimpleFrameBuffer::new(&$self.display, &layer.texture)
+                    .expect("failed to create framebuffer");
+                $draw
+            }
+            None => {
+                $self.begin_frame();
+                let canvas = $self.canvas.as_ref().expect("canvas not created");
+                let $surface = &mut SimpleFrameBuffer::new(&$self.display, canvas)
+                    .expect("failed to create framebuffer");
+                $draw
+            }
+        }
+    };
+}
+
+/// Renders into the default framebuffer of a glium `Display`
+pub struct GliumRenderer {
+    display: Display,
+    /// Frame contents kept across frames, so damaged areas can be redrawn alone
+    canvas: Option<Texture2d>,
+    canvas_drawn: bool,
+    clip: Option<Layout>,
+
+    layers: HashMap<LayerId, Layer>,
+    targets: Vec<LayerId>,
+    /// Uploaded bitmaps, released once the bitmap itself is dropped
+    textures: HashMap<BitmapId, (Weak<Bitmap>, Texture2d)>,
+    /// Fonts seen so far, indexed by their glyph cache id; the weak references keep addresses
+    /// from being reused by later fonts
+    fonts: Vec<Weak<rusttype::Font<'static>>>,
+
+    quad: VertexBuffer<Vertex>,
+    rounded_rect_program: Program,
+    shadow_program: Program,
+    composite_program: Program,
+    blur_program: Program,
+    backdrop_program: Program,
+    image_program: Program,
+
+    text_program: Program,
+    glyph_cache: Cache<'static>,
+    glyph_cache_tex: SrgbTexture2d,
+}
+
+impl GliumRenderer {
+    const ROUNDED_RECT_VSRC: &'static str = include_str!("glium_renderer/rounded_rect.vert");
+    const ROUNDED_RECT_FSRC: &'static str = include_str!("glium_renderer/rounded_rect.frag");
+    const SHADOW_FSRC: &'static str = include_str!("glium_renderer/shadow.frag");
+    const COMPOSITE_VSRC: &'static str = include_str!("glium_renderer/composite.vert");
+    const COMPOSITE_FSRC: &'static str = include_str!("glium_renderer/composite.frag");
+    const BLUR_FSRC: &'static str = include_str!("glium_renderer/blur.frag");
+    const BACKDROP_FSRC: &'static str = include_str!("glium_renderer/backdrop.frag");
+    const IMAGE_FSRC: &'static str = include_str!("glium_renderer/image.frag");
+    const TEXT_VSRC: &'static str = include_str!("glium_renderer/text.vert");
+    const TEXT_FSRC: &'static str = include_str!("glium_renderer/text.frag");
+
+    /// Straight-alpha sources over a premultiplied destination
+    const BLEND: Blend = Blend {
+        color: BlendingFunction::Addition {
+            source: LinearBlendingFactor::SourceAlpha,
+            destination: LinearBlendingFactor::OneMinusSourceAlpha,
+        },
+        alpha: BlendingFunction::Addition {
+            source: LinearBlendingFactor::One,
+            destination: LinearBlendingFactor::OneMinusSourceAlpha,
+        },
+        constant_value: (0.0, 0.0, 0.0, 0.0),
+    };
+
+    /// Premultiplied sources over a premultiplied destination
+    const BLEND_PREMULTIPLIED: Blend = Blend {
+        color: BlendingFunction::Addition {
+            source: LinearBlendingFactor::One,
+            destination: LinearBlendingFactor::OneMinusSourceAlpha,
+        },
+        alpha: BlendingFunction::Addition {
+            source: LinearBlendingFactor::One,
+            destination: LinearBlendingFactor::OneMinusSourceAlpha,
+        },
+        constant_value: (0.0, 0.0, 0.0, 0.0),
+    };
+
+    /// Largest blur radius in physical pixels, bounding the blur shader loops
+    const MAX_BLUR_RADIUS: i32 = 64;
+
+    pub fn new(display: Display) -> Self {
+        let quad = VertexBuffer::new(
+            &display,
+            &[
+                Vertex {
+                    a_position: [0.0, 0.0],
+                },
+                Vertex {
+                    a_position: [1.0, 0.0],
+                },
+                Vertex {
+                    a_position: [1.0, 1.0],
+                },
+                Vertex {
+                    a_position: [0.0, 1.0],
+                },
+            ],
+        )
+        .expect("failed to create vbo");
+        let rounded_rect_program = Program::from_source(
+            &display,
+            Self::ROUNDED_RECT_VSRC,
+            Self::ROUNDED_RECT_FSRC,
+            None,
+        )
+        .unwrap_or_else(|err| panic!("{:#?}", err));
+        let shadow_program =
+            Program::from_source(&display, Self::ROUNDED_RECT_VSRC, Self::SHADOW_FSRC, None)
+                .unwrap_or_else(|err| panic!("{:#?}", err));
+        let composite_program =
+            Program::from_source(&display, Self::COMPOSITE_VSRC, Self::COMPOSITE_FSRC, None)
+                .unwrap_or_else(|err| panic!("{:#?}", err));
+        let blur_program =
+            Program::from_source(&display, Self::COMPOSITE_VSRC, Self::BLUR_FSRC, None)
+                .unwrap_or_else(|err| panic!("{:#?}", err));
+        let backdrop_program =
+            Program::from_source(&display, Self::ROUNDED_RECT_VSRC, Self::BACKDROP_FSRC, None)
+                .unwrap_or_else(|err| panic!("{:#?}", err));
+        let image_program =
+            Program::from_source(&display, Self::ROUNDED_RECT_VSRC, Self::IMAGE_FSRC, None)
+                .unwrap_or_else(|err| panic!("{:#?}", err));
+        let text_program = Program::from_source(&display, Self::TEXT_VSRC, Self::TEXT_FSRC, None)
+            .unwrap_or_else(|err| panic!("{:#?}", err));
+
+        let inner_size = display.gl_window().window().inner_size();
+        let (cache_width, cache_height) = (inner_size.width, inner_size.height);
+        let glyph_cache = Cache::builder()
+            .dimensions(cache_width, cache_height)
+            .build();
+        let glyph_cache_tex = SrgbTexture2d::with_format(
+            &display,
+            RawImage2d {
+                data: Cow::Owned(vec![128u8; cache_width as usize * cache_height as usize]),
+                width: cache_width,
+                height: cache_height,
+                format: ClientFormat::U8,
+            },
+            SrgbFormat::U8U8U8U8,
+            MipmapsOption::NoMipmap,
+        )
+        .unwrap();
+
+        GliumRenderer {
+            display,
+            canvas: None,
+            canvas_drawn: false,
+            clip: None,
+            layers: HashMap::new(),
+            targets: Vec::new(),
+            textures: HashMap::new(),
+            fonts: Vec::new(),
+            quad,
+            rounded_rect_program,
+            shadow_program,
+            composite_program,
+            blur_program,
+            backdrop_program,
+            image_program,
+            text_program,
+            glyph_cache,
+            glyph_cache_tex,
+        }
+    }
+
+    pub fn display(&self) -> &Display {
+        &self.display
+    }
+
+    /// Makes sure the canvas matches the window before drawing to it
+    fn begin_frame(&mut self) {
+        let size = self.frame_buffer_size();
+        let resized = self.canvas.as_ref().is_none_or(|canvas| {
+            (canvas.width(), canvas.height()) != (size.width.max(1), size.height.max(1))
+        });
+        if resized {
+            let canvas = Texture2d::empty(&self.display, size.width.max(1), size.height.max(1))
+                .expect("failed to create canvas texture");
+            self.canvas = Some(canvas);
+        }
+        self.canvas_drawn = true;
+    }
+
+    fn target_size(&self) -> PhysicalSize<u32> {
+        match self.targets.last().and_then(|id| self.layers.get(id)) {
+            Some(layer) => PhysicalSize::new(layer.texture.width(), layer.texture.height()),
+            None => self.frame_buffer_size(),
+        }
+    }
+
+    fn target_origin(&self) -> Vector2<f32> {
+        match self.targets.last().and_then(|id| self.layers.get(id)) {
+            Some(layer) => layer.origin,
+            None => Vector2::zeros(),
+        }
+    }
+
+    fn scissor(&self) -> Option<Rect> {
+        let size = self.target_size();
+        let origin = self.target_origin();
+        self.clip.map(|clip| {
+            let target_rect = PixelRect {
+                left: 0,
+                top: 0,
+                right: size.width as i32,
+                bottom: size.height as i32,
+            };
+            let clip = Layout {
+                position: clip.position - origin,
+                size: clip.size,
+            };
+            let rect =
+                PixelRect::from_layout(&clip, self.scale_factor()).intersection(&target_rect);
+            Rect {
+                left: rect.left.max(0) as u32,
+                bottom: (size.height as i32 - rect.bottom).max(0) as u32,
+                width: rect.width() as u32,
+                height: rect.height() as u32,
+            }
+        })
+    }
+
+    fn layer_size(&self, layout: &Layout) -> PhysicalSize<u32> {
+        let size = layout.size * self.scale_factor() as f32;
+        PhysicalSize::new(size.x.ceil().max(1.0) as u32, size.y.ceil().max(1.0) as u32)
+    }
+
+    fn font_id(&mut self, font: &Rc<rusttype::Font<'static>>) -> usize {
+        let ptr = Rc::as_ptr(font);
+        match self.fonts.iter().position(|known| known.as_ptr() == ptr) {
+            Some(id) => id,
+            None => {
+                self.fonts.push(Rc::downgrade(font));
+                self.fonts.len() - 1
+            }
+        }
+    }
+
+    fn font_render_info(&self, run: &GlyphRun, font_id: usize) -> Vec<FontRenderInfo> {
+        let mut render_info = vec![];
+
+        for glyph in run.glyphs {
+            match self.glyph_cache.rect_for(font_id, glyph) {
+                Err(_) | Ok(None) => continue,
+                Ok(Some((uv, position))) => {
+                    let uv_left_bottom = [uv.min.x, uv.min.y];
+                    let uv_right_bottom = [uv.max.x, uv.min.y];
+                    let uv_left_top = [uv.min.x, uv.max.y];
+                    let uv_right_top = [uv.max.x, uv.max.y];
+
+                    let position_left_bottom = [position.min.x, position.min.y];
+                    let position_right_bottom = [position.max.x, position.min.y];
+                    let position_left_top = [position.min.x, position.max.y];
+                    let position_right_top = [position.max.x, position.max.y];
+
+                    render_info.push(FontRenderInfo {
+                        a_position: position_left_bottom,
+                        a_uv: uv_left_bottom,
+                    });
+                    render_info.push(FontRenderInfo {
+                        a_position: position_right_bottom,
+                        a_uv: uv_right_bottom,
+                    });
+                    render_info.push(FontRenderInfo {
+                        a_position: position_right_top,
+                        a_uv: uv_right_top,
+                    });
+                    render_info.push(FontRenderInfo {
+                        a_position: position_right_top,
+                        a_uv: uv_right_top,
+                    });
+                    render_info.push(FontRenderInfo {
+                        a_position: position_left_top,
+                        a_uv: uv_left_top,
+                    });
+                    render_info.push(FontRenderInfo {
+                        a_position: position_left_bottom,
+                        a_uv: uv_left_bottom,
+                    });
+                }
+            }
+        }
+
+        render_info
+    }
+}
+
+impl Renderer for GliumRenderer {
+    fn frame_buffer_size(&self) -> PhysicalSize<u32> {
+        self.display.gl_window().window().inner_size()
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.display.gl_window().window().scale_factor()
+    }
+
+    fn request_redraw(&self) {
+        self.display.gl_window().window().request_redraw();
+    }
+
+    fn set_clip(&mut self, clip: Option<Layout>) {
+        self.clip = clip;
+    }
+
+    fn clear(&mut self, color: Vector4<f32>) {
+        let a = color.w;
+        let color = (color.x * a, color.y * a, color.z * a, a);
+        let scissor = self.scissor();
+        with_target!(self, surface => surface.clear(scissor.as_ref(), Some(color), false, None, None));
+    }
+
+    fn draw_rounded_rect(&mut self, rect: &RoundedRect) {
+        let layout = rect.layout;
+        let origin = self.target_origin();
+        let center: [f32; 2] = (layout.position - origin + 0.5 * layout.size).into();
+        let position: [f32; 2] = (layout.position - origin).into();
+        let plane_size: [f32; 2] = layout.size.into();
+        let fill = FillUniforms::from(&rect.fill);
+        let border_color: [f32; 4] = rect.border_color.into();
+        let resolution: [f32; 2] = self.target_size().into();
+        let scale_factor = self.scale_factor() as f32;
+        let scissor = self.scissor();
+
+        with_target!(self, surface => surface
+            .draw(
+                &self.quad,
+                NoIndices(PrimitiveType::TriangleFan),
+                &self.rounded_rect_program,
+                &uniform! {
+                    u_center_position: center,
+                    u_fill_kind: fill.kind,
+                    u_fill_from: fill.from,
+                    u_fill_to: fill.to,
+                    u_fill_from_color: fill.from_color,
+                    u_fill_to_color: fill.to_color,
+                    u_round_radius: rect.round_radius,
+                    u_border_width: rect.border_width,
+                    u_border_color: border_color,
+                    u_plane_size: plane_size,
+                    u_resolution: resolution,
+                    u_scale_factor: scale_factor,
+                    u_position: position,
+                },
+                &DrawParameters {
+                    blend: Self::BLEND_PREMULTIPLIED,
+                    scissor,
+                    ..DrawParameters::default()
+                },
+            )
+            .expect("failed to draw"));
+    }
+
+    fn draw_glyphs(&mut self, run: &GlyphRun) {
+        let font_id = self.font_id(run.font);
+        for glyph in run.glyphs {
+            self.glyph_cache.queue_glyph(font_id, glyph.clone());
+        }
+        // separate ownership
+        let cache = &mut self.glyph_cache;
+        let cache_tex = &mut self.glyph_cache_tex;
+        cache
+            .cache_queued(|rect, data| {
+                cache_tex.main_level().write(
+                    Rect {
+                        left: rect.min.x,
+                        bottom: rect.min.y,
+                        width: rect.width(),
+                        height: rect.height(),
+                    },
+                    RawImage2d {
+                        data: Cow::Borrowed(data),
+                        width: rect.width(),
+                        height: rect.height(),
+                        format: ClientFormat::U8,
+                    },
+                )
+            })
+            .expect("failed to cache glyphs");
+
+        let render_info = self.font_render_info(run, font_id);
+        if render_info.is_empty() {
+            return;
+        }
+        let vbo =
+            VertexBuffer::new(&self.display, &render_info).expect("failed to create vertex buffer");
+        let resolution: [f32; 2] = self.target_size().into();
+        let scale_factor = self.scale_factor() as f32;
+        let color: [f32; 4] = run.color.into();
+        let position = run.position - self.target_origin();
+        let scissor = self.scissor();
+
+        with_target!(self, surface => surface
+            .draw(
+                &vbo,
+                NoIndices(PrimitiveType::TrianglesList),
+                &self.text_program,
+                &uniform! {
+                    u_resolution: resolution,
+                    u_color: color,
+                    u_glyph_texture: self
+                        .glyph_cache_tex
+                        .sampled()
+                        .magnify_filter(MagnifySamplerFilter::Linear),
+                    u_scale_factor: scale_factor,
+                    u_position: [position.x, position.y],
+                },
+                &DrawParameters {
+                    blend: Self::BLEND,
+                    scissor,
+                    ..DrawParameters::default()
+                },
+            )
+            .expect("failed to draw text"));
+    }
+
+    fn draw_shadow(&mut self, shadow: &Shadow) {
+        let origin = self.target_origin();
+        let bounds = shadow.bounds();
+        let center: [f32; 2] = (shadow.layout.position - origin + 0.5 * shadow.layout.size).into();
+        let offset: [f32; 2] = shadow.offset.into();
+        let position: [f32; 2] = (bounds.position - origin).into();
+        let plane_size: [f32; 2] = bounds.size.into();
+        let shadow_size: [f32; 2] = shadow.layout.size.into();
+        let color: [f32; 4] = shadow.color.into();
+        let resolution: [f32; 2] = self.target_size().into();
+        let scale_factor = self.scale_factor() as f32;
+        let scissor = self.scissor();
+
+        with_target!(self, surface => surface
+            .draw(
+                &self.quad,
+                NoIndices(PrimitiveType::TriangleFan),
+                &self.shadow_program,
+                &uniform! {
+                    u_center_position: center,
+                    u_color: color,
+                    u_round_radius: shadow.round_radius,
+                    u_blur_radius: shadow.blur_radius,
+                    u_spread: shadow.spread,
+                    u_inset: shadow.inset,
+                    u_offset: offset,
+                    u_shadow_size: shadow_size,
+                    u_plane_size: plane_size,
+                    u_resolution: resolution,
+                    u_scale_factor: scale_factor,
+                    u_position: position,
+                },
+                &DrawParameters {
+                    blend: Self::BLEND,
+                    scissor,
+                    ..DrawParameters::default()
+                },
+            )
+            .expect("failed to draw shadow"));
+    }
+
+    fn draw_image(&mut self, image: &ImageRect) {
+        let bitmap = image.bitmap;
+        if bitmap.width == 0 || bitmap.height == 0 {
+            return;
+        }
+        if !self.textures.contains_key(&bitmap.id()) {
+            // uploaded top row first, so the shader samples with v growing downwards
+            let raw =
+                RawImage2d::from_raw_rgba(bitmap.pixels().to_vec(), (bitmap.width, bitmap.height));
+            let texture =
+                Texture2d::new(&self.display, raw).expect("failed to upload bitmap texture");
+            self.textures
+                .insert(bitmap.id(), (Rc::downgrade(bitmap), texture));
+        }
+
+        let layout = image.layout;
+        let origin = self.target_origin();
+        let center: [f32; 2] = (layout.position - origin + 0.5 * layout.size).into();
+        let position: [f32; 2] = (layout.position - origin).into();
+        let plane_size: [f32; 2] = layout.size.into();
+        let image_position: [f32; 2] = (image.image_layout.position - origin).into();
+        let image_size: [f32; 2] = image.image_layout.size.into();
+        let tint: [f32; 4] = image.tint.into();
+        let resolution: [f32; 2] = self.target_size().into();
+        let scale_factor = self.scale_factor() as f32;
+        let scissor = self.scissor();
+        let wrap = if image.tile {
+            SamplerWrapFunction::Repeat
+        } else {
+            SamplerWrapFunction::Clamp
+        };
+
+        with_target!(self, surface => surface
+            .draw(
+                &self.quad,
+                NoIndices(PrimitiveType::TriangleFan),
+                &self.image_program,
+                &uniform! {
+                    u_texture: self.textures[&bitmap.id()]
+                        .1
+                        .sampled()
+                        .wrap_function(wrap)
+                        .magnify_filter(MagnifySamplerFilter::Linear),
+                    u_image_position: image_position,
+                    u_image_size: image_size,
+                    u_tile: image.tile,
+                    u_tint: tint,
+                    u_center_position: center,
+                    u_round_radius: image.round_radius,
+                    u_plane_size: plane_size,
+                    u_resolution: resolution,
+                    u_scale_factor: scale_factor,
+                    u_position: position,
+                },
+                &DrawParameters {
+                    blend: Self::BLEND_PREMULTIPLIED,
+                    scissor,
+                    ..DrawParameters::default()
+                },
+            )
+            .expect("failed to draw image"));
+    }
+
+    fn draw_backdrop_blur(&mut self, blur: &BackdropBlur) {
+        let scale_factor = self.scale_factor() as f32;
+        let radius = ((blur.blur_radius * scale_factor).round() as i32).min(Self::MAX_BLUR_RADIUS);
+        let size = self.target_size();
+        let origin = self.target_origin();
+        let target_rect = PixelRect {
+            left: 0,
+            top: 0,
+            right: size.width as i32,
+            bottom: size.height as i32,
+        };
+        let layout = Layout {
+            position: blur.layout.position - origin,
+            size: blur.layout.size,
+        };
+        let mut bounds =
+            PixelRect::from_layout(&layout, self.scale_factor()).intersection(&target_rect);
+        if let Some(clip) = self.clip {
+            let clip = Layout {
+                position: clip.position - origin,
+                size: clip.size,
+            };
+            bounds = bounds.intersection(&PixelRect::from_layout(&clip, self.scale_factor()));
+        }
+        if radius <= 0 || bounds.is_empty() {
+            return;
+        }
+
+        // copy what lies beneath, with enough margin for the kernel, and blur it horizontally
+        let source = PixelRect {
+            left: bounds.left - radius,
+            top: bounds.top - radius,
+            right: bounds.right + radius,
+            bottom: bounds.bottom + radius,
+        }
+        .intersection(&target_rect);
+        let (width, height) = (source.width() as u32, source.height() as u32);
+        let source_rect = Rect {
+            left: source.left as u32,
+            bottom: (size.height as i32 - source.bottom) as u32,
+            width,
+            height,
+        };
+        let backdrop = Texture2d::empty(&self.display, width, height)
+            .expect("failed to create backdrop texture");
+        let blurred = Texture2d::empty(&self.display, width, height)
+            .expect("failed to create backdrop texture");
+        with_target!(self, surface => surface.blit_color(
+            &source_rect,
+            &backdrop.as_surface(),
+            &BlitTarget {
+                left: 0,
+                bottom: 0,
+                width: width as i32,
+                height: height as i32,
+            },
+            MagnifySamplerFilter::Nearest,
+        ));
+        blurred
+            .as_surface()
+            .draw(
+                &self.quad,
+                NoIndices(PrimitiveType::TriangleFan),
+                &self.blur_program,
+                &uniform! {
+                    u_texture: backdrop
+                        .sampled()
+                        .wrap_function(SamplerWrapFunction::Clamp)
+                        .magnify_filter(MagnifySamplerFilter::Nearest),
+                    u_radius: radius,
+                    u_plane_size: [width as f32, height as f32],
+                    u_resolution: [width as f32, height as f32],
+                    u_scale_factor: 1.0f32,
+                    u_position: [0.0f32, 0.0],
+                },
+                &DrawParameters::default(),
+            )
+            .expect("failed to blur backdrop");
+
+        // blur vertically into the target, masked by the rounded rect
+        let center: [f32; 2] = (layout.position + 0.5 * layout.size).into();
+        let position: [f32; 2] = layout.position.into();
+        let plane_size: [f32; 2] = layout.size.into();
+        let resolution: [f32; 2] = size.into();
+        let region = [
+            source_rect.left as f32,
+            source_rect.bottom as f32,
+            width as f32,
+            height as f32,
+        ];
+        let scissor = self.scissor();
+
+        with_target!(self, surface => surface
+            .draw(
+                &self.quad,
+                NoIndices(PrimitiveType::TriangleFan),
+                &self.backdrop_program,
+                &uniform! {
+                    u_texture: blurred
+                        .sampled()
+                        .wrap_function(SamplerWrapFunction::Clamp)
+                        .magnify_filter(MagnifySamplerFilter::Nearest),
+                    u_region: region,
+                    u_radius: radius,
+                    u_center_position: center,
+                    u_round_radius: blur.round_radius,
+                    u_plane_size: plane_size,
+                    u_resolution: resolution,
+                    u_scale_factor: scale_factor,
+                    u_position: position,
+                },
+                &DrawParameters {
+                    blend: Self::BLEND_PREMULTIPLIED,
+                    scissor,
+                    ..DrawParameters::default()
+                },
+            )
+            .expect("failed to draw backdrop blur"));
+    }
+
+    fn has_layer(&self, id: LayerId, layout: &Layout) -> bool {
+        let size = self.layer_size(layout);
+        self.layers.get(&id).is_some_and(|layer| {
+            (layer.texture.width(), layer.texture.height()) == (size.width, size.height)
+        })
+    }
+
+    fn begin_layer(&mut self, id: LayerId, layout: &Layout) {
+        let size = self.layer_size(layout);
+        let texture = match self.layers.remove(&id) {
+            Some(layer)
+                if (layer.texture.width(), layer.texture.height()) == (size.width, size.height) =>
+            {
+                layer.texture
+            }
+            _ => Texture2d::empty(&self.display, size.width, size.height)
+                .expect("failed to create layer texture"),
+        };
+        self.layers.insert(
+            id,
+            Layer {
+                texture,
+                origin: layout.position,
+                used: true,
+            },
+        );
+        self.targets.push(id);
+        self.clear(Vector4::zeros());
+    }
+
+    fn end_layer(&mut self) {
+        self.targets.pop();
+    }
+
+    fn composite_layer(&mut self, id: LayerId, composite: &Composite) {
+        let layer = match self.layers.get_mut(&id) {
+            Some(layer) => layer,
+            None => return,
+        };
+        layer.used = true;
+        let scale_factor = self.scale_factor() as f32;
+        let layer = &self.layers[&id];
+        let position: [f32; 2] = (composite.layout.position - self.target_origin()).into();
+        let plane_size: [f32; 2] = [
+            layer.texture.width() as f32 / scale_factor,
+            layer.texture.height() as f32 / scale_factor,
+        ];
+        let resolution: [f32; 2] = self.target_size().into();
+        let scissor = self.scissor();
+
+        with_target!(self, surface => surface
+            .draw(
+                &self.quad,
+                NoIndices(PrimitiveType::TriangleFan),
+                &self.composite_program,
+                &uniform! {
+                    u_layer: self.layers[&id]
+                        .texture
+                        .sampled()
+                        .magnify_filter(MagnifySamplerFilter::Nearest),
+                    u_opacity: composite.opacity,
+                    u_dim: composite.dim,
+                    u_plane_size: plane_size,
+                    u_resolution: resolution,
+                    u_scale_factor: scale_factor,
+                    u_position: position,
+                },
+                &DrawParameters {
+                    blend: Self::BLEND_PREMULTIPLIED,
+                    scissor,
+                    ..DrawParameters::default()
+                },
+            )
+            .expect("failed to composite layer"));
+    }
+
+    fn finish(&mut self) {
+        if std::mem::replace(&mut self.canvas_drawn, false) {
+            if let Some(canvas) = &self.canvas {
+                let frame = self.display.draw();
+                canvas
+                    .as_surface()
+                    .fill(&frame, MagnifySamplerFilter::Nearest);
+                frame.finish().expect("failed to finish frame");
+            }
+        }
+        self.layers
+            .retain(|_, layer| std::mem::replace(&mut layer.used, false));
+        self.textures
+            .retain(|_, (bitmap, _)| bitmap.strong_count() > 0);
+    }
+
+    fn read_frame(&self) -> Screenshot {
+        let image: RawImage2d<u8> = self
+            .display
+            .read_front_buffer()
+            .expect("failed to read front buffer");
+        Screenshot::from_bottom_up(image.width, image.height, &image.data)
+    }
+}
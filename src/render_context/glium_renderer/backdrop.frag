@@ -0,0 +1,39 @@
+#version 400 core
+
+uniform vec2 u_resolution;
+uniform float u_round_radius;
+uniform vec2 u_center_position;
+uniform vec2 u_plane_size;
+uniform float u_scale_factor;
+
+uniform sampler2D u_texture;
+// left, bottom, width and height of the blurred area in target pixels
+uniform vec4 u_region;
+uniform int u_radius;
+
+in vec2 v_position;
+
+out vec4 o_color;
+
+// vertical pass of the backdrop blur, masked by the rounded rect
+void main() {
+    vec2 center_position = vec2(u_center_position.x * u_scale_factor, u_resolution.y - u_center_position.y * u_scale_factor);
+    vec2 half_size = u_plane_size * u_scale_factor * .5;
+    float round_radius = clamp(u_round_radius * u_scale_factor, 0.0, min(half_size.x, half_size.y));
+    vec2 q = abs(gl_FragCoord.xy - center_position) - half_size + vec2(round_radius);
+    float d = length(max(q, vec2(0.0))) + min(max(q.x, q.y), 0.0) - round_radius;
+
+    vec2 uv = (gl_FragCoord.xy - u_region.xy) / u_region.zw;
+    vec2 texel = vec2(0.0, 1.0 / u_region.w);
+    // normalized gaussian weights, as in the software renderer
+    float sigma = max(float(u_radius) / 2.0, 0.5);
+    vec4 color = vec4(0.0);
+    float sum = 0.0;
+    for(int i = -u_radius; i <= u_radius; i++) {
+        float weight = exp(-float(i * i) / (2.0 * sigma * sigma));
+        color += texture(u_texture, uv + texel * float(i)) * weight;
+        sum += weight;
+    }
+    color /= sum;
+    o_color = color * clamp(0.5 - d, 0.0, 1.0);
+}
@@ -0,0 +1,24 @@
+#version 400 core
+
+uniform sampler2D u_texture;
+uniform int u_radius;
+
+in vec2 v_uv;
+
+out vec4 o_color;
+
+// horizontal pass of the backdrop blur
+void main() {
+    vec2 texel = vec2(1.0 / textureSize(u_texture, 0).x, 0.0);
+    // normalized gaussian weights, as in the software renderer
+    float sigma = max(float(u_radius) / 2.0, 0.5);
+    vec4 color = vec4(0.0);
+    float sum = 0.0;
+    for(int i = -u_radius; i <= u_radius; i++) {
+        float weight = exp(-float(i * i) / (2.0 * sigma * sigma));
+        color += texture(u_texture, v_uv + texel * float(i)) * weight;
+        sum += weight;
+    }
+    color /= sum;
+    o_color = color;
+}
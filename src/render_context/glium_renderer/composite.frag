@@ -0,0 +1,15 @@
+#version 400 core
+
+uniform sampler2D u_layer;
+uniform float u_opacity;
+uniform float u_dim;
+
+in vec2 v_uv;
+
+out vec4 o_color;
+
+void main() {
+    // layer contents are premultiplied
+    vec4 color = texture(u_layer, v_uv);
+    o_color = vec4(color.rgb * u_opacity * (1.0 - u_dim), color.a * u_opacity);
+}
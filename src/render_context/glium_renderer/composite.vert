@@ -0,0 +1,23 @@
+#version 400 core
+
+uniform vec2 u_resolution;
+uniform float u_scale_factor;
+uniform vec2 u_position;
+uniform vec2 u_plane_size;
+
+in vec2 a_position;
+
+out vec2 v_uv;
+
+void main() {
+    vec2 local_position = a_position * u_plane_size;
+    vec2 physical_position = vec2(
+            (local_position.x + u_position.x) * u_scale_factor,
+            u_resolution.y - (local_position.y + u_position.y) * u_scale_factor);
+    vec2 scaled_position = (physical_position / u_resolution) * 2.0 - vec2(1.0);
+
+    // layers are rendered upside down relative to the quad
+    v_uv = vec2(a_position.x, 1.0 - a_position.y);
+
+    gl_Position = vec4(scaled_position, 0.0, 1.0);
+}
@@ -0,0 +1,36 @@
+#version 400 core
+
+uniform vec2 u_resolution;
+uniform float u_round_radius;
+uniform vec2 u_center_position;
+uniform vec2 u_plane_size;
+uniform float u_scale_factor;
+
+uniform sampler2D u_texture;
+uniform vec2 u_image_position;
+uniform vec2 u_image_size;
+uniform bool u_tile;
+uniform vec4 u_tint;
+
+in vec2 v_position;
+
+out vec4 o_color;
+
+void main() {
+    vec2 center_position = vec2(u_center_position.x * u_scale_factor, u_resolution.y - u_center_position.y * u_scale_factor);
+    vec2 half_size = u_plane_size * u_scale_factor * .5;
+    float round_radius = clamp(u_round_radius * u_scale_factor, 0.0, min(half_size.x, half_size.y));
+    vec2 q = abs(gl_FragCoord.xy - center_position) - half_size + vec2(round_radius);
+    float d = length(max(q, vec2(0.0))) + min(max(q.x, q.y), 0.0) - round_radius;
+
+    // bitmaps are uploaded top row first, so v grows downwards like the layout
+    vec2 point = vec2(gl_FragCoord.x, u_resolution.y - gl_FragCoord.y) / u_scale_factor;
+    vec2 uv = (point - u_image_position) / u_image_size;
+    if(!u_tile && (min(uv.x, uv.y) < 0.0 || max(uv.x, uv.y) > 1.0)) {
+        discard;
+    }
+
+    // texels are premultiplied
+    vec4 tint = vec4(u_tint.rgb * u_tint.a, u_tint.a);
+    o_color = texture(u_texture, uv) * tint * clamp(0.5 - d, 0.0, 1.0);
+}
@@ -0,0 +1,57 @@
+#version 400 core
+
+uniform vec2 u_resolution;
+uniform float u_round_radius;
+uniform float u_border_width;
+uniform vec4 u_border_color;
+uniform vec2 u_center_position;
+uniform vec2 u_position;
+uniform vec2 u_plane_size;
+uniform float u_scale_factor;
+
+// 0: solid, 1: linear gradient, 2: radial gradient
+uniform int u_fill_kind;
+// linear: start and end points, radial: center and (radius, 0)
+uniform vec2 u_fill_from;
+uniform vec2 u_fill_to;
+uniform vec4 u_fill_from_color;
+uniform vec4 u_fill_to_color;
+
+in vec2 v_position;
+
+out vec4 o_color;
+
+vec4 premultiply(vec4 color) {
+    return vec4(color.rgb * color.a, color.a);
+}
+
+vec4 fill_color(vec2 point) {
+    float t = 0.0;
+    if(u_fill_kind == 1) {
+        vec2 direction = u_fill_to - u_fill_from;
+        t = dot(point - u_fill_from, direction) / max(dot(direction, direction), 1e-6);
+    } else if(u_fill_kind == 2) {
+        t = length(point - u_fill_from) / max(u_fill_to.x, 1e-6);
+    }
+    return mix(premultiply(u_fill_from_color), premultiply(u_fill_to_color), clamp(t, 0.0, 1.0));
+}
+
+void main() {
+    vec2 center_position = vec2(u_center_position.x * u_scale_factor, u_resolution.y - u_center_position.y * u_scale_factor);
+    vec2 half_size = u_plane_size * u_scale_factor * .5;
+    float round_radius = clamp(u_round_radius * u_scale_factor, 0.0, min(half_size.x, half_size.y));
+    float border_width = u_border_width * u_scale_factor;
+
+    // signed distance to the edge, negative inside
+    vec2 q = abs(gl_FragCoord.xy - center_position) - half_size + vec2(round_radius);
+    float d = length(max(q, vec2(0.0))) + min(max(q.x, q.y), 0.0) - round_radius;
+
+    vec2 point = vec2(gl_FragCoord.x, u_resolution.y - gl_FragCoord.y) / u_scale_factor - u_position;
+    float border = border_width > 0.0 ? clamp(d + border_width + 0.5, 0.0, 1.0) : 0.0;
+    float coverage = clamp(0.5 - d, 0.0, 1.0);
+    vec4 color = mix(fill_color(point), premultiply(u_border_color), border) * coverage;
+    if(color.a <= 0.0) {
+        discard;
+    }
+    o_color = color;
+}
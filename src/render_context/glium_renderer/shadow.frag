@@ -0,0 +1,41 @@
+#version 400 core
+
+uniform vec2 u_resolution;
+uniform vec4 u_color;
+uniform float u_round_radius;
+uniform float u_blur_radius;
+uniform float u_spread;
+uniform bool u_inset;
+uniform vec2 u_center_position;
+uniform vec2 u_offset;
+uniform vec2 u_shadow_size;
+uniform float u_scale_factor;
+
+in vec2 v_position;
+
+out vec4 o_color;
+
+float rounded_rect_distance(vec2 p, vec2 half_size, float round_radius) {
+    round_radius = clamp(round_radius, 0.0, min(half_size.x, half_size.y));
+    vec2 q = abs(p) - half_size + vec2(round_radius);
+    return length(max(q, vec2(0.0))) + min(max(q.x, q.y), 0.0) - round_radius;
+}
+
+void main() {
+    float blur_radius = max(u_blur_radius * u_scale_factor, 1.0);
+    vec2 center_position = vec2(u_center_position.x * u_scale_factor, u_resolution.y - u_center_position.y * u_scale_factor);
+    vec2 half_size = u_shadow_size * u_scale_factor * .5;
+    float round_radius = u_round_radius * u_scale_factor;
+
+    // inset shadows shrink by the spread instead of growing
+    float spread = u_spread * u_scale_factor * (u_inset ? -1.0 : 1.0);
+    vec2 shape_center = center_position + vec2(u_offset.x, -u_offset.y) * u_scale_factor;
+    vec2 shape_half_size = max(half_size + vec2(spread), vec2(0.0));
+    float d = rounded_rect_distance(gl_FragCoord.xy - shape_center, shape_half_size, max(round_radius + spread, 0.0));
+    float coverage = 1.0 - smoothstep(-blur_radius, blur_radius, d);
+    if(u_inset) {
+        float d_outer = rounded_rect_distance(gl_FragCoord.xy - center_position, half_size, round_radius);
+        coverage = (1.0 - coverage) * clamp(0.5 - d_outer, 0.0, 1.0);
+    }
+    o_color = vec4(u_color.rgb, u_color.a * coverage);
+}
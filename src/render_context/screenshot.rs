@@ -0,0 +1,86 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+/// RGBA8 image read back from a frame, row-major from the top-left corner
+#[derive(Clone, Debug, PartialEq)]
+pub struct Screenshot {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Screenshot {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width as usize * height as usize * 4,
+            "pixel buffer does not match the image size"
+        );
+        Screenshot {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Builds a screenshot from rows ordered bottom to top, as OpenGL reads them back
+    pub fn from_bottom_up(width: u32, height: u32, pixels: &[u8]) -> Self {
+        let row = width as usize * 4;
+        let pixels = pixels.chunks_exact(row).rev().flatten().copied().collect();
+        Self::new(width, height, pixels)
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = File::create(path)?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        writer.write_image_data(&self.pixels).map_err(png_error)
+    }
+
+    pub fn load_png(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(File::open(path)?);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let (info, mut reader) = decoder.read_info().map_err(png_error)?;
+        let mut buf = vec![0; info.buffer_size()];
+        reader.next_frame(&mut buf).map_err(png_error)?;
+
+        let pixels = match info.color_type {
+            png::ColorType::RGBA => buf,
+            png::ColorType::RGB => buf
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255].to_vec())
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buf
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]].to_vec())
+                .collect(),
+            png::ColorType::Grayscale => {
+                buf.iter().flat_map(|&g| [g, g, g, 255].to_vec()).collect()
+            }
+            png::ColorType::Indexed => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "indexed png was not expanded",
+                ))
+            }
+        };
+        Ok(Self::new(info.width, info.height, pixels))
+    }
+}
+
+fn png_error(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
@@ -0,0 +1,520 @@
+use super::{
+    BackdropBlur, Bitmap, Composite, GlyphRun, ImageRect, LayerId, PixelRect, Renderer,
+    RoundedRect, Screenshot, Shadow,
+};
+use crate::component::Layout;
+
+use glium::glutin::dpi::PhysicalSize;
+
+use nalgebra::{Vector2, Vector4};
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+/// Premultiplied RGBA8 pixels whose top-left corner sits at `origin` in logical coordinates
+struct Canvas {
+    size: PhysicalSize<u32>,
+    origin: Vector2<f32>,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(size: PhysicalSize<u32>, origin: Vector2<f32>) -> Self {
+        let pixels = vec![0u8; size.width as usize * size.height as usize * 4];
+        Canvas {
+            size,
+            origin,
+            pixels,
+        }
+    }
+
+    fn rect(&self) -> PixelRect {
+        PixelRect {
+            left: 0,
+            top: 0,
+            right: self.size.width as i32,
+            bottom: self.size.height as i32,
+        }
+    }
+
+    /// Pixels of this canvas covering `layout`
+    fn pixel_rect(&self, layout: &Layout, scale_factor: f64) -> PixelRect {
+        let layout = Layout {
+            position: layout.position - self.origin,
+            size: layout.size,
+        };
+        PixelRect::from_layout(&layout, scale_factor)
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.size.width as usize + x as usize) * 4
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = self.index(x, y);
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    fn blend(&mut self, x: i32, y: i32, color: Vector4<f32>, coverage: f32) {
+        let alpha = (color.w * coverage).clamp(0.0, 1.0);
+        let premultiplied = Vector4::new(color.x * alpha, color.y * alpha, color.z * alpha, alpha);
+        self.blend_premultiplied(x, y, premultiplied);
+    }
+
+    fn blend_premultiplied(&mut self, x: i32, y: i32, color: Vector4<f32>) {
+        if color.w <= 0.0 || !self.rect().contains(x, y) {
+            return;
+        }
+        let i = self.index(x as u32, y as u32);
+        let dst = &mut self.pixels[i..i + 4];
+        for (c, &src) in dst.iter_mut().zip(color.iter()) {
+            let d = *c as f32 / 255.0;
+            *c = ((src + d * (1.0 - color.w)) * 255.0)
+                .round()
+                .clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Rasterizes on the CPU into an RGBA buffer, so no display server or GL context is required
+pub struct SoftwareRenderer {
+    scale_factor: f64,
+    frame: Canvas,
+    /// Offscreen layers, flagged when used during the current frame
+    layers: HashMap<LayerId, (Canvas, bool)>,
+    targets: Vec<LayerId>,
+    clip: Option<Layout>,
+    redraw_requested: Cell<bool>,
+}
+
+impl SoftwareRenderer {
+    /// Largest blur radius in physical pixels, matching the blur shaders
+    const MAX_BLUR_RADIUS: i32 = 64;
+
+    pub fn new(size: PhysicalSize<u32>, scale_factor: f64) -> Self {
+        SoftwareRenderer {
+            scale_factor,
+            frame: Canvas::new(size, Vector2::zeros()),
+            layers: HashMap::new(),
+            targets: Vec::new(),
+            clip: None,
+            redraw_requested: Cell::new(true),
+        }
+    }
+
+    /// RGBA8 pixels of the last frame, row-major from the top-left corner
+    pub fn pixels(&self) -> &[u8] {
+        &self.frame.pixels
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        self.frame.pixel(x, y)
+    }
+
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        self.frame = Canvas::new(size, Vector2::zeros());
+        self.request_redraw();
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+        self.request_redraw();
+    }
+
+    /// Returns whether a redraw has been requested since the last call
+    pub fn take_redraw_request(&self) -> bool {
+        self.redraw_requested.replace(false)
+    }
+
+    fn target(&mut self) -> &mut Canvas {
+        match self.targets.last() {
+            Some(id) => &mut self.layers.get_mut(id).expect("layer not begun").0,
+            None => &mut self.frame,
+        }
+    }
+
+    /// Pixels of the current target covered by `layout` and the clip
+    fn bounds(&mut self, layout: &Layout) -> PixelRect {
+        let scale_factor = self.scale_factor;
+        let clip = self.clip;
+        let target = self.target();
+        let mut bounds = target
+            .pixel_rect(layout, scale_factor)
+            .intersection(&target.rect());
+        if let Some(clip) = clip {
+            bounds = bounds.intersection(&target.pixel_rect(&clip, scale_factor));
+        }
+        bounds
+    }
+
+    /// Center of pixel `(x, y)` of the current target, in physical frame coordinates
+    fn sample_point(&mut self, x: i32, y: i32) -> Vector2<f32> {
+        let scale_factor = self.scale_factor as f32;
+        let origin = self.target().origin * scale_factor;
+        Vector2::new(x as f32 + 0.5, y as f32 + 0.5) + origin
+    }
+
+    fn layer_size(&self, layout: &Layout) -> PhysicalSize<u32> {
+        let size = layout.size * self.scale_factor as f32;
+        PhysicalSize::new(size.x.ceil().max(0.0) as u32, size.y.ceil().max(0.0) as u32)
+    }
+}
+
+/// Signed distance from `p` to a rounded rectangle centered at the origin, negative inside
+fn rounded_rect_distance(p: Vector2<f32>, half_size: Vector2<f32>, round_radius: f32) -> f32 {
+    let round_radius = round_radius.min(half_size.x).min(half_size.y).max(0.0);
+    let radius = Vector2::new(round_radius, round_radius);
+    let q = p.abs() - half_size + radius;
+    q.sup(&Vector2::zeros()).norm() + q.x.max(q.y).min(0.0) - round_radius
+}
+
+fn premultiply(color: Vector4<f32>) -> Vector4<f32> {
+    Vector4::new(
+        color.x * color.w,
+        color.y * color.w,
+        color.z * color.w,
+        color.w,
+    )
+}
+
+/// Falloff of a shadow at signed distance `d` from its shape, matching the shadow shader
+fn shadow_coverage(d: f32, blur_radius: f32) -> f32 {
+    let t = ((d + blur_radius) / (2.0 * blur_radius)).clamp(0.0, 1.0);
+    1.0 - t * t * (3.0 - 2.0 * t)
+}
+
+/// Bilinearly filtered premultiplied color of `bitmap` at `uv`, matching GL texture sampling
+fn sample_bitmap(bitmap: &Bitmap, uv: Vector2<f32>, tile: bool) -> Vector4<f32> {
+    let (width, height) = (bitmap.width as i32, bitmap.height as i32);
+    let texel = |x: i32, y: i32| {
+        let (x, y) = if tile {
+            (x.rem_euclid(width), y.rem_euclid(height))
+        } else {
+            (x.clamp(0, width - 1), y.clamp(0, height - 1))
+        };
+        let i = (y as usize * width as usize + x as usize) * 4;
+        let p = &bitmap.pixels()[i..i + 4];
+        Vector4::new(p[0], p[1], p[2], p[3]).map(|c| c as f32 / 255.0)
+    };
+    let x = uv.x * width as f32 - 0.5;
+    let y = uv.y * height as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    let top = texel(x0, y0).lerp(&texel(x0 + 1, y0), fx);
+    let bottom = texel(x0, y0 + 1).lerp(&texel(x0 + 1, y0 + 1), fx);
+    top.lerp(&bottom, fy)
+}
+
+/// Normalized gaussian weights for offsets `-radius..=radius`, matching the blur shaders
+fn gaussian_kernel(radius: i32) -> Vec<f32> {
+    let sigma = (radius as f32 / 2.0).max(0.5);
+    let weights: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = weights.iter().sum();
+    weights.into_iter().map(|w| w / sum).collect()
+}
+
+/// Premultiplied color of a rounded rect at signed distance `d` and `point` relative to its
+/// layout, matching the rounded rect shader
+fn rounded_rect_color(
+    rect: &RoundedRect,
+    d: f32,
+    point: Vector2<f32>,
+    scale_factor: f32,
+) -> Vector4<f32> {
+    let border_width = rect.border_width * scale_factor;
+    let border = if border_width > 0.0 {
+        (d + border_width + 0.5).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let coverage = (0.5 - d).clamp(0.0, 1.0);
+    let fill = premultiply(rect.fill.color_at(point));
+    fill.lerp(&premultiply(rect.border_color), border) * coverage
+}
+
+impl Renderer for SoftwareRenderer {
+    fn frame_buffer_size(&self) -> PhysicalSize<u32> {
+        self.frame.size
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    fn request_redraw(&self) {
+        self.redraw_requested.set(true);
+    }
+
+    fn set_clip(&mut self, clip: Option<Layout>) {
+        self.clip = clip;
+    }
+
+    fn clear(&mut self, color: Vector4<f32>) {
+        let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let alpha = color.w.clamp(0.0, 1.0);
+        let color = [
+            to_u8(color.x * alpha),
+            to_u8(color.y * alpha),
+            to_u8(color.z * alpha),
+            to_u8(alpha),
+        ];
+        let scale_factor = self.scale_factor;
+        let clip = self.clip;
+        let target = self.target();
+        let mut bounds = target.rect();
+        if let Some(clip) = clip {
+            bounds = bounds.intersection(&target.pixel_rect(&clip, scale_factor));
+        }
+        for y in bounds.top..bounds.bottom {
+            for x in bounds.left..bounds.right {
+                let i = target.index(x as u32, y as u32);
+                target.pixels[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    fn draw_rounded_rect(&mut self, rect: &RoundedRect) {
+        let scale_factor = self.scale_factor as f32;
+        let half_size = 0.5 * rect.layout.size * scale_factor;
+        let center = (rect.layout.position + 0.5 * rect.layout.size) * scale_factor;
+        let round_radius = rect.round_radius * scale_factor;
+
+        let bounds = self.bounds(&rect.layout);
+        for y in bounds.top..bounds.bottom {
+            for x in bounds.left..bounds.right {
+                // evaluated at the pixel center, like the rounded rect shader
+                let sample_point = self.sample_point(x, y);
+                let d = rounded_rect_distance(sample_point - center, half_size, round_radius);
+                let point = sample_point / scale_factor - rect.layout.position;
+                let color = rounded_rect_color(rect, d, point, scale_factor);
+                self.target().blend_premultiplied(x, y, color);
+            }
+        }
+    }
+
+    fn draw_glyphs(&mut self, run: &GlyphRun) {
+        let scale_factor = self.scale_factor as f32;
+        let clip = self.clip.map(|clip| self.bounds(&clip));
+        let target = self.target();
+        let origin = (run.position - target.origin) * scale_factor;
+        let (origin_x, origin_y) = (origin.x.round() as i32, origin.y.round() as i32);
+
+        for glyph in run.glyphs {
+            if let Some(bb) = glyph.pixel_bounding_box() {
+                let mut coverage = Vec::new();
+                glyph.draw(|x, y, v| coverage.push((x as i32, y as i32, v)));
+                for (x, y, v) in coverage {
+                    // same coverage curve as the text shader
+                    let v = if v >= 1.0 {
+                        1.0
+                    } else {
+                        1.0 - 2f32.powf(-10.0 * v)
+                    };
+                    let x = origin_x + bb.min.x + x;
+                    let y = origin_y + bb.min.y + y;
+                    if clip.is_none_or(|clip| clip.contains(x, y)) {
+                        target.blend(x, y, run.color, v);
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_shadow(&mut self, shadow: &Shadow) {
+        let scale_factor = self.scale_factor as f32;
+        let half_size = 0.5 * shadow.layout.size * scale_factor;
+        let center = (shadow.layout.position + 0.5 * shadow.layout.size) * scale_factor;
+        let round_radius = shadow.round_radius * scale_factor;
+        let blur_radius = (shadow.blur_radius * scale_factor).max(1.0);
+        // inset shadows shrink by the spread instead of growing
+        let spread = shadow.spread * scale_factor * if shadow.inset { -1.0 } else { 1.0 };
+        let shape_half_size = (half_size + Vector2::new(spread, spread)).sup(&Vector2::zeros());
+        let shape_center = center + shadow.offset * scale_factor;
+        let shape_round_radius = (round_radius + spread).max(0.0);
+
+        let bounds = self.bounds(&shadow.bounds());
+        for y in bounds.top..bounds.bottom {
+            for x in bounds.left..bounds.right {
+                let p = self.sample_point(x, y);
+                let d =
+                    rounded_rect_distance(p - shape_center, shape_half_size, shape_round_radius);
+                let coverage = if shadow.inset {
+                    let d_outer = rounded_rect_distance(p - center, half_size, round_radius);
+                    (1.0 - shadow_coverage(d, blur_radius)) * (0.5 - d_outer).clamp(0.0, 1.0)
+                } else {
+                    shadow_coverage(d, blur_radius)
+                };
+                self.target().blend(x, y, shadow.color, coverage);
+            }
+        }
+    }
+
+    fn draw_image(&mut self, image: &ImageRect) {
+        let bitmap = image.bitmap;
+        if bitmap.width == 0 || bitmap.height == 0 {
+            return;
+        }
+        let scale_factor = self.scale_factor as f32;
+        let half_size = 0.5 * image.layout.size * scale_factor;
+        let center = (image.layout.position + 0.5 * image.layout.size) * scale_factor;
+        let round_radius = image.round_radius * scale_factor;
+        let tint = premultiply(image.tint);
+
+        let bounds = self.bounds(&image.layout);
+        for y in bounds.top..bounds.bottom {
+            for x in bounds.left..bounds.right {
+                let p = self.sample_point(x, y);
+                let uv = (p / scale_factor - image.image_layout.position)
+                    .component_div(&image.image_layout.size);
+                if !image.tile && (uv.min() < 0.0 || uv.max() > 1.0) {
+                    continue;
+                }
+                let d = rounded_rect_distance(p - center, half_size, round_radius);
+                let color = sample_bitmap(bitmap, uv, image.tile).component_mul(&tint);
+                self.target()
+                    .blend_premultiplied(x, y, color * (0.5 - d).clamp(0.0, 1.0));
+            }
+        }
+    }
+
+    fn draw_backdrop_blur(&mut self, blur: &BackdropBlur) {
+        let scale_factor = self.scale_factor as f32;
+        let radius = ((blur.blur_radius * scale_factor).round() as i32).min(Self::MAX_BLUR_RADIUS);
+        let bounds = self.bounds(&blur.layout);
+        if radius <= 0 || bounds.is_empty() {
+            return;
+        }
+        let half_size = 0.5 * blur.layout.size * scale_factor;
+        let center = (blur.layout.position + 0.5 * blur.layout.size) * scale_factor;
+        let round_radius = blur.round_radius * scale_factor;
+        let kernel = gaussian_kernel(radius);
+
+        let target = self.target();
+        let origin = target.origin * scale_factor;
+        let source = PixelRect {
+            left: bounds.left - radius,
+            top: bounds.top - radius,
+            right: bounds.right + radius,
+            bottom: bounds.bottom + radius,
+        }
+        .intersection(&target.rect());
+        let read = |x: i32, y: i32| {
+            let x = x.clamp(source.left, source.right - 1) as u32;
+            let y = y.clamp(source.top, source.bottom - 1) as u32;
+            let p = target.pixel(x, y);
+            Vector4::new(p[0], p[1], p[2], p[3]).map(|c| c as f32 / 255.0)
+        };
+
+        // separable gaussian, horizontally over every source row and then vertically
+        let width = bounds.width() as usize;
+        let mut horizontal = Vec::with_capacity(width * source.height() as usize);
+        for y in source.top..source.bottom {
+            for x in bounds.left..bounds.right {
+                let color = kernel
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| read(x + i as i32 - radius, y) * *w)
+                    .sum::<Vector4<f32>>();
+                horizontal.push(color);
+            }
+        }
+        for y in bounds.top..bounds.bottom {
+            for x in bounds.left..bounds.right {
+                let column = (x - bounds.left) as usize;
+                let color = kernel
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| {
+                        let row = (y + i as i32 - radius).clamp(source.top, source.bottom - 1);
+                        horizontal[(row - source.top) as usize * width + column] * *w
+                    })
+                    .sum::<Vector4<f32>>();
+                let p = Vector2::new(x as f32 + 0.5, y as f32 + 0.5) + origin - center;
+                let d = rounded_rect_distance(p, half_size, round_radius);
+                target.blend_premultiplied(x, y, color * (0.5 - d).clamp(0.0, 1.0));
+            }
+        }
+    }
+
+    fn has_layer(&self, id: LayerId, layout: &Layout) -> bool {
+        self.layers
+            .get(&id)
+            .is_some_and(|(canvas, _)| canvas.size == self.layer_size(layout))
+    }
+
+    fn begin_layer(&mut self, id: LayerId, layout: &Layout) {
+        let canvas = Canvas::new(self.layer_size(layout), layout.position);
+        self.layers.insert(id, (canvas, true));
+        self.targets.push(id);
+    }
+
+    fn end_layer(&mut self) {
+        self.targets.pop();
+    }
+
+    fn composite_layer(&mut self, id: LayerId, composite: &Composite) {
+        let scale_factor = self.scale_factor;
+        // take the layer out so it can be read while the target is written
+        let layer = match self.layers.get_mut(&id) {
+            Some((layer, used)) => {
+                *used = true;
+                std::mem::replace(
+                    layer,
+                    Canvas::new(PhysicalSize::new(0, 0), Vector2::zeros()),
+                )
+            }
+            None => return,
+        };
+
+        let clip = self.clip;
+        let target = self.target();
+        let origin = (composite.layout.position - target.origin) * scale_factor as f32;
+        let (origin_x, origin_y) = (origin.x.round() as i32, origin.y.round() as i32);
+        let mut bounds = target.rect();
+        if let Some(clip) = clip {
+            bounds = bounds.intersection(&target.pixel_rect(&clip, scale_factor));
+        }
+        let brightness = composite.opacity * (1.0 - composite.dim);
+        for y in 0..layer.size.height {
+            for x in 0..layer.size.width {
+                let (tx, ty) = (origin_x + x as i32, origin_y + y as i32);
+                if !bounds.contains(tx, ty) {
+                    continue;
+                }
+                let p = layer.pixel(x, y);
+                let color = Vector4::new(
+                    p[0] as f32 / 255.0 * brightness,
+                    p[1] as f32 / 255.0 * brightness,
+                    p[2] as f32 / 255.0 * brightness,
+                    p[3] as f32 / 255.0 * composite.opacity,
+                );
+                target.blend_premultiplied(tx, ty, color);
+            }
+        }
+
+        if let Some((canvas, _)) = self.layers.get_mut(&id) {
+            *canvas = layer;
+        }
+    }
+
+    fn finish(&mut self) {
+        self.layers
+            .retain(|_, (_, used)| std::mem::replace(used, false));
+    }
+
+    fn read_frame(&self) -> Screenshot {
+        Screenshot::new(
+            self.frame.size.width,
+            self.frame.size.height,
+            self.frame.pixels.clone(),
+        )
+    }
+}
@@ -0,0 +1,119 @@
+use nalgebra::Vector4;
+
+/// Colors widgets are painted with
+#[derive(Clone, Debug)]
+pub struct Palette {
+    pub desktop_background: Vector4<f32>,
+    pub window_background: Vector4<f32>,
+    pub window_title: Vector4<f32>,
+    pub window_shadow: Vector4<f32>,
+    /// Default fill of planes
+    pub surface: Vector4<f32>,
+    pub text: Vector4<f32>,
+    pub button_background: Vector4<f32>,
+    pub button_hovered: Vector4<f32>,
+    pub button_pressed: Vector4<f32>,
+    pub button_disabled: Vector4<f32>,
+    pub button_text: Vector4<f32>,
+    /// Labels of controls that can't be interacted with
+    pub disabled_text: Vector4<f32>,
+    pub input_background: Vector4<f32>,
+    pub input_text: Vector4<f32>,
+    /// Outlines of checkboxes, radio buttons and unset switch tracks
+    pub control_border: Vector4<f32>,
+    /// Highlights such as focus rings and selections
+    pub accent: Vector4<f32>,
+}
+
+/// Font sizes in logical pixels
+#[derive(Clone, Debug)]
+pub struct Typography {
+    pub title_size: f32,
+    pub body_size: f32,
+}
+
+/// Corner radii in logical pixels
+#[derive(Clone, Debug)]
+pub struct Radii {
+    pub window: f32,
+    pub control: f32,
+}
+
+/// Look shared by all widgets, held by `Global` and switchable at runtime
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub palette: Palette,
+    pub typography: Typography,
+    pub radii: Radii,
+    /// Padding between a window's edge and its contents
+    pub window_frame_width: f32,
+    /// Padding between a button's edge and its label
+    pub button_frame_width: f32,
+    pub title_height: f32,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Theme {
+            palette: Palette {
+                desktop_background: Vector4::new(0.8, 0.9, 1.0, 1.0),
+                window_background: Vector4::new(0.94, 0.94, 0.95, 1.0),
+                window_title: Vector4::new(0.15, 0.35, 0.55, 1.0),
+                window_shadow: Vector4::new(0.0, 0.0, 0.0, 0.3),
+                surface: Vector4::new(0.8, 0.8, 0.8, 0.8),
+                text: Vector4::new(0.1, 0.1, 0.1, 1.0),
+                button_background: Vector4::new(1.0, 1.0, 0.5, 1.0),
+                button_hovered: Vector4::new(1.0, 1.0, 0.7, 1.0),
+                button_pressed: Vector4::new(0.7, 0.7, 0.35, 1.0),
+                button_disabled: Vector4::new(0.85, 0.85, 0.8, 1.0),
+                button_text: Vector4::new(0.0, 0.4, 0.4, 1.0),
+                disabled_text: Vector4::new(0.55, 0.55, 0.55, 1.0),
+                input_background: Vector4::new(1.0, 1.0, 1.0, 1.0),
+                input_text: Vector4::new(0.1, 0.1, 0.1, 1.0),
+                control_border: Vector4::new(0.55, 0.55, 0.6, 1.0),
+                accent: Vector4::new(0.2, 0.5, 0.9, 1.0),
+            },
+            typography: Typography {
+                title_size: 16.0,
+                body_size: 16.0,
+            },
+            radii: Radii {
+                window: 3.0,
+                control: 2.0,
+            },
+            window_frame_width: 5.0,
+            button_frame_width: 3.0,
+            title_height: 16.0,
+        }
+    }
+
+    pub fn dark() -> Self {
+        Theme {
+            palette: Palette {
+                desktop_background: Vector4::new(0.1, 0.12, 0.15, 1.0),
+                window_background: Vector4::new(0.2, 0.2, 0.2, 1.0),
+                window_title: Vector4::new(0.4, 0.7, 0.9, 1.0),
+                window_shadow: Vector4::new(0.0, 0.0, 0.0, 0.5),
+                surface: Vector4::new(0.3, 0.3, 0.3, 0.8),
+                text: Vector4::new(0.9, 0.9, 0.9, 1.0),
+                button_background: Vector4::new(0.3, 0.45, 0.55, 1.0),
+                button_hovered: Vector4::new(0.36, 0.53, 0.64, 1.0),
+                button_pressed: Vector4::new(0.21, 0.32, 0.39, 1.0),
+                button_disabled: Vector4::new(0.25, 0.25, 0.25, 1.0),
+                button_text: Vector4::new(0.9, 0.95, 1.0, 1.0),
+                disabled_text: Vector4::new(0.5, 0.5, 0.5, 1.0),
+                input_background: Vector4::new(0.12, 0.12, 0.12, 1.0),
+                input_text: Vector4::new(0.9, 0.9, 0.9, 1.0),
+                control_border: Vector4::new(0.5, 0.5, 0.55, 1.0),
+                accent: Vector4::new(0.4, 0.7, 0.9, 1.0),
+            },
+            ..Self::light()
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
@@ -0,0 +1,100 @@
+use glium::glutin::dpi::{LogicalPosition, PhysicalSize};
+
+use wm::headless::golden::{GoldenHarness, Tolerance};
+use wm::headless::Headless;
+
+const SIZE: PhysicalSize<u32> = PhysicalSize {
+    width: 640,
+    height: 420,
+};
+
+type Scene = (&'static str, fn(&mut Headless));
+
+/// Scenes scripted on a fresh desktop, each compared with `tests/golden/<name>.png`
+const SCENES: [Scene; 3] = [
+    ("default_desktop", default_desktop),
+    ("focused_input", focused_input),
+    ("open_menu", open_menu),
+];
+
+fn default_desktop(_: &mut Headless) {}
+
+fn focused_input(headless: &mut Headless) {
+    headless.click(LogicalPosition::new(140.0, 180.0));
+    headless.type_str("Hello, golden");
+}
+
+fn open_menu(headless: &mut Headless) {
+    headless.click(LogicalPosition::new(70.0, 85.0));
+    headless.move_cursor(LogicalPosition::new(90.0, 115.0));
+}
+
+fn harness() -> GoldenHarness {
+    let font =
+        rusttype::Font::try_from_bytes(include_bytes!("../resource/GenRyuMinJP-Regular.ttf"))
+            .expect("failed to load font");
+    GoldenHarness::new(
+        font,
+        SIZE,
+        1.0,
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden"),
+    )
+    .with_output_dir(concat!(env!("CARGO_TARGET_TMPDIR"), "/golden"))
+}
+
+fn check(name: &str) {
+    let (_, scene) = SCENES
+        .iter()
+        .find(|(scene, _)| *scene == name)
+        .expect("unknown scene");
+    if let Err(err) = harness().check(name, scene) {
+        panic!("{}: {}", name, err);
+    }
+}
+
+#[test]
+fn default_desktop_matches() {
+    check("default_desktop");
+}
+
+#[test]
+fn focused_input_matches() {
+    check("focused_input");
+}
+
+#[test]
+fn open_menu_matches() {
+    check("open_menu");
+}
+
+/// Runs the same scenes through the GL shaders on hidden windows. Needs a display and a
+/// monitor at scale factor 1.0, so it only runs when asked for with `--ignored`
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn scenes_match_with_gl() {
+    use glium::glutin::event_loop::EventLoop;
+    use glium::glutin::platform::unix::EventLoopExtUnix;
+    use glium::glutin::{window::WindowBuilder, ContextBuilder};
+    use glium::Display;
+    use wm::RenderContext;
+
+    // tests run off the main thread
+    let event_loop = EventLoop::<()>::new_any_thread();
+    // the GPU rasterizes edges and glyphs slightly differently from the software renderer
+    let harness = harness().with_tolerance(Tolerance {
+        channel: 8,
+        pixels: (SIZE.width * SIZE.height / 100) as usize,
+    });
+    for (name, scene) in SCENES.iter() {
+        let window = WindowBuilder::new()
+            .with_inner_size(SIZE)
+            .with_resizable(false)
+            .with_visible(false);
+        let display = Display::new(window, ContextBuilder::new(), &event_loop)
+            .expect("failed to create a GL window");
+        if let Err(err) = harness.check_with(name, RenderContext::new(display), scene) {
+            panic!("{} with GL: {}", name, err);
+        }
+    }
+}